@@ -0,0 +1,147 @@
+use crate::settings::Settings;
+use std::time::{Duration, Instant};
+
+/// Recovery margin: FPS must climb 10% above the alert threshold before the alert clears
+const FPS_RECOVERY_FACTOR: f64 = 1.1;
+/// Recovery margin for CPU/GPU usage, in percentage points
+const USAGE_RECOVERY_MARGIN: f32 = 5.0;
+/// Recovery margin of the FPS band: a bound is crossed back only 5% past it
+const FPS_BAND_MARGIN: f64 = 0.05;
+/// How long the FPS must stay under `alert_below_fps` before the low-FPS alarm fires
+const LOW_FPS_SUSTAIN: Duration = Duration::from_secs(3);
+
+/// Decides when the overlay is visible in alert-only mode.
+/// Alerts enter on the raw threshold, clear only past a recovery margin (hysteresis)
+/// and keep the overlay up for `alert_linger_ms` afterwards so it doesn't flicker.
+#[derive(Default)]
+pub struct AlertGate {
+    fps_alert: bool,
+    usage_alert: bool,
+    stutter_alert: bool,
+    visible_until: Option<Instant>,
+}
+
+impl AlertGate {
+    /// Feed the latest readings, returns true while the overlay should be shown
+    pub fn update(&mut self, fps: f64, stutters: u32, cpu: f32, gpu: f32, settings: &Settings) -> bool {
+        let fps_limit = settings.alert_only_fps_below as f64;
+        if fps_limit > 0.0 && fps > 0.0 {
+            self.fps_alert = if self.fps_alert {
+                fps < fps_limit * FPS_RECOVERY_FACTOR
+            } else {
+                fps < fps_limit
+            };
+        } else {
+            // No frames (loading/paused) is not an alert condition
+            self.fps_alert = false;
+        }
+
+        let usage_limit = settings.alert_only_usage_above as f32;
+        let usage = cpu.max(gpu);
+        if usage_limit > 0.0 {
+            self.usage_alert = if self.usage_alert {
+                usage >= usage_limit - USAGE_RECOVERY_MARGIN
+            } else {
+                usage >= usage_limit
+            };
+        } else {
+            self.usage_alert = false;
+        }
+
+        // Stutters come and go with the window, so this one recovers when they're all gone
+        let stutter_limit = settings.alert_only_stutters;
+        self.stutter_alert = stutter_limit > 0
+            && if self.stutter_alert {
+                stutters > 0
+            } else {
+                stutters >= stutter_limit
+            };
+
+        let now = Instant::now();
+        if self.fps_alert || self.usage_alert || self.stutter_alert {
+            self.visible_until = Some(now + Duration::from_millis(settings.alert_linger_ms as u64));
+            return true;
+        }
+
+        self.visible_until.is_some_and(|until| now < until)
+    }
+}
+
+/// Decides whether the FPS is inside the `show_below_fps`/`hide_above_fps` band.
+/// Each bound is crossed on the raw value and crossed back only past FPS_BAND_MARGIN,
+/// so a reading hovering at a bound doesn't make the overlay flicker.
+#[derive(Default)]
+pub struct FpsBandGate {
+    above_hide: bool,
+    below_show: bool,
+}
+
+impl FpsBandGate {
+    /// Feed the latest FPS, returns true while the overlay should be shown
+    pub fn update(&mut self, fps: f64, settings: &Settings) -> bool {
+        let hide_above = settings.hide_above_fps as f64;
+        let show_below = settings.show_below_fps as f64;
+        if hide_above <= 0.0 && show_below <= 0.0 {
+            return true;
+        }
+        // No frames: nothing is presenting, so there's no FPS to be in the band
+        if fps <= 0.0 {
+            *self = Self::default();
+            return false;
+        }
+
+        self.above_hide = hide_above > 0.0
+            && if self.above_hide {
+                fps > hide_above * (1.0 - FPS_BAND_MARGIN)
+            } else {
+                fps > hide_above
+            };
+        self.below_show = show_below <= 0.0
+            || if self.below_show {
+                fps < show_below * (1.0 + FPS_BAND_MARGIN)
+            } else {
+                fps < show_below
+            };
+
+        !self.above_hide && self.below_show
+    }
+}
+
+/// Decides when to fire the low-FPS alarm (border flash, optional beep).
+/// Unlike AlertGate it's a one-shot notification: the FPS must stay under
+/// `alert_below_fps` for LOW_FPS_SUSTAIN, and it fires at most once per `alert_repeat_secs`.
+#[derive(Default)]
+pub struct LowFpsAlarm {
+    below_since: Option<Instant>,
+    last_fired: Option<Instant>,
+}
+
+impl LowFpsAlarm {
+    /// Feed the latest FPS, returns true when the alarm should fire now
+    pub fn update(&mut self, fps: f64, settings: &Settings) -> bool {
+        let limit = match settings.alert_below_fps {
+            Some(limit) if limit > 0 => limit as f64,
+            _ => {
+                self.below_since = None;
+                return false;
+            }
+        };
+        // No frames (loading/paused) is not a performance drop
+        if fps <= 0.0 || fps >= limit {
+            self.below_since = None;
+            return false;
+        }
+
+        let now = Instant::now();
+        let since = *self.below_since.get_or_insert(now);
+        if now.duration_since(since) < LOW_FPS_SUSTAIN {
+            return false;
+        }
+        let repeat = Duration::from_secs(settings.alert_repeat_secs as u64);
+        if self.last_fired.is_some_and(|fired| now.duration_since(fired) < repeat) {
+            return false;
+        }
+        self.last_fired = Some(now);
+        true
+    }
+}
@@ -0,0 +1,161 @@
+//! Command-line flags. Setting overrides apply to this session only, settings.json is left as is.
+//!
+//! `--no-tray`, `--position top-right|top-left|bottom-right|bottom-left`,
+//! `--size small|medium|large`, `--color white|green|#RRGGBB`, `--show-cpu`, `--show-gpu`,
+//! `--benchmark <csv path>`
+
+use crate::settings::{FpsColor, OverlayPosition, OverlaySize, Settings};
+use parking_lot::Mutex;
+use std::path::PathBuf;
+
+/// Flags that aren't settings
+#[derive(Debug, Default)]
+pub struct Args {
+    /// Overlay-only mode, settings and exit are reached through hotkeys
+    pub no_tray: bool,
+    /// Start a benchmark right away, logging its frames here
+    pub benchmark: Option<PathBuf>,
+    /// Started by the "relaunch as administrator" prompt, which isn't offered again
+    pub relaunched: bool,
+}
+
+/// Settings set by flags. They're laid over settings.json whenever it's (re)loaded and
+/// kept out of what's saved to it.
+#[derive(Debug, Clone, Copy)]
+struct Overrides {
+    show_cpu: bool,
+    show_gpu: bool,
+    position: Option<OverlayPosition>,
+    size: Option<OverlaySize>,
+    fps_color: Option<FpsColor>,
+}
+
+// The session's overrides, set once by `parse`
+static OVERRIDES: Mutex<Overrides> = Mutex::new(Overrides {
+    show_cpu: false,
+    show_gpu: false,
+    position: None,
+    size: None,
+    fps_color: None,
+});
+
+/// Parse the flags in `args` (program name excluded), recording the setting overrides for
+/// `apply`. Unknown flags are logged and skipped, a missing or invalid value is an error.
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut overrides = *OVERRIDES.lock();
+    let mut args = args.into_iter();
+
+    while let Some(flag) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("{} needs a value", flag));
+        match flag.as_str() {
+            "--no-tray" => parsed.no_tray = true,
+            "--show-cpu" => overrides.show_cpu = true,
+            "--show-gpu" => overrides.show_gpu = true,
+            "--position" => overrides.position = Some(parse_position(&value()?)?),
+            "--size" => overrides.size = Some(parse_size(&value()?)?),
+            "--color" => overrides.fps_color = Some(parse_color(&value()?)?),
+            "--benchmark" => parsed.benchmark = Some(PathBuf::from(value()?)),
+            crate::elevation::RELAUNCHED_FLAG => parsed.relaunched = true,
+            _ => crate::log::warn(&format!("Ignoring unknown command-line flag: {}", flag)),
+        }
+    }
+
+    *OVERRIDES.lock() = overrides;
+    Ok(parsed)
+}
+
+/// Lay the command-line overrides over settings loaded from settings.json
+pub fn apply(settings: &mut Settings) {
+    let overrides = *OVERRIDES.lock();
+    settings.show_cpu_usage |= overrides.show_cpu;
+    settings.show_gpu_usage |= overrides.show_gpu;
+    if let Some(position) = overrides.position {
+        settings.position = position;
+    }
+    if let Some(size) = overrides.size {
+        settings.size = size;
+    }
+    if let Some(color) = overrides.fps_color {
+        settings.fps_color = color;
+    }
+}
+
+/// Put `on_disk`'s values back into `settings` where they still hold an override, so saving
+/// doesn't persist it. A setting changed away from its override keeps the new value, and
+/// the override is dropped for the rest of the session.
+pub fn restore_saved(settings: &mut Settings, on_disk: &Settings) {
+    let mut overrides = OVERRIDES.lock();
+    if overrides.show_cpu {
+        if settings.show_cpu_usage {
+            settings.show_cpu_usage = on_disk.show_cpu_usage;
+        } else {
+            overrides.show_cpu = false;
+        }
+    }
+    if overrides.show_gpu {
+        if settings.show_gpu_usage {
+            settings.show_gpu_usage = on_disk.show_gpu_usage;
+        } else {
+            overrides.show_gpu = false;
+        }
+    }
+    if let Some(position) = overrides.position {
+        if settings.position == position {
+            settings.position = on_disk.position;
+        } else {
+            overrides.position = None;
+        }
+    }
+    if let Some(size) = overrides.size {
+        if settings.size == size {
+            settings.size = on_disk.size;
+        } else {
+            overrides.size = None;
+        }
+    }
+    if let Some(color) = overrides.fps_color {
+        if settings.fps_color == color {
+            settings.fps_color = on_disk.fps_color;
+        } else {
+            overrides.fps_color = None;
+        }
+    }
+}
+
+/// Drop the `--position` override, the overlay was moved by hand
+pub fn clear_position() {
+    OVERRIDES.lock().position = None;
+}
+
+fn parse_position(value: &str) -> Result<OverlayPosition, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "top-right" => Ok(OverlayPosition::TopRight),
+        "top-left" => Ok(OverlayPosition::TopLeft),
+        "bottom-right" => Ok(OverlayPosition::BottomRight),
+        "bottom-left" => Ok(OverlayPosition::BottomLeft),
+        _ => Err(format!("Invalid --position \"{}\" (top-right, top-left, bottom-right, bottom-left)", value)),
+    }
+}
+
+fn parse_size(value: &str) -> Result<OverlaySize, String> {
+    match value.to_ascii_lowercase().as_str() {
+        "small" => Ok(OverlaySize::Small),
+        "medium" => Ok(OverlaySize::Medium),
+        "large" => Ok(OverlaySize::Large),
+        _ => Err(format!("Invalid --size \"{}\" (small, medium, large)", value)),
+    }
+}
+
+fn parse_color(value: &str) -> Result<FpsColor, String> {
+    let invalid = || format!("Invalid --color \"{}\" (white, green, #RRGGBB)", value);
+    match value.to_ascii_lowercase().as_str() {
+        "white" => Ok(FpsColor::White),
+        "green" => Ok(FpsColor::Green),
+        hex => {
+            let hex = hex.strip_prefix('#').filter(|h| h.len() == 6).ok_or_else(invalid)?;
+            let rgb = u32::from_str_radix(hex, 16).map_err(|_| invalid())?;
+            Ok(FpsColor::Custom { r: (rgb >> 16) as u8, g: (rgb >> 8) as u8, b: rgb as u8 })
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use crate::i18n::{tr, Text};
+use crate::settings::CsvColumn;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+/// One row of the 1Hz system-stat log
+pub struct StatsRow<'a> {
+    pub fps: f64,
+    pub one_percent_low: f64,
+    pub cpu: f32,
+    pub gpu: f32,
+    pub ram: f32,
+    pub game: Option<&'a str>,
+}
+
+/// Writes one CSV row per second with the user-selected columns.
+/// Kept separate from per-frame frametime logging: different cadence, different file.
+#[derive(Default)]
+pub struct StatsLogger {
+    writer: Option<BufWriter<File>>,
+    columns: Vec<CsvColumn>,
+}
+
+impl StatsLogger {
+    fn new_log_path() -> Option<PathBuf> {
+        let mut path = dirs::data_local_dir()?;
+        path.push("EasyFPS");
+        path.push("logs");
+        std::fs::create_dir_all(&path).ok()?;
+        path.push(format!("stats_{}.csv", unix_time().as_secs()));
+        Some(path)
+    }
+
+    /// Append a row. A new file (with header) is started on first use or when the columns change.
+    pub fn log(&mut self, columns: &[CsvColumn], row: &StatsRow) {
+        if columns.is_empty() {
+            return;
+        }
+        if self.writer.is_none() || self.columns != columns {
+            self.close();
+            let Some(path) = Self::new_log_path() else { return };
+            let Ok(file) = File::create(&path) else { return };
+            let mut writer = BufWriter::new(file);
+            let header: Vec<&str> = columns.iter().map(|c| c.header()).collect();
+            let _ = writeln!(writer, "{}", header.join(","));
+            crate::notify::notify(tr(Text::CsvLogStarted), &path.display().to_string());
+            self.writer = Some(writer);
+            self.columns = columns.to_vec();
+        }
+
+        let fields: Vec<String> = columns
+            .iter()
+            .map(|column| match column {
+                CsvColumn::Timestamp => format!("{:.3}", unix_time().as_secs_f64()),
+                CsvColumn::Fps => format!("{:.1}", row.fps),
+                CsvColumn::OnePercentLow => format!("{:.1}", row.one_percent_low),
+                CsvColumn::Cpu => format!("{:.1}", row.cpu),
+                CsvColumn::Gpu => format!("{:.1}", row.gpu),
+                CsvColumn::Ram => format!("{:.1}", row.ram),
+                // Process names can't contain quotes, but may contain commas
+                CsvColumn::Game => format!("\"{}\"", row.game.unwrap_or("")),
+            })
+            .collect();
+
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writeln!(writer, "{}", fields.join(","));
+            let _ = writer.flush();
+        }
+    }
+
+    /// Close the current file; the next `log` starts a new one
+    pub fn close(&mut self) {
+        if let Some(mut writer) = self.writer.take() {
+            let _ = writer.flush();
+        }
+    }
+}
+
+/// Where a new benchmark run is logged: Documents\EasyFPS\bench_<timestamp>.csv
+pub fn benchmark_path() -> PathBuf {
+    let mut path = dirs::document_dir().unwrap_or_else(std::env::temp_dir);
+    path.push("EasyFPS");
+    path.push(format!("bench_{}.csv", unix_time().as_secs()));
+    path
+}
+
+fn unix_time() -> std::time::Duration {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+}
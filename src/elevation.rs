@@ -0,0 +1,97 @@
+//! Administrator rights. PresentMon reads frame events through ETW, which normally needs
+//! an elevated process, so a limited start offers to relaunch EasyFPS as administrator.
+
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{CloseHandle, HANDLE, HWND};
+use windows::Win32::Security::{GetTokenInformation, TokenElevation, TOKEN_ELEVATION, TOKEN_QUERY};
+use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+use windows::Win32::UI::Shell::ShellExecuteW;
+use windows::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+/// Passed to the elevated copy, so it never offers the relaunch again
+pub const RELAUNCHED_FLAG: &str = "--elevated";
+
+/// Whether this process runs with an elevated token
+pub fn is_elevated() -> bool {
+    unsafe {
+        let mut token = HANDLE::default();
+        if OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).is_err() {
+            return false;
+        }
+        let mut elevation = TOKEN_ELEVATION::default();
+        let mut returned = 0u32;
+        let queried = GetTokenInformation(
+            token,
+            TokenElevation,
+            Some(&mut elevation as *mut _ as *mut _),
+            std::mem::size_of::<TOKEN_ELEVATION>() as u32,
+            &mut returned,
+        );
+        let _ = CloseHandle(token);
+        queried.is_ok() && elevation.TokenIsElevated != 0
+    }
+}
+
+/// Start this executable again as administrator with the same arguments (plus
+/// `RELAUNCHED_FLAG`). Returns false when the UAC prompt was declined or the start failed.
+pub fn relaunch_as_admin() -> bool {
+    let Ok(exe) = std::env::current_exe() else {
+        return false;
+    };
+    let params = std::env::args()
+        .skip(1)
+        .chain(std::iter::once(RELAUNCHED_FLAG.to_string()))
+        .map(|arg| quote_arg(&arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let exe_wide: Vec<u16> = exe.as_os_str().to_string_lossy().encode_utf16().chain(std::iter::once(0)).collect();
+    let params_wide: Vec<u16> = params.encode_utf16().chain(std::iter::once(0)).collect();
+    let result = unsafe {
+        ShellExecuteW(
+            HWND(0),
+            w!("runas"),
+            PCWSTR(exe_wide.as_ptr()),
+            PCWSTR(params_wide.as_ptr()),
+            PCWSTR::null(),
+            SW_SHOWNORMAL,
+        )
+    };
+    // Values above 32 mean success
+    if result.0 > 32 {
+        true
+    } else {
+        crate::log::error(&format!("Relaunch as administrator failed (code {})", result.0));
+        false
+    }
+}
+
+/// Quote an argument for the Windows command line, when it needs it
+fn quote_arg(arg: &str) -> String {
+    if !arg.is_empty() && !arg.contains([' ', '\t', '"']) {
+        return arg.to_string();
+    }
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are escaped, then the quote itself
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                backslashes = 0;
+            }
+        }
+        if c != '\\' {
+            quoted.push(c);
+        }
+    }
+    // Trailing backslashes are doubled so they don't escape the closing quote
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
@@ -1,101 +1,1307 @@
 use std::collections::VecDeque;
-use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, AtomicUsize, Ordering};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-use std::io::{Write, BufRead, BufReader};
+use std::io::{Write, BufRead, BufReader, Read};
 use std::process::{Command, Stdio, Child};
 use parking_lot::Mutex;
+use crate::i18n::{tr, Text};
 
-// --- LOGGING ---
-fn log_debug(msg: &str) {
-    if let Some(mut path) = dirs::data_local_dir() {
-        path.push("EasyFPS");
-        let _ = std::fs::create_dir_all(&path);
-        path.push("debug.log");
-        if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
-            let _ = writeln!(file, "[{}] {}", chrono_lite(), msg);
-        }
-    }
-}
+// --- STRUTTURE DATI ---
+// Rolling buffer size until configure() applies the user setting
+const DEFAULT_SAMPLES: usize = 2000;
+// Upper bound for a benchmark run (~16 MB, over 1.5 hours at 360 FPS)
+const BENCHMARK_MAX_SAMPLES: usize = 2_000_000;
+// Raw PresentMon log size before it's rotated to presentmon_raw.old.csv
+const MAX_RAW_LOG_BYTES: u64 = 50 * 1024 * 1024;
+// How often the raw PresentMon log is flushed, so a crash or kill loses at most this much
+const RAW_LOG_FLUSH: Duration = Duration::from_secs(1);
+// PresentMon restarts allowed per target when no frames arrive (avoids a restart loop)
+const MAX_WATCHDOG_RESTARTS: u32 = 1;
+// How often the liveness thread checks that PresentMon is still running
+const LIVENESS_POLL: Duration = Duration::from_secs(1);
+// Wait before relaunching a dead PresentMon, multiplied by the recent crash count
+const RESTART_BACKOFF: Duration = Duration::from_secs(1);
+// PresentMon crashes tolerated within CRASH_WINDOW before we give up on the target
+const MAX_CRASH_RESTARTS: usize = 3;
+const CRASH_WINDOW: Duration = Duration::from_secs(10);
+// A frame longer than this multiple of the median frametime counts as a stutter
+const STUTTER_FACTOR: f64 = 2.0;
+// Most buckets `histogram` returns, the last one collects the slower frames
+const HISTOGRAM_MAX_BUCKETS: usize = 500;
+// Frames needed before the 1%/0.1% lows mean anything; below this they report the average
+const MIN_LOW_SAMPLES: usize = 200;
+// PresentMon must print its CSV header this soon after starting, or it couldn't attach
+const HEADER_TIMEOUT: Duration = Duration::from_secs(5);
+// Unparsed PresentMon lines written to the log per session, to show the format without spamming
+const MAX_LOGGED_BAD_LINES: u64 = 5;
+// Interval the smoothing alpha is defined over: one step per 60 Hz overlay tick, so alpha
+// means the same whatever overlay_update_hz is
+const EMA_STEP: Duration = Duration::from_micros(16_667);
 
-fn chrono_lite() -> String {
-    let now = std::time::SystemTime::now();
-    let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
-    format!("{}", dur.as_secs())
+/// Why PresentMon could not be started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CaptureError {
+    /// No PresentMon executable found and the embedded copy could not be extracted
+    NotFound,
+    /// The executable was written but then removed or locked (antivirus/SmartScreen quarantine)
+    BlockedByAntivirus(std::path::PathBuf),
+    /// Windows refused to start PresentMon without elevation
+    NeedsAdmin,
+    /// No PresentMon next to the executable and the embedded copy couldn't be written
+    /// or doesn't run where it was written (usually blocked by the antivirus)
+    ExtractFailed(String),
+    Other(String),
 }
 
-// --- STRUTTURE DATI ---
-const MAX_SAMPLES: usize = 2000;
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::NotFound => write!(f, "{}", tr(Text::PresentMonNotFound)),
+            CaptureError::BlockedByAntivirus(path) => write!(
+                f,
+                "{}\n{} {}",
+                tr(Text::PresentMonBlocked),
+                tr(Text::AddExclusionFor),
+                path.display()
+            ),
+            CaptureError::NeedsAdmin => write!(f, "{}", tr(Text::PresentMonNeedsAdmin)),
+            CaptureError::ExtractFailed(msg) => write!(
+                f,
+                "{}: {}.\n{} {}",
+                tr(Text::PresentMonExtractFailed),
+                msg,
+                tr(Text::PresentMonExtractHint),
+                std::env::temp_dir().join("EasyFPS").display()
+            ),
+            CaptureError::Other(msg) => write!(f, "{}: {}", tr(Text::PresentMonStartFailed), msg),
+        }
+    }
+}
 
 #[derive(Debug, Clone, Default)]
 pub struct FpsData {
     pub fps: f64,
+    /// FPS from the median of the last INSTANT_FRAMES frametimes: reacts at once, unlike
+    /// the windowed `fps`, without jumping on a single slow frame
+    pub fps_instant: f64,
     pub one_percent_low: f64,
+    pub point_one_percent_low: f64,
+    /// Detected frame cap and how consistently it's hit (experimental)
+    pub cap: Option<FrameCap>,
+    /// The framerate is held by a limiter or VSync (at `cap`), see `is_capped`
+    pub is_capped: bool,
+    /// Frames actually displayed per second (None when PresentMon doesn't report display changes)
+    pub display_fps: Option<f64>,
+    /// Average time the GPU spent on each frame over the averaging window (ms), and that time
+    /// as a share of the frametime. Near 100% the game is GPU-bound, well under it CPU-bound.
+    /// None when this PresentMon build doesn't report GPU busy time.
+    pub gpu_busy_ms: Option<f64>,
+    pub gpu_busy_pct: Option<f64>,
+    /// No frames arrived for `detect_stall_ms` (game paused), the other fields are zero
+    pub stalled: bool,
+    /// PresentMon runs but delivers no frame data (no admin rights, ETW session conflict),
+    /// the other fields are zero
+    pub capture_failed: bool,
+    /// Average frametime over the averaging window (ms), not smoothed
+    pub frametime_ms: f64,
+    /// Frame pacing jitter over the averaging window (ms)
+    pub jitter_ms: f64,
+    /// Frames in the averaging window that took over STUTTER_FACTOR times the median frametime
+    pub stutter_count: u32,
+    /// FPS of the slowest and fastest frame in the averaging window
+    pub window_min_fps: f64,
+    pub window_max_fps: f64,
+    /// Lowest and highest displayed FPS since the last `reset_stats` (0 before any frame)
+    pub min_fps: f64,
+    pub max_fps: f64,
+    /// Average FPS over every frame since the last `reset_stats`
+    pub avg_fps_session: f64,
+}
+
+/// Running session stats, cleared by `reset_stats`
+#[derive(Debug, Default)]
+struct SessionStats {
+    frames: u64,
+    total_ms: f64,
+    min_fps: Option<f64>,
+    max_fps: f64,
 }
 
-// Stato globale condiviso
+/// A frame cap inferred from the frametime distribution
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameCap {
+    pub fps: u32,
+    /// True when nearly every recent frame lands on the cap interval
+    pub stable: bool,
+}
+
+/// Capture options taken from the user settings
+#[derive(Debug, Clone, Default)]
+struct CaptureConfig {
+    /// EMA smoothing factor for the displayed FPS (None = raw window average)
+    ema_alpha: Option<f64>,
+    /// Averaging window in ms (0 = every buffered sample)
+    avg_window_ms: u32,
+    /// A gap without frames longer than this counts as a stall (None = disabled)
+    stall_after: Option<Duration>,
+    /// Tee PresentMon's stdout to presentmon_raw.csv
+    save_raw_presentmon: bool,
+    /// Restart PresentMon if no frame arrives this long after the target is set (None = disabled)
+    watchdog_after: Option<Duration>,
+    /// PresentMon.exe chosen by the user (None = normal search)
+    presentmon_path: Option<std::path::PathBuf>,
+    /// Definition of the 1%/0.1% lows reported to the overlay
+    low_method: crate::settings::LowMethod,
+    /// Frametime average or frames counted over the last second
+    fps_method: crate::settings::FpsMethod,
+}
+
+// Stato di una sessione di cattura
 struct FpsCaptureState {
     target_process_id: AtomicU32,
     ms_samples: Mutex<VecDeque<f64>>, // MsBetweenPresents
+    display_samples: Mutex<VecDeque<f64>>, // MsBetweenDisplayChange, parallel to ms_samples (0 = not displayed)
+    gpu_busy_samples: Mutex<VecDeque<f64>>, // MsGPUActive/GPUBusy, parallel to ms_samples
+    frame_times: Mutex<VecDeque<f64>>, // Present time (s), parallel to ms_samples: TimeInSeconds, or arrival since `epoch`
+    epoch: Instant, // Clock origin of frame_times when PresentMon has no TimeInSeconds column
+    sample_capacity: AtomicUsize, // Max length of ms_samples/display_samples/gpu_busy_samples
     running_process: Mutex<Option<Child>>,
     is_running: AtomicBool,
+    watcher_generation: AtomicU32, // Bumped by init, so a liveness thread from before a shutdown exits
+    last_error: Mutex<Option<CaptureError>>,
+    config: Mutex<CaptureConfig>,
+    ema_fps: Mutex<Option<(f64, Instant)>>, // Running EMA of the displayed FPS, and when it was updated
+    last_sample_at: Mutex<Option<Instant>>, // When the reader last received a frame
+    target_set_at: Mutex<Option<Instant>>, // When PresentMon was (re)started for the target
+    watchdog_restarts: AtomicU32, // Watchdog restarts for the current target
+    parsed_lines: AtomicU64,  // Data lines parsed since PresentMon started
+    dropped_lines: AtomicU64, // Data lines that couldn't be parsed
+    header_found: AtomicBool, // PresentMon printed its CSV header since it started
+    attach_failed: AtomicBool, // PresentMon ended or timed out without a header
+    presentmon_runs: AtomicU32, // Bumped at each PresentMon start, so a stale reader thread keeps quiet
+    // Benchmark run: every sample is kept (no rolling window) until the run stops
+    benchmark_samples: Mutex<Option<Vec<f64>>>,
+    // Per-frame CSV of the benchmark run (None when the file couldn't be created)
+    benchmark_csv: Mutex<Option<std::io::BufWriter<std::fs::File>>>,
+    session: Mutex<SessionStats>,
+    presentmon_path: Mutex<Option<std::path::PathBuf>>, // Resolved PresentMon.exe, used at the next start
+    session_name: String, // ETW session name of this capture's PresentMon, unique per Capture
 }
 
-static STATE: once_cell::sync::Lazy<Arc<FpsCaptureState>> = once_cell::sync::Lazy::new(|| {
-    Arc::new(FpsCaptureState {
-        target_process_id: AtomicU32::new(0),
-        ms_samples: Mutex::new(VecDeque::with_capacity(MAX_SAMPLES)),
-        running_process: Mutex::new(None),
-        is_running: AtomicBool::new(false),
-    })
-});
+// A dropped session must not leave PresentMon running
+impl Drop for FpsCaptureState {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.running_process.get_mut().take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
 
-// --- API PUBBLICHE ---
+/// A PresentMon capture session: its own PresentMon process, samples and settings.
+/// Clones share the session, so one can be handed to another thread.
+#[derive(Clone)]
+pub struct Capture {
+    state: Arc<FpsCaptureState>,
+}
 
-pub fn init() -> Result<(), String> {
-    if STATE.is_running.load(Ordering::SeqCst) {
-        return Ok(());
+impl Default for Capture {
+    fn default() -> Self {
+        Self::new()
     }
-    STATE.is_running.store(true, Ordering::SeqCst);
-    log_debug("FPS capture init (PresentMon Mode)");
+}
+
+// Numbers the PresentMon sessions of the Capture instances. Deterministic, so the first
+// capture of a new run finds (and stops) the session a crashed run left with its name.
+static NEXT_SESSION: AtomicU32 = AtomicU32::new(0);
+
+impl Capture {
+    pub fn new() -> Self {
+        Self {
+            state: Arc::new(FpsCaptureState {
+                target_process_id: AtomicU32::new(0),
+                ms_samples: Mutex::new(VecDeque::with_capacity(DEFAULT_SAMPLES)),
+                display_samples: Mutex::new(VecDeque::with_capacity(DEFAULT_SAMPLES)),
+                gpu_busy_samples: Mutex::new(VecDeque::with_capacity(DEFAULT_SAMPLES)),
+                frame_times: Mutex::new(VecDeque::with_capacity(DEFAULT_SAMPLES)),
+                epoch: Instant::now(),
+                sample_capacity: AtomicUsize::new(DEFAULT_SAMPLES),
+                running_process: Mutex::new(None),
+                is_running: AtomicBool::new(false),
+                watcher_generation: AtomicU32::new(0),
+                last_error: Mutex::new(None),
+                config: Mutex::new(CaptureConfig::default()),
+                ema_fps: Mutex::new(None),
+                last_sample_at: Mutex::new(None),
+                target_set_at: Mutex::new(None),
+                watchdog_restarts: AtomicU32::new(0),
+                parsed_lines: AtomicU64::new(0),
+                dropped_lines: AtomicU64::new(0),
+                header_found: AtomicBool::new(false),
+                attach_failed: AtomicBool::new(false),
+                presentmon_runs: AtomicU32::new(0),
+                benchmark_samples: Mutex::new(None),
+                benchmark_csv: Mutex::new(None),
+                session: Mutex::new(SessionStats::default()),
+                presentmon_path: Mutex::new(None),
+                session_name: format!("EasyFPS_{}", NEXT_SESSION.fetch_add(1, Ordering::SeqCst)),
+            }),
+        }
+    }
+
+    pub fn init(&self, custom_path: Option<&std::path::Path>) -> Result<(), CaptureError> {
+        if self.state.is_running.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        self.state.is_running.store(true, Ordering::SeqCst);
+        crate::log::info("FPS capture init (PresentMon Mode)");
+        self.state.config.lock().presentmon_path = custom_path.map(|p| p.to_path_buf());
     
-    // Cerca PresentMon.exe in varie posizioni
-    if let Some(path) = detect_presentmon_path() {
-        log_debug(&format!("PresentMon found at: {:?}", path));
-        // Salviamo il percorso trovato nello stato o usiamo una variabile globale/local statica se necessario
-        // Per semplicità, start_presentmon userà la stessa logica o salviamo il path in una static
-        let mut path_guard = PRESENTMON_PATH.lock();
+        // Cerca PresentMon.exe in varie posizioni
+        let found = detect_presentmon_path(custom_path)
+            .map_err(|e| {
+                crate::log::error(&format!("No usable PresentMon.exe: {}", e));
+                e
+            })
+            .and_then(|path| {
+                crate::log::info(&format!("PresentMon found at: {:?}", path));
+                match verify_presentmon(&path) {
+                    Ok(()) => Ok(path),
+                    Err(e) => {
+                        crate::log::error(&format!("PresentMon at {:?} is not usable: {}", path, e));
+                        Err(e)
+                    }
+                }
+            });
+        // Not running after a failure, so the next init (resume, idle restart) detects again
+        let path = match found {
+            Ok(path) => path,
+            Err(e) => {
+                self.state.is_running.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
+        // Il percorso trovato resta nello stato, start_presentmon lo usa ad ogni avvio
+        let mut path_guard = self.state.presentmon_path.lock();
         *path_guard = Some(path);
+        let generation = self.state.watcher_generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let capture = self.clone();
+        std::thread::spawn(move || capture.watch_presentmon(generation));
         Ok(())
-    } else {
-        log_debug("PresentMon.exe not found in CWD or executable dir!");
-        Err("PresentMon.exe non trovato. Assicurati che sia nella stessa cartella dell'eseguibile (o nella root del progetto).".to_string())
+    }
+
+    /// Line counters of the running PresentMon session (debug API)
+    pub fn line_stats(&self) -> LineStats {
+        LineStats {
+            parsed: self.state.parsed_lines.load(Ordering::Relaxed),
+            dropped: self.state.dropped_lines.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Returns (and clears) the last error reported while starting PresentMon
+    pub fn take_error(&self) -> Option<CaptureError> {
+        self.state.last_error.lock().take()
+    }
+
+    pub fn shutdown(&self) {
+        crate::log::info("Shutdown requested");
+        self.state.is_running.store(false, Ordering::SeqCst);
+        self.state.target_process_id.store(0, Ordering::SeqCst);
+        self.stop_presentmon();
+    }
+
+    /// Start the session min/max/average over
+    pub fn reset_stats(&self) {
+        crate::log::info("Session stats reset");
+        *self.state.session.lock() = SessionStats::default();
+    }
+
+    /// Apply the capture-related user settings
+    pub fn configure(&self, settings: &crate::settings::Settings) {
+        let mut config = self.state.config.lock();
+        config.ema_alpha = match settings.smoothing {
+            crate::settings::Smoothing::Ema { alpha } => Some(alpha.clamp(0.05, 0.5) as f64),
+            crate::settings::Smoothing::None => None,
+        };
+        config.avg_window_ms = settings.avg_window_ms;
+        config.low_method = settings.low_method;
+        config.fps_method = settings.fps_method;
+        config.save_raw_presentmon = settings.save_raw_presentmon;
+        config.watchdog_after = match settings.capture_watchdog_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms as u64)),
+        };
+        config.stall_after = match settings.detect_stall_ms {
+            0 => None,
+            ms => Some(Duration::from_millis(ms as u64)),
+        };
+        self.set_sample_capacity(settings.sample_buffer_size);
+        if config.presentmon_path != settings.presentmon_path {
+            config.presentmon_path = settings.presentmon_path.clone();
+            // Picked up the next time PresentMon starts
+            if let Ok(path) = detect_presentmon_path(config.presentmon_path.as_deref()) {
+                crate::log::info(&format!("PresentMon path changed to: {:?}", path));
+                *self.state.presentmon_path.lock() = Some(path);
+            }
+        }
+    }
+
+    pub fn set_target_process(&self, pid: u32) {
+        let old_pid = self.state.target_process_id.swap(pid, Ordering::SeqCst);
+        if old_pid != pid {
+            crate::log::info(&format!("Target PID changed to: {}", pid));
+            *self.state.ema_fps.lock() = None;
+            self.state.watchdog_restarts.store(0, Ordering::SeqCst);
+            self.start_presentmon(pid);
+        }
+    }
+
+    /// PresentMon sometimes fails to attach (transient ETW hiccup, game not rendering yet).
+    /// If nothing arrived within the timeout and the process is alive and accessible,
+    /// restart it, at most MAX_WATCHDOG_RESTARTS times per target.
+    fn check_watchdog(&self, pid: u32, timeout: Duration) {
+        if pid == 0 || self.state.last_sample_at.lock().is_some() {
+            return;
+        }
+        let started = match *self.state.target_set_at.lock() {
+            Some(started) => started,
+            None => return,
+        };
+        if started.elapsed() < timeout {
+            return;
+        }
+
+        let restarts = self.state.watchdog_restarts.load(Ordering::SeqCst);
+        if restarts >= MAX_WATCHDOG_RESTARTS {
+            return;
+        }
+        // A process we can't open (exited or protected) won't do better after a restart
+        if crate::fullscreen::get_process_name(pid).is_none() {
+            return;
+        }
+
+        self.state.watchdog_restarts.store(restarts + 1, Ordering::SeqCst);
+        crate::log::warn(&format!(
+            "Watchdog: no frames from PID {} after {} ms, restarting PresentMon (attempt {}/{})",
+            pid,
+            timeout.as_millis(),
+            restarts + 1,
+            MAX_WATCHDOG_RESTARTS
+        ));
+        self.start_presentmon(pid);
+    }
+
+    /// Relaunch PresentMon when it dies on its own (crash, killed by anti-cheat).
+    /// Without this the reader just hits EOF and the overlay freezes on the last reading.
+    fn watch_presentmon(&self, generation: u32) {
+        let mut crashes: VecDeque<Instant> = VecDeque::new();
+        // Target we stopped restarting for after too many crashes
+        let mut given_up: Option<u32> = None;
+
+        while self.state.is_running.load(Ordering::SeqCst) {
+            std::thread::sleep(LIVENESS_POLL);
+            // Capture was stopped and started again meanwhile: the new thread takes over
+            if self.state.watcher_generation.load(Ordering::SeqCst) != generation {
+                return;
+            }
+
+            let exited = {
+                let mut proc = self.state.running_process.lock();
+                match proc.as_mut().map(|child| child.try_wait()) {
+                    Some(Ok(Some(status))) => {
+                        proc.take();
+                        Some(status)
+                    }
+                    _ => None,
+                }
+            };
+            let Some(status) = exited else { continue };
+
+            let pid = self.state.target_process_id.load(Ordering::SeqCst);
+            crate::log::warn(&format!("PresentMon exited unexpectedly ({}) while capturing PID {}", status, pid));
+            // Drop the stale samples so the overlay doesn't keep the last reading
+            self.stop_presentmon();
+            *self.state.ema_fps.lock() = None;
+
+            if pid == 0 || given_up == Some(pid) {
+                continue;
+            }
+            // The game itself is gone: nothing to restart for
+            if crate::fullscreen::get_process_name(pid).is_none() {
+                continue;
+            }
+
+            let now = Instant::now();
+            crashes.retain(|at| now.duration_since(*at) < CRASH_WINDOW);
+            if crashes.len() >= MAX_CRASH_RESTARTS {
+                crate::log::error(&format!(
+                    "PresentMon died {} times in {} s, not restarting it for PID {}",
+                    crashes.len(),
+                    CRASH_WINDOW.as_secs(),
+                    pid
+                ));
+                given_up = Some(pid);
+                crashes.clear();
+                continue;
+            }
+            crashes.push_back(now);
+
+            std::thread::sleep(RESTART_BACKOFF * crashes.len() as u32);
+            // Shutdown, a new target or a restart from elsewhere happened meanwhile
+            if !self.state.is_running.load(Ordering::SeqCst)
+                || self.state.target_process_id.load(Ordering::SeqCst) != pid
+                || self.state.running_process.lock().is_some()
+            {
+                continue;
+            }
+            crate::log::warn(&format!("Restarting PresentMon for PID {} (crash {}/{})", pid, crashes.len(), MAX_CRASH_RESTARTS));
+            self.start_presentmon(pid);
+        }
+    }
+
+    pub fn get_fps_for_process(&self, process_id: u32) -> Option<FpsData> {
+        // Assicurati che il processo target sia impostato
+        if self.state.target_process_id.load(Ordering::SeqCst) != process_id {
+            self.set_target_process(process_id);
+        }
+    
+        let config = self.state.config.lock().clone();
+
+        if let Some(timeout) = config.watchdog_after {
+            self.check_watchdog(process_id, timeout);
+        }
+
+        // PresentMon never got going: say so instead of a misleading 0 FPS
+        if self.capture_failed() {
+            return Some(FpsData { capture_failed: true, ..Default::default() });
+        }
+
+        // Paused game: don't keep showing the last (stale) numbers
+        if let (Some(stall_after), Some(last)) = (config.stall_after, *self.state.last_sample_at.lock()) {
+            if last.elapsed() >= stall_after {
+                return Some(FpsData { stalled: true, ..Default::default() });
+            }
+        }
+
+        let mut samples = self.state.ms_samples.lock();
+        let samples = samples.make_contiguous();
+        let windowed = window_slice(samples, config.avg_window_ms);
+        let stats = compute_stats(windowed);
+        // A short window may hold too few frames for the lows: widen it to MIN_LOW_SAMPLES
+        let low_window = windowed.len().max(MIN_LOW_SAMPLES).min(samples.len());
+        let low_stats = if low_window > windowed.len() {
+            compute_stats(&samples[samples.len() - low_window..])
+        } else {
+            stats.clone()
+        };
+        let recent = &samples[samples.len().saturating_sub(CAP_WINDOW)..];
+        let mut display = self.state.display_samples.lock();
+        let display = display.make_contiguous();
+        let display_fps = compute_display_fps(windowed, &display[display.len().saturating_sub(windowed.len())..]);
+        let mut gpu_busy = self.state.gpu_busy_samples.lock();
+        let gpu_busy = gpu_busy.make_contiguous();
+        let gpu_busy = compute_gpu_busy(windowed, &gpu_busy[gpu_busy.len().saturating_sub(windowed.len())..]);
+
+        let (one_percent_low, point_one_percent_low) = match config.low_method {
+            crate::settings::LowMethod::FrameCount => (low_stats.one_percent_low, low_stats.point_one_percent_low),
+            crate::settings::LowMethod::TimeWeighted => (low_stats.one_percent_low_time, low_stats.point_one_percent_low_time),
+        };
+
+        let raw_fps = match config.fps_method {
+            crate::settings::FpsMethod::FrameTimeAverage => stats.avg_fps,
+            crate::settings::FpsMethod::FramesPerSecondCount => self.frames_in_last_second(),
+        };
+
+        // Optional EMA smoothing of the displayed number (1% low stays raw)
+        let fps = match config.ema_alpha {
+            Some(alpha) if raw_fps > 0.0 => {
+                let mut ema = self.state.ema_fps.lock();
+                let now = Instant::now();
+                let smoothed = match *ema {
+                    Some((prev, at)) => ema_step(prev, raw_fps, alpha, now.duration_since(at)),
+                    None => raw_fps,
+                };
+                *ema = Some((smoothed, now));
+                smoothed
+            }
+            _ => raw_fps,
+        };
+
+        // Min/max follow the displayed value; stalls returned early above, so only real gameplay counts
+        let mut session = self.state.session.lock();
+        if fps > 0.0 {
+            session.min_fps = Some(session.min_fps.map_or(fps, |min| min.min(fps)));
+            session.max_fps = session.max_fps.max(fps);
+        }
+        let avg_fps_session = if session.total_ms > 0.0 {
+            session.frames as f64 * 1000.0 / session.total_ms
+        } else {
+            0.0
+        };
+
+        let cap = detect_cap(recent);
+        Some(FpsData {
+            fps,
+            fps_instant: instant_fps(samples),
+            one_percent_low,
+            point_one_percent_low,
+            window_min_fps: stats.min_fps,
+            window_max_fps: stats.max_fps,
+            min_fps: session.min_fps.unwrap_or(0.0),
+            max_fps: session.max_fps,
+            avg_fps_session,
+            is_capped: is_capped(recent, cap),
+            cap,
+            display_fps,
+            gpu_busy_ms: gpu_busy.map(|(ms, _)| ms),
+            gpu_busy_pct: gpu_busy.map(|(_, pct)| pct),
+            stalled: false,
+            capture_failed: false,
+            frametime_ms: stats.avg_ms,
+            jitter_ms: stats.jitter_ms,
+            stutter_count: count_stutters(windowed, stats.p50_ms),
+        })
+    }
+
+    /// Frames presented during the last second of wall-clock time (at most the sample buffer)
+    fn frames_in_last_second(&self) -> f64 {
+        let times = self.state.frame_times.lock();
+        let Some(&latest) = times.back() else {
+            return 0.0;
+        };
+        // "Now" on the timestamps' clock: the newest frame plus the time since it arrived
+        let since_latest = self.state.last_sample_at.lock().map_or(0.0, |at| at.elapsed().as_secs_f64());
+        let start = latest + since_latest - 1.0;
+        times.iter().rev().take_while(|&&t| t > start).count() as f64
+    }
+
+    /// Whether PresentMon failed to attach: its output ended without a CSV header,
+    /// or none came within HEADER_TIMEOUT
+    fn capture_failed(&self) -> bool {
+        if self.state.attach_failed.load(Ordering::SeqCst) {
+            return true;
+        }
+        if self.state.header_found.load(Ordering::SeqCst) || self.state.running_process.lock().is_none() {
+            return false;
+        }
+        let timed_out = self.state.target_set_at.lock().is_some_and(|started| started.elapsed() >= HEADER_TIMEOUT);
+        if timed_out && !self.state.attach_failed.swap(true, Ordering::SeqCst) {
+            crate::log::error(&format!("No PresentMon header after {} s, capture failed", HEADER_TIMEOUT.as_secs()));
+        }
+        timed_out
+    }
+
+    /// Start a benchmark run: from now on every frametime is collected, not just the rolling window,
+    /// and written to a CSV at `path`. If the file can't be created (e.g. locked by a spreadsheet)
+    /// the run still goes on in memory and the error is returned.
+    pub fn start_benchmark(&self, path: &std::path::Path) -> std::io::Result<()> {
+        crate::log::info(&format!("Benchmark started, logging to {:?}", path));
+        *self.state.benchmark_samples.lock() = Some(Vec::with_capacity(DEFAULT_SAMPLES * 10));
+
+        match open_benchmark_csv(path) {
+            Ok(csv) => {
+                *self.state.benchmark_csv.lock() = Some(csv);
+                Ok(())
+            }
+            Err(e) => {
+                crate::log::warn(&format!("Benchmark CSV unavailable: {}", e));
+                *self.state.benchmark_csv.lock() = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Append one frame to the benchmark CSV. A write error (disk full, file yanked away)
+    /// ends the CSV but not the run.
+    fn log_benchmark_frame(&self, pid: u32, ms: f64) {
+        let mut csv = self.state.benchmark_csv.lock();
+        let Some(writer) = csv.as_mut() else { return };
+        let timestamp = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap_or_default();
+        if let Err(e) = writeln!(writer, "{:.3},{},{:.3}", timestamp.as_secs_f64(), pid, ms) {
+            crate::log::warn(&format!("Benchmark CSV write failed, no longer logging frames: {}", e));
+            *csv = None;
+        }
+    }
+
+    /// Close the benchmark CSV with a summary of the run
+    fn finish_benchmark_csv(&self, stats: &FrameStats) {
+        let Some(mut writer) = self.state.benchmark_csv.lock().take() else { return };
+        let footer = writeln!(writer)
+            .and_then(|_| writeln!(writer, "summary,frames,avg_fps,one_percent_low,point_one_percent_low,min_fps,max_fps"))
+            .and_then(|_| {
+                writeln!(
+                    writer,
+                    "summary,{},{:.1},{:.1},{:.1},{:.1},{:.1}",
+                    stats.frames,
+                    stats.avg_fps,
+                    stats.one_percent_low,
+                    stats.point_one_percent_low,
+                    stats.min_fps,
+                    stats.max_fps
+                )
+            })
+            .and_then(|_| writer.flush());
+        if let Err(e) = footer {
+            crate::log::warn(&format!("Could not write the benchmark summary: {}", e));
+        }
+    }
+
+    pub fn is_benchmarking(&self) -> bool {
+        self.state.benchmark_samples.lock().is_some()
+    }
+
+    /// Stop the benchmark run and compute statistics over all collected frames.
+    /// The collection buffer is dropped here, returning to normal buffering.
+    pub fn stop_benchmark(&self) -> Option<FrameStats> {
+        let samples = self.state.benchmark_samples.lock().take()?;
+        let stats = compute_stats(&samples);
+        crate::log::info(&format!(
+            "Benchmark stopped: {} frames, avg {:.1} FPS, 1% low {:.1}",
+            stats.frames, stats.avg_fps, stats.one_percent_low
+        ));
+        self.finish_benchmark_csv(&stats);
+        Some(stats)
+    }
+
+    /// The last `n` frametimes (ms) in the sample buffer, oldest first
+    pub fn recent_frametimes(&self, n: usize) -> Vec<f64> {
+        let samples = self.state.ms_samples.lock();
+        samples.iter().skip(samples.len().saturating_sub(n)).copied().collect()
+    }
+
+    /// Statistics over the frametimes currently in the sample buffer
+    pub fn get_stats(&self) -> FrameStats {
+        let mut samples = self.state.ms_samples.lock();
+        compute_stats(samples.make_contiguous())
+    }
+
+    /// Frametime distribution of the sample buffer, see `histogram`
+    pub fn frametime_histogram(&self, bucket_ms: f64) -> Vec<(f64, u32)> {
+        let mut samples = self.state.ms_samples.lock();
+        histogram(samples.make_contiguous(), bucket_ms)
+    }
+
+    /// Resize the rolling sample buffers, dropping the oldest frames when shrinking
+    fn set_sample_capacity(&self, size: usize) {
+        let size = size.clamp(crate::settings::SAMPLE_BUFFER_MIN, crate::settings::SAMPLE_BUFFER_MAX);
+        if self.state.sample_capacity.swap(size, Ordering::SeqCst) == size {
+            return;
+        }
+        crate::log::info(&format!("Sample buffer size set to {}", size));
+        let buffers = [
+            &self.state.ms_samples,
+            &self.state.display_samples,
+            &self.state.gpu_busy_samples,
+            &self.state.frame_times,
+        ];
+        for buffer in buffers {
+            let mut samples = buffer.lock();
+            let excess = samples.len().saturating_sub(size);
+            samples.drain(..excess);
+            let len = samples.len();
+            if samples.capacity() > size {
+                samples.shrink_to(size);
+            } else {
+                samples.reserve(size - len);
+            }
+        }
+    }
+
+    fn stop_presentmon(&self) {
+        let mut proc = self.state.running_process.lock();
+        if let Some(mut child) = proc.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+
+            let stats = self.line_stats();
+            crate::log::info(&format!(
+                "PresentMon stopped: {} lines parsed, {} dropped",
+                stats.parsed, stats.dropped
+            ));
+        }
+        self.state.ms_samples.lock().clear();
+        self.state.display_samples.lock().clear();
+        self.state.gpu_busy_samples.lock().clear();
+        self.state.frame_times.lock().clear();
+        *self.state.last_sample_at.lock() = None;
+        self.state.parsed_lines.store(0, Ordering::Relaxed);
+        self.state.dropped_lines.store(0, Ordering::Relaxed);
+        self.state.header_found.store(false, Ordering::SeqCst);
+        self.state.attach_failed.store(false, Ordering::SeqCst);
+    }
+
+    fn start_presentmon(&self, pid: u32) {
+        self.stop_presentmon();
+    
+        if pid == 0 {
+            return;
+        }
+
+        crate::log::info(&format!("Starting PresentMon for PID {}", pid));
+        *self.state.target_set_at.lock() = Some(Instant::now());
+        let run = self.state.presentmon_runs.fetch_add(1, Ordering::SeqCst) + 1;
+
+        let pm_path = self.state.presentmon_path.lock().clone();
+        let pm_executable = pm_path.clone().unwrap_or_else(|| std::path::PathBuf::from("PresentMon.exe"));
+        let flavor = presentmon_flavor(&pm_executable);
+
+        let mut cmd = Command::new(&pm_executable);
+        // Argomenti per PresentMon (1.x "-flag", 2.x "--flag"):
+        // process_id <PID>
+        // output_stdout : Scrive CSV su stdout
+        // session_name + stop_existing_session : sessione ETW propria, fermando solo quella
+        //   rimasta da un avvio precedente con lo stesso nome
+        // La durata è infinita di default
+        cmd.args(flavor.capture_args(pid, &self.state.session_name));
+
+        // Nascondi finestra console se possibile
+        cmd.stdout(Stdio::piped());
+    
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            const CREATE_NO_WINDOW: u32 = 0x08000000;
+            cmd.creation_flags(CREATE_NO_WINDOW);
+        }
+
+        match cmd.spawn() {
+            Ok(mut child) => {
+                kill_with_parent(&child);
+                if let Some(stdout) = child.stdout.take() {
+                    let capture = self.clone();
+                    std::thread::spawn(move || {
+                        let reader = BufReader::new(stdout);
+                        let mut lines = reader.lines();
+                        let mut raw_log = if capture.state.config.lock().save_raw_presentmon {
+                            RawLog::open()
+                        } else {
+                            None
+                        };
+                    
+                        // Cerca l'header per trovare l'indice della colonna del frametime
+                        let mut ms_idx = usize::MAX;
+                        // Optional: not every PresentMon version/mode reports display changes
+                        let mut display_idx: Option<usize> = None;
+                        // Same for GPU busy time (1.x only reports it with GPU tracking on)
+                        let mut gpu_busy_idx: Option<usize> = None;
+                        // Present timestamps, else frames are timed by when they're read
+                        let mut time_idx: Option<usize> = None;
+                    
+                        // Leggi finché non trovi l'header
+                        while let Some(Ok(line)) = lines.next() {
+                            let cols: Vec<&str> = line.split(',').collect();
+                            if let Some(idx) = find_column(&cols, &FRAMETIME_COLUMNS) {
+                                ms_idx = idx;
+                                if let Some(raw) = raw_log.as_mut() {
+                                    raw.write_header(&line);
+                                }
+                                crate::log::info(&format!("Found frametime column {} at col {}", cols[idx].trim(), ms_idx));
+                                capture.state.header_found.store(true, Ordering::SeqCst);
+                                display_idx = find_column(&cols, &DISPLAY_COLUMNS);
+                                if display_idx.is_none() {
+                                    crate::log::info("Display change column not available, display FPS disabled");
+                                }
+                                gpu_busy_idx = find_column(&cols, &GPU_BUSY_COLUMNS);
+                                if gpu_busy_idx.is_none() {
+                                    crate::log::info("GPU busy column not available, GPU busy disabled");
+                                }
+                                time_idx = find_column(&cols, &TIME_COLUMNS);
+                                break;
+                            }
+                            // Kept out of the raw CSV, which starts at the header
+                            crate::log::info(&format!("PresentMon: {}", line));
+                        }
+                    
+                        if ms_idx == usize::MAX {
+                            crate::log::error(&format!("Could not find a frametime column ({})", FRAMETIME_COLUMNS.join(", ")));
+                            // Output ended without a header (PresentMon couldn't attach and quit),
+                            // unless this PresentMon was stopped on purpose and replaced
+                            if capture.state.presentmon_runs.load(Ordering::SeqCst) == run {
+                                capture.state.attach_failed.store(true, Ordering::SeqCst);
+                            }
+                            return;
+                        }
+
+                        // Leggi i dati
+                        while let Some(Ok(line)) = lines.next() {
+                             // Controlla se dobbiamo fermarci
+                             if !capture.state.is_running.load(Ordering::SeqCst) {
+                                 break;
+                             }
+
+                             if let Some(raw) = raw_log.as_mut() {
+                                 raw.write_line(&line);
+                             }
+
+                             let cols: Vec<&str> = line.split(',').collect();
+                             let parsed = cols.get(ms_idx).and_then(|c| c.trim().parse::<f64>().ok());
+                             match parsed {
+                                 Some(ms) => {
+                                     capture.state.parsed_lines.fetch_add(1, Ordering::Relaxed);
+
+                                     // First frame after a stall: drop the pre-pause history (and the gap
+                                     // itself, which isn't a real frame) so the display recovers at once
+                                     let now = Instant::now();
+                                     let last = capture.state.last_sample_at.lock().replace(now);
+                                     let stall_after = capture.state.config.lock().stall_after;
+                                     if let (Some(stall_after), Some(last)) = (stall_after, last) {
+                                         if now.duration_since(last) >= stall_after {
+                                             crate::log::info("Frames resumed after a stall");
+                                             capture.state.ms_samples.lock().clear();
+                                             capture.state.display_samples.lock().clear();
+                                             capture.state.gpu_busy_samples.lock().clear();
+                                             capture.state.frame_times.lock().clear();
+                                             *capture.state.ema_fps.lock() = None;
+                                             continue;
+                                         }
+                                     }
+                                     let capacity = capture.state.sample_capacity.load(Ordering::Relaxed);
+                                     let mut samples = capture.state.ms_samples.lock();
+                                     samples.push_back(ms);
+                                     if samples.len() > capacity {
+                                         samples.pop_front();
+                                     }
+                                     drop(samples);
+
+                                     let mut session = capture.state.session.lock();
+                                     session.frames += 1;
+                                     session.total_ms += ms;
+                                     drop(session);
+
+                                     if let Some(idx) = display_idx {
+                                         let display_ms = cols.get(idx).and_then(|c| c.trim().parse::<f64>().ok()).unwrap_or(0.0);
+                                         let mut display = capture.state.display_samples.lock();
+                                         display.push_back(display_ms);
+                                         if display.len() > capacity {
+                                             display.pop_front();
+                                         }
+                                     }
+
+                                     if let Some(idx) = gpu_busy_idx {
+                                         let busy_ms = cols.get(idx).and_then(|c| c.trim().parse::<f64>().ok()).unwrap_or(0.0);
+                                         let mut gpu_busy = capture.state.gpu_busy_samples.lock();
+                                         gpu_busy.push_back(busy_ms);
+                                         if gpu_busy.len() > capacity {
+                                             gpu_busy.pop_front();
+                                         }
+                                     }
+
+                                     let time = time_idx
+                                         .and_then(|idx| cols.get(idx).and_then(|c| c.trim().parse::<f64>().ok()))
+                                         .unwrap_or_else(|| now.duration_since(capture.state.epoch).as_secs_f64());
+                                     let mut frame_times = capture.state.frame_times.lock();
+                                     frame_times.push_back(time);
+                                     if frame_times.len() > capacity {
+                                         frame_times.pop_front();
+                                     }
+                                     drop(frame_times);
+
+                                     if let Some(bench) = capture.state.benchmark_samples.lock().as_mut() {
+                                         if bench.len() < BENCHMARK_MAX_SAMPLES {
+                                             bench.push(ms);
+                                             capture.log_benchmark_frame(pid, ms);
+                                         }
+                                     }
+                                 }
+                                 None => {
+                                     // Too few columns or not a number: count it and log the first few
+                                     let dropped = capture.state.dropped_lines.fetch_add(1, Ordering::Relaxed);
+                                     if dropped < MAX_LOGGED_BAD_LINES {
+                                         crate::log::warn(&format!("Unparsed PresentMon line ({} cols): {}", cols.len(), line));
+                                     } else if dropped == MAX_LOGGED_BAD_LINES {
+                                         crate::log::warn("Further unparsed lines will not be logged");
+                                     }
+                                 }
+                             }
+                        }
+                    });
+                }
+            
+                *self.state.running_process.lock() = Some(child);
+            }
+            Err(e) => {
+                let err = if pm_path.is_some() {
+                    classify_error(&e, &pm_executable)
+                } else {
+                    CaptureError::NotFound
+                };
+                crate::log::error(&format!("Failed to start PresentMon: {} ({:?})", e, err));
+                if let CaptureError::BlockedByAntivirus(_) = err {
+                    crate::log::error("Hint: add an antivirus exclusion for the PresentMon path above");
+                }
+                *self.state.last_error.lock() = Some(err);
+            }
+        }
     }
 }
 
-// Global static path cache
-static PRESENTMON_PATH: once_cell::sync::Lazy<Mutex<Option<std::path::PathBuf>>> = once_cell::sync::Lazy::new(|| {
-    Mutex::new(None)
-});
+/// A source of frame data. The module-level API delegates to the one picked by
+/// the `capture_backend` setting; PresentMon is the default and the only one with frametimes.
+pub trait CaptureBackend: Send + Sync {
+    fn init(&self, custom_path: Option<&std::path::Path>) -> Result<(), CaptureError>;
+    fn is_running(&self) -> bool;
+    fn set_target_process(&self, pid: u32);
+    fn get_fps_for_process(&self, process_id: u32) -> Option<FpsData>;
+    fn shutdown(&self);
+}
+
+impl CaptureBackend for Capture {
+    fn init(&self, custom_path: Option<&std::path::Path>) -> Result<(), CaptureError> {
+        Capture::init(self, custom_path)
+    }
+
+    fn is_running(&self) -> bool {
+        self.state.is_running.load(Ordering::SeqCst)
+    }
+
+    fn set_target_process(&self, pid: u32) {
+        Capture::set_target_process(self, pid)
+    }
+
+    fn get_fps_for_process(&self, process_id: u32) -> Option<FpsData> {
+        Capture::get_fps_for_process(self, process_id)
+    }
+
+    fn shutdown(&self) {
+        Capture::shutdown(self)
+    }
+}
+
+// Shortest interval the DWM frame counter is sampled over, shorter ones are too noisy
+const DWM_SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Fallback backend for systems where ETW is blocked: polls the DWM composition
+/// frame counter. It counts every frame DWM composed, not the target's presents,
+/// so it only matches the game's FPS for a windowed/borderless game that is the only
+/// thing updating, and can't see past the refresh rate. No frametimes, lows or stats.
+#[derive(Default)]
+pub struct DwmCapture {
+    running: AtomicBool,
+    target_process_id: AtomicU32,
+    // Counter value and time of the last sample, and the FPS measured from it
+    last_sample: Mutex<Option<(Instant, u64)>>,
+    fps: Mutex<f64>,
+}
+
+impl DwmCapture {
+    fn composed_frames() -> Option<u64> {
+        let mut info = windows::Win32::Graphics::Dwm::DWM_TIMING_INFO {
+            cbSize: std::mem::size_of::<windows::Win32::Graphics::Dwm::DWM_TIMING_INFO>() as u32,
+            ..Default::default()
+        };
+        unsafe {
+            windows::Win32::Graphics::Dwm::DwmGetCompositionTimingInfo(
+                windows::Win32::Foundation::HWND(0),
+                &mut info,
+            )
+            .ok()?;
+        }
+        Some(info.cFrame)
+    }
+}
+
+impl CaptureBackend for DwmCapture {
+    fn init(&self, _custom_path: Option<&std::path::Path>) -> Result<(), CaptureError> {
+        crate::log::info("FPS capture init (DWM polling mode)");
+        if Self::composed_frames().is_none() {
+            return Err(CaptureError::Other("DWM composition timing unavailable".to_string()));
+        }
+        self.running.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    fn set_target_process(&self, pid: u32) {
+        if self.target_process_id.swap(pid, Ordering::SeqCst) != pid {
+            *self.last_sample.lock() = None;
+            *self.fps.lock() = 0.0;
+        }
+    }
+
+    fn get_fps_for_process(&self, process_id: u32) -> Option<FpsData> {
+        if !self.is_running() || process_id == 0 {
+            return None;
+        }
+        self.set_target_process(process_id);
+        let frames = Self::composed_frames()?;
+        let now = Instant::now();
+        let mut last = self.last_sample.lock();
+        match *last {
+            Some((at, count)) => {
+                let elapsed = now.duration_since(at);
+                if elapsed >= DWM_SAMPLE_INTERVAL {
+                    *self.fps.lock() = frames.saturating_sub(count) as f64 / elapsed.as_secs_f64();
+                    *last = Some((now, frames));
+                }
+            }
+            None => *last = Some((now, frames)),
+        }
+        let fps = *self.fps.lock();
+        Some(FpsData { fps, fps_instant: fps, ..Default::default() })
+    }
+
+    fn shutdown(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.target_process_id.store(0, Ordering::SeqCst);
+        *self.last_sample.lock() = None;
+    }
+}
+
+// The session behind the module-level functions used by the app
+static DEFAULT: once_cell::sync::Lazy<Capture> = once_cell::sync::Lazy::new(Capture::new);
+static DWM: once_cell::sync::Lazy<DwmCapture> = once_cell::sync::Lazy::new(DwmCapture::default);
+// The capture_backend setting, as CaptureBackendKind discriminant
+static ACTIVE_BACKEND: AtomicU8 = AtomicU8::new(0);
+
+fn backend_for(kind: crate::settings::CaptureBackendKind) -> &'static dyn CaptureBackend {
+    match kind {
+        crate::settings::CaptureBackendKind::PresentMon => &*DEFAULT,
+        crate::settings::CaptureBackendKind::DwmPolling => &*DWM,
+    }
+}
+
+fn backend_kind(value: u8) -> crate::settings::CaptureBackendKind {
+    match value {
+        1 => crate::settings::CaptureBackendKind::DwmPolling,
+        _ => crate::settings::CaptureBackendKind::PresentMon,
+    }
+}
+
+/// The backend picked by the `capture_backend` setting
+fn backend() -> &'static dyn CaptureBackend {
+    backend_for(backend_kind(ACTIVE_BACKEND.load(Ordering::SeqCst)))
+}
+
+// --- API PUBBLICHE ---
+
+pub fn init(custom_path: Option<&std::path::Path>) -> Result<(), CaptureError> {
+    backend().init(custom_path)
+}
+
+/// Parsed vs dropped PresentMon data lines for the current session.
+/// A high drop rate points at a CSV format mismatch.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineStats {
+    pub parsed: u64,
+    pub dropped: u64,
+}
+
+/// Line counters of the running PresentMon session (debug API)
+pub fn line_stats() -> LineStats {
+    DEFAULT.line_stats()
+}
+
+/// Returns (and clears) the last error reported while starting PresentMon
+pub fn take_error() -> Option<CaptureError> {
+    DEFAULT.take_error()
+}
+
+// Windows error codes that matter when opening/spawning PresentMon
+const ERROR_FILE_NOT_FOUND: i32 = 2;
+const ERROR_PATH_NOT_FOUND: i32 = 3;
+const ERROR_ACCESS_DENIED: i32 = 5;
+const ERROR_VIRUS_INFECTED: i32 = 225;
+const ERROR_VIRUS_DELETED: i32 = 226;
+const ERROR_ELEVATION_REQUIRED: i32 = 740;
+
+/// Map an OS error on the resolved PresentMon path to a user-facing cause.
+/// The path was already resolved once, so "not found" here means it vanished (quarantine).
+fn classify_error(e: &std::io::Error, path: &std::path::Path) -> CaptureError {
+    match e.raw_os_error() {
+        Some(ERROR_VIRUS_INFECTED | ERROR_VIRUS_DELETED)
+        | Some(ERROR_FILE_NOT_FOUND | ERROR_PATH_NOT_FOUND) => {
+            CaptureError::BlockedByAntivirus(path.to_path_buf())
+        }
+        // Access denied on a file we can still read is about rights, not the antivirus
+        // (which locks the file itself)
+        Some(ERROR_ACCESS_DENIED) if read_header(path).is_ok() => CaptureError::NeedsAdmin,
+        Some(ERROR_ACCESS_DENIED) => CaptureError::BlockedByAntivirus(path.to_path_buf()),
+        Some(ERROR_ELEVATION_REQUIRED) => CaptureError::NeedsAdmin,
+        _ => CaptureError::Other(e.to_string()),
+    }
+}
+
+/// Read the first bytes of `path`. Reading (not just opening) triggers the antivirus'
+/// on-access scan, so a quarantined or locked file fails here.
+fn read_header(path: &std::path::Path) -> std::io::Result<()> {
+    use std::io::Read;
+    std::fs::File::open(path)?.read_exact(&mut [0u8; 2])
+}
+
+/// Make sure the resolved executable is still there and readable
+fn verify_presentmon(path: &std::path::Path) -> Result<(), CaptureError> {
+    read_header(path).map_err(|e| classify_error(&e, path))
+}
+
+// Command-line flavor per PresentMon executable, probed the first time it's started
+static PRESENTMON_FLAVOR: Mutex<Option<(std::path::PathBuf, PresentMonFlavor)>> = Mutex::new(None);
+// How long `--version` may take before the executable is assumed to be 1.x
+const VERSION_PROBE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Frametime column names: 1.x and most 2.x builds, then the 2.x rename
+const FRAMETIME_COLUMNS: [&str; 2] = ["MsBetweenPresents", "FrameTime"];
+const DISPLAY_COLUMNS: [&str; 2] = ["MsBetweenDisplayChange", "DisplayedTime"];
+const GPU_BUSY_COLUMNS: [&str; 2] = ["MsGPUActive", "GPUBusy"];
+// Seconds since the capture started at each present (PresentMon 1.x)
+const TIME_COLUMNS: [&str; 1] = ["TimeInSeconds"];
+
+/// Command-line conventions of a PresentMon build
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PresentMonFlavor {
+    /// 1.x: single-dash flags
+    V1,
+    /// 2.x: double-dash flags
+    V2,
+}
+
+impl PresentMonFlavor {
+    /// Arguments for a CSV capture of `pid` on stdout in the ETW session `session`, stopping
+    /// a session of that name left behind (other captures use other names)
+    fn capture_args(self, pid: u32, session: &str) -> Vec<String> {
+        let dash = match self {
+            PresentMonFlavor::V1 => "-",
+            PresentMonFlavor::V2 => "--",
+        };
+        vec![
+            format!("{}process_id", dash),
+            pid.to_string(),
+            format!("{}output_stdout", dash),
+            format!("{}session_name", dash),
+            session.to_string(),
+            format!("{}stop_existing_session", dash),
+        ]
+    }
+}
+
+/// Flavor of the executable at `path`, probed once per path
+fn presentmon_flavor(path: &std::path::Path) -> PresentMonFlavor {
+    let mut cached = PRESENTMON_FLAVOR.lock();
+    if let Some((cached_path, flavor)) = cached.as_ref() {
+        if cached_path == path {
+            return *flavor;
+        }
+    }
+    let flavor = probe_flavor(path);
+    *cached = Some((path.to_path_buf(), flavor));
+    flavor
+}
+
+/// 2.x prints "PresentMon 2.x.y" for `--version`; 1.x rejects the flag (usage on stderr)
+fn probe_flavor(path: &std::path::Path) -> PresentMonFlavor {
+    let Ok(mut child) = version_command(path).spawn() else {
+        crate::log::warn("PresentMon version probe failed to start, assuming 1.x");
+        return PresentMonFlavor::V1;
+    };
+
+    let deadline = Instant::now() + VERSION_PROBE_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            // Still running (maybe capturing instead): not a build that knows --version
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                crate::log::warn("PresentMon did not answer --version, assuming 1.x");
+                return PresentMonFlavor::V1;
+            }
+        }
+    }
+
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        let _ = stdout.read_to_string(&mut output);
+    }
+    let version = output.lines().find(|line| line.to_ascii_lowercase().contains("presentmon")).unwrap_or("").trim();
+    let major = version
+        .split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty())
+        .and_then(|part| part.parse::<u32>().ok());
+    let flavor = match major {
+        Some(major) if major >= 2 => PresentMonFlavor::V2,
+        _ => PresentMonFlavor::V1,
+    };
+    crate::log::info(&format!("PresentMon version \"{}\", using {:?} arguments", version, flavor));
+    flavor
+}
+
+/// `path --version` without a console window, stdout piped
+fn version_command(path: &std::path::Path) -> Command {
+    let mut cmd = Command::new(path);
+    cmd.arg("--version").stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        cmd.creation_flags(CREATE_NO_WINDOW);
+    }
+    cmd
+}
+
+/// Check that the PresentMon at `path` actually starts, by running `--version`.
+/// A copy the antivirus blocks fails to spawn; one that runs but doesn't know
+/// --version (1.x starts capturing instead) is killed and still counts as runnable.
+fn probe_runnable(path: &std::path::Path) -> Result<(), CaptureError> {
+    let mut child = version_command(path).spawn().map_err(|e| {
+        crate::log::warn(&format!("Version probe of {:?} failed to start: {}", path, e));
+        classify_error(&e, path)
+    })?;
+    let deadline = Instant::now() + VERSION_PROBE_TIMEOUT;
+    loop {
+        match child.try_wait() {
+            Ok(Some(status)) => {
+                crate::log::info(&format!("Version probe of {:?} exited with {}", path, status));
+                return Ok(());
+            }
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(20)),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                crate::log::info(&format!("Version probe of {:?} started but did not exit, killed it", path));
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Index of the first column called one of `names` (case-insensitive)
+fn find_column(cols: &[&str], names: &[&str]) -> Option<usize> {
+    cols.iter().position(|col| names.iter().any(|name| col.trim().eq_ignore_ascii_case(name)))
+}
 
 // EMBEDDED BINARY
 const PRESENTMON_BIN: &[u8] = include_bytes!("../PresentMon.exe");
 
-fn detect_presentmon_path() -> Option<std::path::PathBuf> {
+/// Where to run PresentMon from: the configured path, a copy next to the executable
+/// (or in the CWD), else the embedded one extracted to the temp folder
+fn detect_presentmon_path(custom_path: Option<&std::path::Path>) -> Result<std::path::PathBuf, CaptureError> {
     let filename = "PresentMon.exe";
     
+    // 0. Percorso scelto dall'utente nelle impostazioni
+    if let Some(p) = custom_path {
+        if p.exists() { return Ok(p.to_path_buf()); }
+        crate::log::warn(&format!("Configured PresentMon {:?} not found, using the default search", p));
+    }
+
     // 1. Controllo directory eseguibile (Priorità massima per override manuale)
     if let Ok(exe_path) = std::env::current_exe() {
         if let Some(parent) = exe_path.parent() {
             let p = parent.join(filename);
-            if p.exists() { return Some(p); }
+            if p.exists() { return Ok(p); }
         }
     }
 
     // 2. Controllo directory di lavoro corrente (CWD)
     if let Ok(cwd) = std::env::current_dir() {
         let p = cwd.join(filename);
-        if p.exists() { return Some(p); }
+        if p.exists() { return Ok(p); }
     }
     
     // 3. Controllo directory genitore (utile per dev)
@@ -104,195 +1310,691 @@ fn detect_presentmon_path() -> Option<std::path::PathBuf> {
         for _ in 0..4 {
             if let Some(p) = current {
                 let path = p.join(filename);
-                if path.exists() { return Some(path); }
+                if path.exists() { return Ok(path); }
                 current = p.parent();
             }
         }
     }
 
     // 4. Estrazione binario integrato (Fallback portatile)
-    if let Some(path) = extract_embedded_presentmon() {
-        return Some(path);
-    }
-
-    None
+    extract_embedded_presentmon()
 }
 
-fn extract_embedded_presentmon() -> Option<std::path::PathBuf> {
-    let mut temp_path = std::env::temp_dir();
-    temp_path.push("EasyFPS");
-    
-    if let Err(e) = std::fs::create_dir_all(&temp_path) {
-        log_debug(&format!("Failed to create temp dir: {}", e));
-        return None;
-    }
-    
-    temp_path.push("PresentMon_Internal.exe");
+// Names tried in turn for the extracted PresentMon; the second one is used when
+// the first is locked by an older EasyFPS or blocked
+const EXTRACTED_NAMES: [&str; 2] = ["PresentMon_Internal.exe", "PresentMon_Internal_2.exe"];
+
+/// Write the embedded PresentMon to the temp folder and make sure it runs there
+fn extract_embedded_presentmon() -> Result<std::path::PathBuf, CaptureError> {
+    let mut temp_dir = std::env::temp_dir();
+    temp_dir.push("EasyFPS");
     
-    // Proviamo a scrivere il file. Se è in uso (es. istanza precedente bloccata),
-    // ignoriamo l'errore sperando che il file esistente sia valido.
-    match std::fs::write(&temp_path, PRESENTMON_BIN) {
-        Ok(_) => log_debug("Embedded PresentMon extracted."),
-        Err(e) => log_debug(&format!("Could not write embedded binary (might be in use): {}", e)),
+    if let Err(e) = std::fs::create_dir_all(&temp_dir) {
+        crate::log::error(&format!("Failed to create temp dir: {}", e));
+        return Err(CaptureError::ExtractFailed(e.to_string()));
     }
     
-    if temp_path.exists() {
-        Some(temp_path)
-    } else {
-        None
+    let mut last_error = String::new();
+    for name in EXTRACTED_NAMES {
+        let temp_path = temp_dir.join(name);
+        // Se è in uso (es. istanza precedente), il file esistente va bene purché parta
+        match std::fs::write(&temp_path, PRESENTMON_BIN) {
+            Ok(_) => crate::log::info(&format!("Embedded PresentMon extracted to {:?}", temp_path)),
+            Err(e) => {
+                crate::log::warn(&format!("Could not write {:?} (might be in use): {}", temp_path, e));
+                last_error = e.to_string();
+            }
+        }
+        if !temp_path.exists() {
+            continue;
+        }
+        match probe_runnable(&temp_path) {
+            Ok(()) => return Ok(temp_path),
+            Err(e) => {
+                crate::log::warn(&format!("Extracted PresentMon at {:?} does not run: {}", temp_path, e));
+                last_error = e.to_string();
+            }
+        }
     }
+    Err(CaptureError::ExtractFailed(last_error))
 }
 
 pub fn shutdown() {
-    log_debug("Shutdown requested");
-    STATE.is_running.store(false, Ordering::SeqCst);
-    STATE.target_process_id.store(0, Ordering::SeqCst);
-    stop_presentmon();
+    backend().shutdown()
 }
 
-pub fn set_target_process(pid: u32) {
-    let old_pid = STATE.target_process_id.swap(pid, Ordering::SeqCst);
-    if old_pid != pid {
-        log_debug(&format!("Target PID changed to: {}", pid));
-        start_presentmon(pid);
+// Job object PresentMon runs in. JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE ends PresentMon when
+// its handle closes, i.e. when EasyFPS exits for any reason (crash, Task Manager), so a
+// capture isn't left running without us. Never closed explicitly. 0 = couldn't be created.
+static KILL_ON_EXIT_JOB: once_cell::sync::Lazy<isize> = once_cell::sync::Lazy::new(|| unsafe {
+    use windows::Win32::System::JobObjects::{
+        CreateJobObjectW, JobObjectExtendedLimitInformation, SetInformationJobObject,
+        JOBOBJECT_EXTENDED_LIMIT_INFORMATION, JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE,
+    };
+
+    let job = match CreateJobObjectW(None, windows::core::PCWSTR::null()) {
+        Ok(job) => job,
+        Err(e) => {
+            crate::log::warn(&format!("Job object unavailable, PresentMon may outlive a crash: {}", e));
+            return 0;
+        }
+    };
+    let mut limits = JOBOBJECT_EXTENDED_LIMIT_INFORMATION::default();
+    limits.BasicLimitInformation.LimitFlags = JOB_OBJECT_LIMIT_KILL_ON_JOB_CLOSE;
+    if let Err(e) = SetInformationJobObject(
+        job,
+        JobObjectExtendedLimitInformation,
+        &limits as *const _ as *const std::ffi::c_void,
+        std::mem::size_of::<JOBOBJECT_EXTENDED_LIMIT_INFORMATION>() as u32,
+    ) {
+        crate::log::warn(&format!("Job object limits not set: {}", e));
+        let _ = windows::Win32::Foundation::CloseHandle(job);
+        return 0;
+    }
+    job.0
+});
+
+/// Tie `child` to this process, see KILL_ON_EXIT_JOB
+fn kill_with_parent(child: &Child) {
+    use std::os::windows::io::AsRawHandle;
+    use windows::Win32::Foundation::HANDLE;
+
+    let job = *KILL_ON_EXIT_JOB;
+    if job == 0 {
+        return;
+    }
+    let process = HANDLE(child.as_raw_handle() as isize);
+    if let Err(e) = unsafe { windows::Win32::System::JobObjects::AssignProcessToJobObject(HANDLE(job), process) } {
+        crate::log::warn(&format!("PresentMon not added to the job object: {}", e));
+    }
+}
+
+/// Start the session min/max/average over
+pub fn reset_stats() {
+    DEFAULT.reset_stats()
+}
+
+/// Apply the capture-related user settings.
+/// Switching `capture_backend` stops the old backend and starts the new one if it was running.
+pub fn configure(settings: &crate::settings::Settings) {
+    DEFAULT.configure(settings);
+    let kind = settings.capture_backend;
+    let previous = backend_kind(ACTIVE_BACKEND.swap(kind as u8, Ordering::SeqCst));
+    if previous != kind {
+        crate::log::info(&format!("Capture backend changed to: {:?}", kind));
+        let old = backend_for(previous);
+        let was_running = old.is_running();
+        old.shutdown();
+        if was_running {
+            if let Err(e) = backend_for(kind).init(settings.presentmon_path.as_deref()) {
+                crate::log::error(&format!("Capture backend {:?} failed to start: {}", kind, e));
+            }
+        }
     }
 }
 
+pub fn set_target_process(pid: u32) {
+    backend().set_target_process(pid)
+}
+
 pub fn get_fps_for_process(process_id: u32) -> Option<FpsData> {
-    // Assicurati che il processo target sia impostato
-    if STATE.target_process_id.load(Ordering::SeqCst) != process_id {
-        set_target_process(process_id);
+    backend().get_fps_for_process(process_id)
+}
+
+/// Frames taking more than STUTTER_FACTOR times the median frametime.
+/// Counted over the given window only, so it drops back once the game runs smoothly.
+fn count_stutters(samples: &[f64], median_ms: f64) -> u32 {
+    if median_ms <= 0.0 {
+        return 0;
     }
-    
-    let samples = STATE.ms_samples.lock();
-    
-    if samples.is_empty() {
-        return Some(FpsData { fps: 0.0, one_percent_low: 0.0 });
+    samples.iter().filter(|&&ms| ms > median_ms * STUTTER_FACTOR).count() as u32
+}
+
+/// The most recent samples whose cumulative frametime fits in `window_ms`
+/// (at least one sample when any exist). A window of 0 returns every sample.
+pub fn window_slice(samples: &[f64], window_ms: u32) -> &[f64] {
+    if window_ms == 0 {
+        return samples;
+    }
+    let mut total = 0.0;
+    let mut start = samples.len();
+    for (i, &ms) in samples.iter().enumerate().rev() {
+        if total + ms > window_ms as f64 && start < samples.len() {
+            break;
+        }
+        total += ms;
+        start = i;
+    }
+    &samples[start..]
+}
+
+/// Displayed frames per second over the same frames as `present_ms`.
+/// `display_ms` holds MsBetweenDisplayChange for each present (0 when the frame never reached
+/// the screen), so fewer displayed than presented frames means frames were dropped.
+/// Returns None when no display data is available.
+pub fn compute_display_fps(present_ms: &[f64], display_ms: &[f64]) -> Option<f64> {
+    if display_ms.is_empty() || display_ms.len() != present_ms.len() {
+        return None;
+    }
+    let total_ms: f64 = present_ms.iter().sum();
+    if total_ms <= 0.0 {
+        return None;
+    }
+    let displayed = display_ms.iter().filter(|&&ms| ms > 0.0).count();
+    Some(displayed as f64 * 1000.0 / total_ms)
+}
+
+/// Average GPU busy time per frame (ms) and its share of the frametime (%, at most 100),
+/// over the same frames as `present_ms`. `busy_ms` holds the GPU busy time of each present.
+/// Returns None when no GPU busy data is available.
+pub fn compute_gpu_busy(present_ms: &[f64], busy_ms: &[f64]) -> Option<(f64, f64)> {
+    if busy_ms.is_empty() || busy_ms.len() != present_ms.len() {
+        return None;
+    }
+    let total_ms: f64 = present_ms.iter().sum();
+    if total_ms <= 0.0 {
+        return None;
+    }
+    let busy_total: f64 = busy_ms.iter().sum();
+    Some((busy_total / busy_ms.len() as f64, (busy_total / total_ms * 100.0).min(100.0)))
+}
+
+// Frame caps commonly set by games, drivers and VSync on common refresh rates
+const COMMON_CAPS: [u32; 17] = [30, 48, 50, 60, 72, 75, 90, 100, 120, 144, 165, 170, 180, 200, 240, 280, 360];
+// Number of recent frames inspected for cap detection
+const CAP_WINDOW: usize = 240;
+// Frametime spread (standard deviation over mean) below which a stable cap counts as a limit
+const CAPPED_MAX_VARIATION: f64 = 0.03;
+// Frames behind the instant FPS
+const INSTANT_FRAMES: usize = 5;
+
+/// FPS from the median of the last INSTANT_FRAMES frametimes (0 without samples).
+/// The median keeps one hitch among them from moving the number.
+pub fn instant_fps(samples: &[f64]) -> f64 {
+    let mut recent: Vec<f64> = samples[samples.len().saturating_sub(INSTANT_FRAMES)..].to_vec();
+    if recent.is_empty() {
+        return 0.0;
+    }
+    recent.sort_by(|a, b| a.total_cmp(b));
+    let median = recent[recent.len() / 2];
+    if median > 0.0 { 1000.0 / median } else { 0.0 }
+}
+
+/// Whether the framerate is actually held by a limiter or VSync. Conservative: the cap must
+/// be detected as stable and the frametimes nearly constant, so a game that merely runs
+/// steadily near 60/120/144 FPS isn't reported as capped.
+pub fn is_capped(samples: &[f64], cap: Option<FrameCap>) -> bool {
+    if !cap.is_some_and(|cap| cap.stable) || samples.is_empty() {
+        return false;
+    }
+    let count = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / count;
+    if mean <= 0.0 {
+        return false;
+    }
+    let variance = samples.iter().map(|ms| (ms - mean).powi(2)).sum::<f64>() / count;
+    variance.sqrt() / mean < CAPPED_MAX_VARIATION
+}
+
+/// Infer a frame cap from recent frametimes.
+/// A cap shows up as a median sitting on a common cap interval with (almost) no frames
+/// faster than it. It is "stable" when >= 95% of frames land within 10% of the interval,
+/// otherwise the cap is being missed often. Returns None when no cap is evident.
+pub fn detect_cap(samples: &[f64]) -> Option<FrameCap> {
+    if samples.len() < 60 {
+        return None;
+    }
+
+    let mut sorted = samples.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    let median_ms = sorted[sorted.len() / 2];
+    if median_ms <= 0.0 {
+        return None;
     }
 
-    // Calcolo FPS (Media degli ultimi campioni)
-    // Usiamo una finestra mobile, es. ultimi 1000ms o max campioni
+    // Median must be within 3% of a known cap interval
+    let median_fps = 1000.0 / median_ms;
+    let cap = COMMON_CAPS
+        .iter()
+        .copied()
+        .find(|&c| (median_fps - c as f64).abs() / (c as f64) < 0.03)?;
+    let cap_ms = 1000.0 / cap as f64;
+
+    // Frames much faster than the interval mean the game isn't actually limited
+    let count = samples.len() as f64;
+    let faster = samples.iter().filter(|&&ms| ms < cap_ms * 0.9).count() as f64;
+    if faster / count > 0.05 {
+        return None;
+    }
+
+    let on_cap = samples.iter().filter(|&&ms| (ms - cap_ms).abs() <= cap_ms * 0.1).count() as f64;
+    if on_cap / count < 0.5 {
+        return None;
+    }
+
+    Some(FrameCap { fps: cap, stable: on_cap / count >= 0.95 })
+}
+
+/// Start a benchmark run: from now on every frametime is collected, not just the rolling window,
+/// and written to a CSV at `path`. If the file can't be created (e.g. locked by a spreadsheet)
+/// the run still goes on in memory and the error is returned.
+pub fn start_benchmark(path: &std::path::Path) -> std::io::Result<()> {
+    DEFAULT.start_benchmark(path)
+}
+
+fn open_benchmark_csv(path: &std::path::Path) -> std::io::Result<std::io::BufWriter<std::fs::File>> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut csv = std::io::BufWriter::new(std::fs::File::create(path)?);
+    writeln!(csv, "timestamp,pid,ms_between_presents")?;
+    Ok(csv)
+}
+
+pub fn is_benchmarking() -> bool {
+    DEFAULT.is_benchmarking()
+}
+
+/// Stop the benchmark run and compute statistics over all collected frames.
+/// The collection buffer is dropped here, returning to normal buffering.
+pub fn stop_benchmark() -> Option<FrameStats> {
+    DEFAULT.stop_benchmark()
+}
+
+/// The last `n` frametimes (ms) in the sample buffer, oldest first
+pub fn recent_frametimes(n: usize) -> Vec<f64> {
+    DEFAULT.recent_frametimes(n)
+}
+
+/// Statistics over the frametimes currently in the sample buffer
+pub fn get_stats() -> FrameStats {
+    DEFAULT.get_stats()
+}
+
+/// Frametime distribution of the sample buffer, see `histogram`
+pub fn frametime_histogram(bucket_ms: f64) -> Vec<(f64, u32)> {
+    DEFAULT.frametime_histogram(bucket_ms)
+}
+
+/// Count frametimes in `bucket_ms` wide ranges: (range start in ms, frames), from 0 up to
+/// the slowest frame, empty ranges included. Past HISTOGRAM_MAX_BUCKETS the last bucket
+/// takes every slower frame, so a long hitch can't produce thousands of buckets.
+pub fn histogram(samples: &[f64], bucket_ms: f64) -> Vec<(f64, u32)> {
+    if bucket_ms <= 0.0 || samples.is_empty() {
+        return Vec::new();
+    }
+    let bucket_of = |ms: f64| ((ms.max(0.0) / bucket_ms) as usize).min(HISTOGRAM_MAX_BUCKETS - 1);
+    let buckets = samples.iter().map(|&ms| bucket_of(ms)).max().unwrap_or(0) + 1;
+    let mut counts = vec![0u32; buckets];
+    for &ms in samples {
+        counts[bucket_of(ms)] += 1;
+    }
+    counts
+        .into_iter()
+        .enumerate()
+        .map(|(i, count)| (i as f64 * bucket_ms, count))
+        .collect()
+}
+
+/// Frame statistics computed from a set of frametimes
+#[derive(Debug, Clone, Default)]
+pub struct FrameStats {
+    pub frames: usize,
+    /// Sum of the frametimes (ms), how long the captured frames took
+    pub duration_ms: f64,
+    pub avg_fps: f64,
+    /// Mean frametime (ms)
+    pub avg_ms: f64,
+    pub one_percent_low: f64,
+    pub point_one_percent_low: f64,
+    /// Time-weighted 1% low: average FPS of the slowest frames filling 1% of the capture time
+    pub one_percent_low_time: f64,
+    /// Time-weighted 0.1% low
+    pub point_one_percent_low_time: f64,
+    pub min_fps: f64,
+    pub max_fps: f64,
+    /// Median frametime (ms)
+    pub p50_ms: f64,
+    /// 99th percentile frametime (ms), the fastest frame of the 1% low slice
+    pub p99_ms: f64,
+    /// Average absolute difference between consecutive frametimes (ms), 0 with fewer than 2 frames
+    pub jitter_ms: f64,
+}
+
+/// One EMA step from `previous` toward `sample`, `elapsed` after the last one. `alpha` is
+/// the weight per EMA_STEP, so the result doesn't depend on how often it's called.
+fn ema_step(previous: f64, sample: f64, alpha: f64, elapsed: Duration) -> f64 {
+    let steps = elapsed.as_secs_f64() / EMA_STEP.as_secs_f64();
+    let weight = 1.0 - (1.0 - alpha.clamp(0.0, 1.0)).powf(steps);
+    previous + weight * (sample - previous)
+}
+
+/// Compute FPS statistics from frametimes in milliseconds (MsBetweenPresents)
+pub fn compute_stats(samples: &[f64]) -> FrameStats {
     let count = samples.len();
     let sum: f64 = samples.iter().sum();
     
-    if sum == 0.0 {
-        return Some(FpsData { fps: 0.0, one_percent_low: 0.0 });
+    if count == 0 || sum == 0.0 {
+        return FrameStats::default();
     }
 
     // Average Frame Time
     let avg_ms = sum / count as f64;
-    let fps = if avg_ms > 0.0 { 1000.0 / avg_ms } else { 0.0 };
+    let avg_fps = if avg_ms > 0.0 { 1000.0 / avg_ms } else { 0.0 };
+
+    // Jitter, on the samples in arrival order (before sorting)
+    let jitter_ms = if count < 2 {
+        0.0
+    } else {
+        samples.windows(2).map(|pair| (pair[1] - pair[0]).abs()).sum::<f64>() / (count - 1) as f64
+    };
 
     // 1% Low
     // Sort samples to find the 99th percentile (slowest frames)
-    let mut sorted: Vec<f64> = samples.iter().cloned().collect();
+    let mut sorted: Vec<f64> = samples.to_vec();
     sorted.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal)); // Descending order (highest ms first)
     
-    let idx_1_percent = (count as f64 * 0.01).ceil() as usize;
-    // Prendi il valore all'1% peggiore
-    let low_ms = if count > 0 { sorted[idx_1_percent.min(count - 1)] } else { 0.0 };
-    let one_percent_low = if low_ms > 0.0 { 1000.0 / low_ms } else { 0.0 };
+    // The slowest 1% of frames (at least one); the last of them is the 99th percentile frame
+    let worst_1_percent = ((count as f64 * 0.01).ceil() as usize).clamp(1, count);
+    let p99_ms = sorted[worst_1_percent - 1];
 
-    Some(FpsData { fps, one_percent_low })
-}
+    let to_fps = |ms: f64| if ms > 0.0 { 1000.0 / ms } else { 0.0 };
 
-// --- INTERNAL ---
+    // Average FPS over the worst 1% (0.1%) slice, and the time-weighted lows (CapFrameX
+    // definition) from the same descending order. With too few frames the "slice" is a
+    // single outlier, so the average is reported until there's enough data.
+    let (one_percent_low, point_one_percent_low, one_percent_low_time, point_one_percent_low_time) =
+        if count < MIN_LOW_SAMPLES {
+            (avg_fps, avg_fps, avg_fps, avg_fps)
+        } else {
+            (
+                slice_fps(&sorted, 0.01),
+                slice_fps(&sorted, 0.001),
+                time_weighted_low(&sorted, sum, 0.01),
+                time_weighted_low(&sorted, sum, 0.001),
+            )
+        };
 
-fn stop_presentmon() {
-    let mut proc = STATE.running_process.lock();
-    if let Some(mut child) = proc.take() {
-        let _ = child.kill();
-        let _ = child.wait();
+    FrameStats {
+        frames: count,
+        duration_ms: sum,
+        avg_fps,
+        avg_ms,
+        one_percent_low,
+        point_one_percent_low,
+        one_percent_low_time,
+        point_one_percent_low_time,
+        min_fps: to_fps(sorted[0]),
+        max_fps: to_fps(sorted[count - 1]),
+        p50_ms: sorted[count / 2],
+        p99_ms,
+        jitter_ms,
     }
-    STATE.ms_samples.lock().clear();
 }
 
-fn start_presentmon(pid: u32) {
-    stop_presentmon();
-    
-    if pid == 0 {
-        return;
+/// Average FPS over the slowest `fraction` of frames (at least one).
+/// `sorted_desc` must be sorted slowest first and not be empty.
+fn slice_fps(sorted_desc: &[f64], fraction: f64) -> f64 {
+    let frames = ((sorted_desc.len() as f64 * fraction).ceil() as usize).clamp(1, sorted_desc.len());
+    let spent: f64 = sorted_desc[..frames].iter().sum();
+    if spent > 0.0 {
+        frames as f64 * 1000.0 / spent
+    } else {
+        0.0
     }
+}
 
-    log_debug(&format!("Starting PresentMon for PID {}", pid));
+/// Average FPS over the slowest frames that together take `fraction` of the total time.
+/// `sorted_desc` must be sorted slowest first; at least one frame is always included.
+/// Unlike the frame-count percentile this weighs long frames by how long they lasted.
+fn time_weighted_low(sorted_desc: &[f64], total_ms: f64, fraction: f64) -> f64 {
+    let budget = total_ms * fraction;
+    let mut spent = 0.0;
+    let mut frames = 0;
+    for &ms in sorted_desc {
+        spent += ms;
+        frames += 1;
+        if spent >= budget {
+            break;
+        }
+    }
+    if spent > 0.0 {
+        frames as f64 * 1000.0 / spent
+    } else {
+        0.0
+    }
+}
 
-    let pm_path_guard = PRESENTMON_PATH.lock();
-    let pm_executable = pm_path_guard.as_ref()
-        .map(|p| p.as_os_str())
-        .unwrap_or(std::ffi::OsStr::new("PresentMon.exe"));
+// --- INTERNAL ---
 
-    let mut cmd = Command::new(pm_executable);
-    // Argomenti per PresentMon:
-    // -process_id <PID>
-    // -output_stdout : Scrive CSV su stdout
-    // -stop_existing_session : Ferma altre sessioni
-    // -timed 0 : durata infinita (default)
-    cmd.arg("-process_id").arg(pid.to_string())
-       .arg("-output_stdout")
-       .arg("-stop_existing_session");
+/// Copy of PresentMon's raw output, rotated at MAX_RAW_LOG_BYTES (one old file is kept).
+/// Appended to, so PresentMon restarts and target changes keep the earlier capture.
+struct RawLog {
+    path: std::path::PathBuf,
+    file: Option<std::io::BufWriter<std::fs::File>>,
+    written: u64,
+    last_flush: Instant,
+    // CSV header of the current file, repeated at the top of each rotated file
+    header: Option<String>,
+}
 
-    // Nascondi finestra console se possibile
-    cmd.stdout(Stdio::piped());
-    
-    #[cfg(windows)]
-    {
-        use std::os::windows::process::CommandExt;
-        const CREATE_NO_WINDOW: u32 = 0x08000000;
-        cmd.creation_flags(CREATE_NO_WINDOW);
+impl RawLog {
+    fn open() -> Option<Self> {
+        let mut path = dirs::data_local_dir()?;
+        path.push("EasyFPS");
+        std::fs::create_dir_all(&path).ok()?;
+        path.push("presentmon_raw.csv");
+        let file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(|e| crate::log::warn(&format!("Cannot open raw PresentMon log: {}", e)))
+            .ok()?;
+        let written = file.metadata().map(|m| m.len()).unwrap_or(0);
+        // Header of an earlier session, to tell whether its columns can be appended to
+        let header = std::fs::File::open(&path)
+            .ok()
+            .and_then(|existing| BufReader::new(existing).lines().next())
+            .and_then(|line| line.ok());
+        crate::log::info(&format!("Saving raw PresentMon output to {:?}", path));
+        Some(Self {
+            path,
+            file: Some(std::io::BufWriter::new(file)),
+            written,
+            last_flush: Instant::now(),
+            header,
+        })
     }
 
-    match cmd.spawn() {
-        Ok(mut child) => {
-            if let Some(stdout) = child.stdout.take() {
-                std::thread::spawn(move || {
-                    let reader = BufReader::new(stdout);
-                    let mut lines = reader.lines();
-                    
-                    // Cerca l'header per trovare l'indice della colonna "MsBetweenPresents"
-                    let mut ms_idx = usize::MAX;
-                    
-                    // Leggi finché non trovi l'header
-                    while let Some(Ok(line)) = lines.next() {
-                        if line.starts_with("Application") || line.contains("MsBetweenPresents") {
-                            let cols: Vec<&str> = line.split(',').collect();
-                            if let Some(idx) = cols.iter().position(|&c| c.trim() == "MsBetweenPresents") {
-                                ms_idx = idx;
-                                log_debug(&format!("Found MsBetweenPresents at col {}", ms_idx));
-                                break;
-                            }
-                        }
-                    }
-                    
-                    if ms_idx == usize::MAX {
-                        log_debug("Could not find MsBetweenPresents header");
-                        return;
-                    }
+    /// Start this session's rows. The header is written only at the top of the file: a file
+    /// with other columns (another PresentMon flavor) is rotated, so each file stays one CSV.
+    fn write_header(&mut self, header: &str) {
+        if self.written == 0 {
+            self.header = Some(header.to_string());
+            self.write_line(header);
+        } else if self.header.as_deref() != Some(header) {
+            self.header = Some(header.to_string());
+            if let Err(e) = self.rotate() {
+                crate::log::warn(&format!("Raw PresentMon log rotation failed, stopping it: {}", e));
+                self.file = None;
+            }
+        }
+    }
 
-                    // Leggi i dati
-                    while let Some(Ok(line)) = lines.next() {
-                         // Controlla se dobbiamo fermarci
-                         if !STATE.is_running.load(Ordering::SeqCst) {
-                             break;
-                         }
-
-                         let cols: Vec<&str> = line.split(',').collect();
-                         if cols.len() > ms_idx {
-                             if let Ok(ms) = cols[ms_idx].trim().parse::<f64>() {
-                                 let mut samples = STATE.ms_samples.lock();
-                                 samples.push_back(ms);
-                                 if samples.len() > MAX_SAMPLES {
-                                     samples.pop_front();
-                                 }
-                             }
-                         }
-                    }
-                });
+    fn write_line(&mut self, line: &str) {
+        if self.file.is_none() {
+            return;
+        }
+        if self.written >= MAX_RAW_LOG_BYTES {
+            if let Err(e) = self.rotate() {
+                crate::log::warn(&format!("Raw PresentMon log rotation failed, stopping it: {}", e));
+                self.file = None;
             }
-            
-            *STATE.running_process.lock() = Some(child);
         }
-        Err(e) => {
-            log_debug(&format!("Failed to start PresentMon: {}", e));
+        if let Some(file) = self.file.as_mut() {
+            if writeln!(file, "{}", line).is_ok() {
+                self.written += line.len() as u64 + 1;
+            }
+            if self.last_flush.elapsed() >= RAW_LOG_FLUSH {
+                let _ = file.flush();
+                self.last_flush = Instant::now();
+            }
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        // Close the file first: Windows can't rename a file that's still open
+        if let Some(mut file) = self.file.take() {
+            file.flush()?;
+        }
+        std::fs::rename(&self.path, self.path.with_extension("old.csv"))?;
+        self.file = Some(std::io::BufWriter::new(std::fs::File::create(&self.path)?));
+        self.written = 0;
+        if let Some(header) = self.header.clone() {
+            self.write_line(&header);
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn compute_stats_single_frame() {
+        let stats = compute_stats(&[16.0]);
+        assert_eq!(stats.frames, 1);
+        assert_close(stats.avg_fps, 62.5);
+        assert_close(stats.min_fps, 62.5);
+        assert_close(stats.max_fps, 62.5);
+        assert_close(stats.p50_ms, 16.0);
+        assert_close(stats.p99_ms, 16.0);
+        assert_close(stats.jitter_ms, 0.0);
+        // Too few frames for lows: they report the average
+        assert_close(stats.one_percent_low, stats.avg_fps);
+        assert_close(stats.point_one_percent_low, stats.avg_fps);
+    }
+
+    #[test]
+    fn compute_stats_hundred_frames() {
+        let mut samples = vec![10.0; 99];
+        samples.push(20.0);
+        let stats = compute_stats(&samples);
+        assert_eq!(stats.frames, 100);
+        assert_close(stats.avg_ms, 10.1);
+        assert_close(stats.min_fps, 50.0);
+        assert_close(stats.max_fps, 100.0);
+        // Still below MIN_LOW_SAMPLES
+        assert_close(stats.one_percent_low, stats.avg_fps);
+        assert_close(stats.one_percent_low_time, stats.avg_fps);
+    }
+
+    #[test]
+    fn compute_stats_full_buffer() {
+        // 1980 frames at 10 ms and 20 at 40 ms: the slowest 1% is exactly the 40 ms frames
+        let mut samples = vec![10.0; 1980];
+        samples.extend([40.0; 20]);
+        let stats = compute_stats(&samples);
+        assert_eq!(stats.frames, DEFAULT_SAMPLES);
+        assert_close(stats.avg_ms, 10.3);
+        assert_close(stats.one_percent_low, 25.0);
+        assert_close(stats.point_one_percent_low, 25.0);
+        assert_close(stats.p50_ms, 10.0);
+        assert_close(stats.p99_ms, 40.0);
+    }
+
+    #[test]
+    fn time_weighted_low_weighs_long_frames() {
+        // 1990 frames at 10 ms and 10 hitches of 100 ms: the hitches are 0.5% of the frames
+        // but 4.8% of the time
+        let mut samples = vec![10.0; 1990];
+        samples.extend([100.0; 10]);
+        let stats = compute_stats(&samples);
+        // Frame count: the slowest 20 frames, 10 hitches and 10 normal ones
+        assert_close(stats.one_percent_low, 20.0 * 1000.0 / 1100.0);
+        // Time weighted: 1% of 20.9 s is filled by hitches alone
+        assert_close(stats.one_percent_low_time, 10.0);
+        assert!(stats.one_percent_low_time < stats.one_percent_low);
+    }
+
+    #[test]
+    fn count_stutters_counts_spikes_only() {
+        // Spikes above STUTTER_FACTOR x the 10 ms median; exactly 2x isn't one
+        let samples = [10.0, 11.0, 35.0, 9.0, 20.0, 10.0, 21.0, 60.0, 10.0];
+        assert_eq!(count_stutters(&samples, 10.0), 3);
+        assert_eq!(count_stutters(&[10.0; 50], 10.0), 0);
+        assert_eq!(count_stutters(&samples, 0.0), 0);
+    }
+
+    #[test]
+    fn lows_are_ordered_on_a_synthetic_distribution() {
+        // 10-20 ms spread, 18 slow frames and 2 hitches
+        let mut samples: Vec<f64> = (0..1980).map(|i| 10.0 + (i % 100) as f64 * 0.1).collect();
+        samples.extend([40.0; 18]);
+        samples.extend([80.0; 2]);
+        let stats = compute_stats(&samples);
+        assert_close(stats.point_one_percent_low, 12.5);
+        assert_close(stats.one_percent_low, 20.0 * 1000.0 / 880.0);
+        assert!(stats.point_one_percent_low < stats.one_percent_low);
+        assert!(stats.one_percent_low < stats.avg_fps);
+    }
+
+    #[test]
+    fn window_slice_keeps_the_latest_frames() {
+        let samples = [30.0, 10.0, 10.0, 10.0, 10.0];
+        assert_eq!(window_slice(&samples, 25), &[10.0, 10.0]);
+        assert_eq!(window_slice(&samples, 40), &[10.0; 4]);
+        // Never empty while there are samples, 0 = everything
+        assert_eq!(window_slice(&samples, 5), &[10.0]);
+        assert_eq!(window_slice(&samples, 0), &samples);
+        assert!(window_slice(&[], 1000).is_empty());
+    }
+
+    #[test]
+    fn ema_converges_toward_a_steady_input() {
+        let mut fps = 0.0;
+        let mut previous_gap = f64::MAX;
+        for _ in 0..60 {
+            fps = ema_step(fps, 100.0, 0.2, EMA_STEP);
+            let gap = 100.0 - fps;
+            assert!(gap >= 0.0 && gap < previous_gap);
+            previous_gap = gap;
         }
+        assert!(previous_gap < 0.01);
+
+        // Same result whether it's called once per step or twice as often
+        let once = ema_step(0.0, 100.0, 0.2, EMA_STEP);
+        let half = ema_step(ema_step(0.0, 100.0, 0.2, EMA_STEP / 2), 100.0, 0.2, EMA_STEP / 2);
+        assert_close(once, half);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn histogram_buckets_a_known_sample_set() {
+        let buckets = histogram(&[1.0, 4.9, 5.0, 12.0, 14.5], 5.0);
+        assert_eq!(buckets, vec![(0.0, 2), (5.0, 1), (10.0, 2)]);
+
+        // Empty ranges below the slowest frame are kept
+        let buckets = histogram(&[2.0, 17.0], 5.0);
+        assert_eq!(buckets, vec![(0.0, 1), (5.0, 0), (10.0, 0), (15.0, 1)]);
+
+        assert!(histogram(&[10.0], 0.0).is_empty());
+        assert!(histogram(&[10.0], -1.0).is_empty());
+        assert!(histogram(&[], 5.0).is_empty());
+    }
+
+    #[test]
+    fn histogram_puts_the_slowest_frames_in_the_last_bucket() {
+        let last = (HISTOGRAM_MAX_BUCKETS - 1) as f64;
+        let buckets = histogram(&[0.5, last + 0.5, 2000.0, 1e9], 1.0);
+        assert_eq!(buckets.len(), HISTOGRAM_MAX_BUCKETS);
+        assert_eq!(buckets[0], (0.0, 1));
+        assert_eq!(buckets[HISTOGRAM_MAX_BUCKETS - 1], (last, 3));
+        assert_eq!(buckets.iter().map(|&(_, count)| count).sum::<u32>(), 4);
+    }
+}
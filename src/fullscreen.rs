@@ -1,17 +1,80 @@
-use windows::Win32::Foundation::{HWND, RECT};
+use crate::settings::FullscreenPolicy;
+use std::sync::atomic::{AtomicBool, Ordering};
+use windows::Win32::Foundation::{BOOL, HWND, LPARAM, RECT};
 use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::Graphics::Gdi::{
+    EnumDisplaySettingsW, GetMonitorInfoW, MonitorFromWindow, DEVMODEW, ENUM_CURRENT_SETTINGS,
+    MONITORINFO, MONITORINFOEXW, MONITOR_DEFAULTTONEAREST,
+};
+use windows::Win32::System::Threading::GetCurrentProcessId;
 use windows::Win32::UI::WindowsAndMessaging::{
-    GetForegroundWindow, GetWindowLongW, GetWindowRect, GetWindowThreadProcessId,
-    IsWindowVisible, GWL_EXSTYLE, GWL_STYLE, WS_EX_TOOLWINDOW,
+    EnumWindows, GetClassNameW, GetForegroundWindow, GetShellWindow, GetWindowLongW,
+    GetWindowRect, GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, GWL_EXSTYLE, GWL_STYLE,
+    MONITORINFOF_PRIMARY, WS_EX_TOOLWINDOW, WS_POPUP,
 };
 
+// Share of the monitor a borderless window must cover to count as fullscreen
+const BORDERLESS_MIN_COVERAGE: f64 = 0.9;
+
+// Polls without a fullscreen reading before FullscreenHold lets the last app go. At the main
+// loop's 16 ms poll that's about half a second, enough to ride out a display mode switch.
+const FULLSCREEN_MISS_LIMIT: u32 = 30;
+
+// Also accept large borderless windows, see is_window_fullscreen
+static DETECT_BORDERLESS: AtomicBool = AtomicBool::new(false);
+
+/// Apply the `detect_borderless` setting
+pub fn set_detect_borderless(enabled: bool) {
+    DETECT_BORDERLESS.store(enabled, Ordering::Relaxed);
+}
+
+/// Bounds of a monitor, in virtual screen coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisplayRect {
+    pub left: i32,
+    pub top: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
 /// Information about the current fullscreen application
 #[allow(dead_code)]
+#[derive(Clone)]
 pub struct FullscreenApp {
      pub hwnd: isize,
      pub process_id: u32,
      pub width: i32,
      pub height: i32,
+     pub on_primary: bool,
+     /// Monitor the window is on, None if it couldn't be resolved
+     pub display: Option<DisplayRect>,
+}
+
+/// Keeps the last fullscreen app through brief non-fullscreen readings, e.g. a DX12 game
+/// reporting a window smaller than the monitor while it switches display modes.
+/// A minimized or closed window is let go at once, so alt-tabbing out still hides the overlay.
+#[derive(Default)]
+pub struct FullscreenHold {
+    last: Option<FullscreenApp>,
+    misses: u32,
+}
+
+impl FullscreenHold {
+    /// Feed this poll's app, returns the app to use
+    pub fn update(&mut self, app: Option<FullscreenApp>) -> Option<FullscreenApp> {
+        if let Some(app) = app {
+            self.misses = 0;
+            self.last = Some(app.clone());
+            return Some(app);
+        }
+        let hwnd = HWND(self.last.as_ref()?.hwnd);
+        self.misses += 1;
+        let gone = unsafe { !IsWindow(hwnd).as_bool() || IsIconic(hwnd).as_bool() };
+        if gone || self.misses > FULLSCREEN_MISS_LIMIT {
+            self.last = None;
+        }
+        self.last.clone()
+    }
 }
 
 /// Check if there's a fullscreen application running
@@ -21,72 +84,193 @@ pub fn get_fullscreen_app() -> Option<FullscreenApp> {
         if hwnd.0 == 0 {
             return None;
         }
+        check_window(hwnd, true)
+    }
+}
 
-        // Check if window is visible
-        if !IsWindowVisible(hwnd).as_bool() {
+/// The foreground window's app, fullscreen or not ("show everywhere" mode).
+/// The desktop and EasyFPS's own windows (settings, summary) don't count.
+pub fn get_foreground_app() -> Option<FullscreenApp> {
+    unsafe {
+        let hwnd = GetForegroundWindow();
+        if hwnd.0 == 0 || is_shell_window(hwnd) {
             return None;
         }
+        let app = check_window(hwnd, false)?;
+        (app.process_id != GetCurrentProcessId()).then_some(app)
+    }
+}
 
-        // Check if window is cloaked (virtual desktop)
-        let mut cloaked: u32 = 0;
-        let _ = DwmGetWindowAttribute(
-            hwnd,
-            DWMWA_CLOAKED,
-            &mut cloaked as *mut _ as *mut _,
-            std::mem::size_of::<u32>() as u32,
-        );
-        if cloaked != 0 {
-            return None;
+/// Pick the fullscreen app to monitor according to the multi-monitor policy
+pub fn select_fullscreen_app(policy: FullscreenPolicy) -> Option<FullscreenApp> {
+    match policy {
+        FullscreenPolicy::Foreground => get_fullscreen_app(),
+        FullscreenPolicy::Largest => get_all_fullscreen_apps()
+            .into_iter()
+            .max_by_key(|app| app.width as i64 * app.height as i64),
+        FullscreenPolicy::PrimaryMonitor => {
+            // Prefer the foreground window if it's on the primary monitor
+            match get_fullscreen_app() {
+                Some(app) if app.on_primary => Some(app),
+                _ => get_all_fullscreen_apps().into_iter().find(|app| app.on_primary),
+            }
         }
+    }
+}
 
-        // Get window style
-        let style = GetWindowLongW(hwnd, GWL_STYLE) as u32;
-        let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
-
-        // Skip tool windows
-        if (ex_style & WS_EX_TOOLWINDOW.0) != 0 {
-            return None;
+/// Enumerate every top-level fullscreen window, across all monitors
+pub fn get_all_fullscreen_apps() -> Vec<FullscreenApp> {
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let apps = &mut *(lparam.0 as *mut Vec<FullscreenApp>);
+        if !is_shell_window(hwnd) && !IsIconic(hwnd).as_bool() {
+            if let Some(app) = check_window(hwnd, true) {
+                apps.push(app);
+            }
         }
+        BOOL(1) // continue enumeration
+    }
 
-        // Get window rect
-        let mut rect = RECT::default();
-        if GetWindowRect(hwnd, &mut rect).is_err() {
-            return None;
-        }
+    let mut apps: Vec<FullscreenApp> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(Some(enum_proc), LPARAM(&mut apps as *mut _ as isize));
+    }
+    apps
+}
 
-        let window_width = rect.right - rect.left;
-        let window_height = rect.bottom - rect.top;
+/// The desktop (Progman/WorkerW) also covers the whole screen, skip it
+unsafe fn is_shell_window(hwnd: HWND) -> bool {
+    if hwnd == GetShellWindow() {
+        return true;
+    }
+    let mut class = [0u16; 64];
+    let len = GetClassNameW(hwnd, &mut class) as usize;
+    let class = String::from_utf16_lossy(&class[..len]);
+    class == "Progman" || class == "WorkerW"
+}
 
-        // Get monitor info for the window
-        let (screen_width, screen_height) = get_primary_monitor_size();
+/// Return the app info if `hwnd` is a visible non-tool window, and fullscreen when required
+unsafe fn check_window(hwnd: HWND, require_fullscreen: bool) -> Option<FullscreenApp> {
+    // Check if window is visible
+    if !IsWindowVisible(hwnd).as_bool() {
+        return None;
+    }
 
-        // Check if the window covers the entire screen
-        let is_fullscreen = is_window_fullscreen(hwnd, &rect, screen_width, screen_height, style);
+    // Check if window is cloaked (virtual desktop)
+    let mut cloaked: u32 = 0;
+    let _ = DwmGetWindowAttribute(
+        hwnd,
+        DWMWA_CLOAKED,
+        &mut cloaked as *mut _ as *mut _,
+        std::mem::size_of::<u32>() as u32,
+    );
+    if cloaked != 0 {
+        return None;
+    }
 
-        if !is_fullscreen {
-            return None;
+    // Get window style
+    let style = GetWindowLongW(hwnd, GWL_STYLE) as u32;
+    let ex_style = GetWindowLongW(hwnd, GWL_EXSTYLE) as u32;
+
+    // Skip tool windows
+    if (ex_style & WS_EX_TOOLWINDOW.0) != 0 {
+        return None;
+    }
+
+    // Get window rect
+    let mut rect = RECT::default();
+    if GetWindowRect(hwnd, &mut rect).is_err() {
+        return None;
+    }
+
+    let window_width = rect.right - rect.left;
+    let window_height = rect.bottom - rect.top;
+
+    // Compare against the monitor the window is on, not the primary one
+    // (falling back to the primary monitor if it can't be resolved)
+    let monitor = window_monitor(hwnd);
+    let display = monitor.map(|info| DisplayRect {
+        left: info.rcMonitor.left,
+        top: info.rcMonitor.top,
+        width: info.rcMonitor.right - info.rcMonitor.left,
+        height: info.rcMonitor.bottom - info.rcMonitor.top,
+    });
+    let screen = match monitor {
+        Some(info) => info.rcMonitor,
+        None => {
+            let (width, height) = get_primary_monitor_size();
+            RECT { left: 0, top: 0, right: width, bottom: height }
         }
+    };
 
-        // Get process ID
-        let mut process_id: u32 = 0;
-        GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+    // Check if the window covers the entire screen
+    let is_fullscreen = is_window_fullscreen(&rect, &screen, style);
 
-        Some(FullscreenApp {
-            hwnd: hwnd.0 as isize,
-            process_id,
-            width: window_width,
-            height: window_height,
-        })
+    if require_fullscreen && !is_fullscreen {
+        return None;
+    }
+
+    // Get process ID
+    let mut process_id: u32 = 0;
+    GetWindowThreadProcessId(hwnd, Some(&mut process_id));
+
+    Some(FullscreenApp {
+        hwnd: hwnd.0,
+        process_id,
+        width: window_width,
+        height: window_height,
+        on_primary: monitor.is_none_or(|info| (info.dwFlags & MONITORINFOF_PRIMARY) != 0),
+        display,
+    })
+}
+
+/// Info for the monitor the window is (mostly) on
+unsafe fn window_monitor(hwnd: HWND) -> Option<MONITORINFO> {
+    let monitor = MonitorFromWindow(hwnd, MONITOR_DEFAULTTONEAREST);
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    GetMonitorInfoW(monitor, &mut info).as_bool().then_some(info)
+}
+
+/// Refresh rate (Hz) of the display mode of the monitor `hwnd` is on.
+/// Queries the driver, so callers should cache it per window and monitor.
+pub fn refresh_rate(hwnd: isize) -> Option<u32> {
+    unsafe {
+        let monitor = MonitorFromWindow(HWND(hwnd), MONITOR_DEFAULTTONEAREST);
+        let mut info = MONITORINFOEXW::default();
+        info.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if !GetMonitorInfoW(monitor, &mut info.monitorInfo).as_bool() {
+            return None;
+        }
+        let mut mode = DEVMODEW {
+            dmSize: std::mem::size_of::<DEVMODEW>() as u16,
+            ..Default::default()
+        };
+        if !EnumDisplaySettingsW(
+            windows::core::PCWSTR(info.szDevice.as_ptr()),
+            ENUM_CURRENT_SETTINGS,
+            &mut mode,
+        )
+        .as_bool()
+        {
+            return None;
+        }
+        // 0 and 1 mean "hardware default"
+        (mode.dmDisplayFrequency > 1).then_some(mode.dmDisplayFrequency)
     }
 }
 
-/// Check if a window is fullscreen
-fn is_window_fullscreen(_hwnd: HWND, rect: &RECT, screen_width: i32, screen_height: i32, _style: u32) -> bool {
+/// Check if a window is fullscreen on the monitor `screen`
+fn is_window_fullscreen(rect: &RECT, screen: &RECT, style: u32) -> bool {
     let window_width = rect.right - rect.left;
     let window_height = rect.bottom - rect.top;
+    let screen_width = screen.right - screen.left;
+    let screen_height = screen.bottom - screen.top;
 
     // Robust Check: If the window is roughly the size of the screen (or bigger), it's fullscreen.
-    // We allow a margin of error (e.g. 30 pixels) for borders or weird scaling.
+    // We allow a margin of error (e.g. 30 pixels) for borders or weird scaling. It also covers
+    // exclusive-fullscreen games that briefly report e.g. 1920x1079 during a mode switch.
     let width_diff = (window_width - screen_width).abs();
     let height_diff = (window_height - screen_height).abs();
 
@@ -99,6 +283,19 @@ fn is_window_fullscreen(_hwnd: HWND, rect: &RECT, screen_width: i32, screen_heig
         return true;
     }
 
+    // Borderless windowed games that aren't quite maximized (or leave the taskbar visible)
+    // miss the 30px margin. WS_POPUP means no caption or frame, which normal desktop apps
+    // nearly always have, so "popup covering most of the monitor" is a game-like window.
+    // Opt-in, since some launchers and media players are large popups too.
+    if DETECT_BORDERLESS.load(Ordering::Relaxed) && (style & WS_POPUP.0) != 0 {
+        let covered_width = (rect.right.min(screen.right) - rect.left.max(screen.left)).max(0) as f64;
+        let covered_height = (rect.bottom.min(screen.bottom) - rect.top.max(screen.top)).max(0) as f64;
+        let screen_area = screen_width as f64 * screen_height as f64;
+        if screen_area > 0.0 && covered_width * covered_height / screen_area >= BORDERLESS_MIN_COVERAGE {
+            return true;
+        }
+    }
+
     false
 }
 
@@ -114,7 +311,6 @@ fn get_primary_monitor_size() -> (i32, i32) {
 }
 
 /// Get the name of a process by its ID
-#[allow(dead_code)]
 pub fn get_process_name(process_id: u32) -> Option<String> {
     use windows::Win32::Foundation::CloseHandle;
     use windows::Win32::System::ProcessStatus::GetModuleBaseNameW;
@@ -140,3 +336,77 @@ pub fn get_process_name(process_id: u32) -> Option<String> {
         None
     }
 }
+
+/// The largest visible top-level window of `process_id`, fullscreen or not
+/// (for a process picked by hand with `target_override`)
+pub fn get_process_app(process_id: u32) -> Option<FullscreenApp> {
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let (process_id, best) = &mut *(lparam.0 as *mut (u32, Option<FullscreenApp>));
+        if !IsIconic(hwnd).as_bool() {
+            if let Some(app) = check_window(hwnd, false) {
+                let area = |app: &FullscreenApp| app.width as i64 * app.height as i64;
+                if app.process_id == *process_id && best.as_ref().is_none_or(|b| area(&app) > area(b)) {
+                    *best = Some(app);
+                }
+            }
+        }
+        BOOL(1) // continue enumeration
+    }
+
+    let mut search: (u32, Option<FullscreenApp>) = (process_id, None);
+    unsafe {
+        let _ = EnumWindows(Some(enum_proc), LPARAM(&mut search as *mut _ as isize));
+    }
+    search.1
+}
+
+/// Processes with a visible top-level window, one per executable name, sorted by name.
+/// The desktop and EasyFPS itself are left out.
+pub fn list_windowed_processes() -> Vec<(u32, String)> {
+    unsafe extern "system" fn enum_proc(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let pids = &mut *(lparam.0 as *mut Vec<u32>);
+        if !is_shell_window(hwnd) {
+            if let Some(app) = check_window(hwnd, false) {
+                if app.process_id != GetCurrentProcessId() && !pids.contains(&app.process_id) {
+                    pids.push(app.process_id);
+                }
+            }
+        }
+        BOOL(1) // continue enumeration
+    }
+
+    let mut pids: Vec<u32> = Vec::new();
+    unsafe {
+        let _ = EnumWindows(Some(enum_proc), LPARAM(&mut pids as *mut _ as isize));
+    }
+    let mut processes: Vec<(u32, String)> = pids
+        .into_iter()
+        .filter_map(|pid| get_process_name(pid).map(|name| (pid, name)))
+        .collect();
+    processes.sort_by_key(|(_, name)| name.to_lowercase());
+    processes.dedup_by(|a, b| a.1.eq_ignore_ascii_case(&b.1));
+    processes
+}
+
+/// PID of a running process called `name` (case-insensitive). One with a window is
+/// preferred, so a launcher's background helper of the same name isn't picked.
+pub fn find_process_id(name: &str) -> Option<u32> {
+    use windows::Win32::System::ProcessStatus::EnumProcesses;
+
+    if let Some((pid, _)) = list_windowed_processes()
+        .into_iter()
+        .find(|(_, n)| n.eq_ignore_ascii_case(name))
+    {
+        return Some(pid);
+    }
+
+    let mut pids = vec![0u32; 4096];
+    let mut needed: u32 = 0;
+    unsafe {
+        EnumProcesses(pids.as_mut_ptr(), (pids.len() * 4) as u32, &mut needed).ok()?;
+    }
+    pids.truncate(needed as usize / 4);
+    pids.into_iter()
+        .filter(|&pid| pid != 0)
+        .find(|&pid| get_process_name(pid).is_some_and(|n| n.eq_ignore_ascii_case(name)))
+}
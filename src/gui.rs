@@ -1,4 +1,6 @@
-use crate::settings::{FpsColor, OverlayPosition, OverlaySize, Settings};
+use crate::i18n::{tr, Lang, Text};
+use crate::fps_capture::FrameStats;
+use crate::settings::{FpsColor, GpuSelection, GuiTheme, NumberLocale, FONT_CHOICES, FONT_WEIGHTS, OverlayPosition, OverlaySize, Profiles, Settings};
 use std::sync::atomic::{AtomicBool, Ordering};
 use windows::core::PCWSTR;
 use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, WPARAM};
@@ -16,14 +18,16 @@ const TBM_GETPOS: u32 = WM_USER;
 const TBM_SETPOS: u32 = WM_USER + 5;
 const TBM_SETRANGEMIN: u32 = WM_USER + 7;
 const TBM_SETRANGEMAX: u32 = WM_USER + 8;
+const CB_ADDSTRING: u32 = 0x0143;
+const CB_GETCURSEL: u32 = 0x0147;
+const CB_SETCURSEL: u32 = 0x014E;
+const CBS_DROPDOWNLIST: u32 = 0x0003;
 
 static GUI_OPEN: AtomicBool = AtomicBool::new(false);
 
 // Control IDs
 const ID_POS_RIGHT: i32 = 101;
 const ID_POS_LEFT: i32 = 102;
-const ID_COLOR_WHITE: i32 = 103;
-const ID_COLOR_GREEN: i32 = 104;
 const ID_SIZE_SMALL: i32 = 105;
 const ID_SIZE_MEDIUM: i32 = 106;
 const ID_SIZE_LARGE: i32 = 107;
@@ -33,6 +37,38 @@ const ID_SHOW_CPU: i32 = 112;
 const ID_SHOW_GPU: i32 = 113;
 const ID_OPACITY_SLIDER: i32 = 114;
 const ID_OPACITY_VAL: i32 = 115;
+const ID_FPS_BOTTOM: i32 = 116;
+const ID_TRACK_PLAYTIME: i32 = 117;
+const ID_ALERT_ONLY: i32 = 118;
+const ID_SHOW_POINT1LOW: i32 = 119;
+const ID_POS_BOTTOM_RIGHT: i32 = 120;
+const ID_POS_BOTTOM_LEFT: i32 = 121;
+const ID_COLOR_PICK: i32 = 122;
+const ID_COLOR_SWATCH: i32 = 123;
+const ID_SHOW_RAM: i32 = 124;
+const ID_FRAMETIME_GRAPH: i32 = 125;
+const ID_SAVE_PROFILE: i32 = 126;
+const ID_PRESENTMON_BROWSE: i32 = 127;
+const ID_PRESENTMON_PATH: i32 = 128;
+const ID_PRESENTMON_RESET: i32 = 129;
+const ID_LANGUAGE: i32 = 130;
+const ID_SHOW_GPU_TEMP: i32 = 131;
+const ID_GPU_SELECT: i32 = 132;
+const ID_RESET: i32 = 133;
+const ID_STATS_COLOR_PICK: i32 = 134;
+const ID_STATS_COLOR_SWATCH: i32 = 135;
+const ID_EXPORT: i32 = 136;
+const ID_IMPORT: i32 = 137;
+const ID_FONT: i32 = 138;
+const ID_MONOSPACE_DIGITS: i32 = 139;
+const ID_CLICKTHROUGH: i32 = 140;
+const ID_FONT_WEIGHT: i32 = 141;
+const ID_FONT_ITALIC: i32 = 142;
+const ID_POS_PROPORTIONAL: i32 = 143;
+const ID_EXCLUDE_FROM_CAPTURE: i32 = 144;
+const ID_FPS_DECIMALS: i32 = 145;
+const ID_USAGE_DECIMALS: i32 = 146;
+const ID_SHOW_PLAYTIME: i32 = 147;
 const ID_SAVE: i32 = 110;
 const ID_CANCEL: i32 = 111;
 
@@ -43,38 +79,95 @@ const ID_CLOSE_BTN: i32 = 201;
 // Button check states
 const BST_CHECKED_VAL: usize = 1;
 
-// Colors (BGR format per Windows)
-const COL_BLACK: u32 = 0x000000;
-const COL_DARK_GRAY: u32 = 0x2D2D2D; 
-const COL_RED: u32 = 0x0000FF;       
-const COL_WHITE: u32 = 0xFFFFFF;
+/// Window colors (BGR format per Windows)
+#[derive(Clone, Copy)]
+struct Theme {
+    background: u32,
+    /// Title bar
+    accent: u32,
+    text: u32,
+    close_button: u32,
+}
+
+impl Theme {
+    const DARK: Theme = Theme { background: 0x000000, accent: 0x2D2D2D, text: 0xFFFFFF, close_button: 0x0000FF };
+    const LIGHT: Theme = Theme { background: 0xF3F3F3, accent: 0xDADADA, text: 0x1A1A1A, close_button: 0x2311E8 };
+
+    fn from_setting(theme: GuiTheme) -> Self {
+        match theme {
+            GuiTheme::Dark => Self::DARK,
+            GuiTheme::Light => Self::LIGHT,
+        }
+    }
+}
 
 // Definiamo manualmente le costanti mancanti per sicurezza
 const SS_CENTER: u32 = 0x1;
 const SS_NOTIFY: u32 = 0x100;
 const SS_CENTERIMAGE: u32 = 0x200;
+const SS_PATHELLIPSIS: u32 = 0x8000;
 
 thread_local! {
     static CURRENT_SETTINGS: std::cell::RefCell<Option<Settings>> = std::cell::RefCell::new(None);
-    static SAVE_CALLBACK: std::cell::RefCell<Option<Box<dyn FnOnce(Settings) + Send>>> = std::cell::RefCell::new(None);
-    // Correzione: Usiamo std::ptr::null_mut() invece di 0
-    static BRUSH_BLACK: std::cell::RefCell<HBRUSH> = std::cell::RefCell::new(HBRUSH(0));
-    static BRUSH_DARK_GRAY: std::cell::RefCell<HBRUSH> = std::cell::RefCell::new(HBRUSH(0));
-    static BRUSH_RED: std::cell::RefCell<HBRUSH> = std::cell::RefCell::new(HBRUSH(0));
+    static EVENT_CALLBACK: std::cell::RefCell<Option<EventCallback>> = const { std::cell::RefCell::new(None) };
+    // Executable of the running game, offered as a profile target
+    static CURRENT_GAME: std::cell::RefCell<Option<String>> = const { std::cell::RefCell::new(None) };
+    // This thread's window colors and their brushes, see create_theme_brushes
+    static THEME: std::cell::Cell<Theme> = const { std::cell::Cell::new(Theme::DARK) };
+    static BRUSH_BACKGROUND: std::cell::RefCell<HBRUSH> = const { std::cell::RefCell::new(HBRUSH(0)) };
+    static BRUSH_ACCENT: std::cell::RefCell<HBRUSH> = const { std::cell::RefCell::new(HBRUSH(0)) };
+    static BRUSH_CLOSE: std::cell::RefCell<HBRUSH> = const { std::cell::RefCell::new(HBRUSH(0)) };
+    // Color chosen in the picker (saved with the other settings) and its preview brush
+    static PICKED_COLOR: std::cell::Cell<FpsColor> = const { std::cell::Cell::new(FpsColor::White) };
+    // Same for the stats values (None = follow the FPS color)
+    static PICKED_STATS_COLOR: std::cell::Cell<Option<FpsColor>> = const { std::cell::Cell::new(None) };
+    // PresentMon.exe chosen with "Browse..." (None = automatic search)
+    static PICKED_PRESENTMON: std::cell::RefCell<Option<std::path::PathBuf>> = const { std::cell::RefCell::new(None) };
+    static BRUSH_SWATCH: std::cell::RefCell<HBRUSH> = const { std::cell::RefCell::new(HBRUSH(0)) };
+    static BRUSH_STATS_SWATCH: std::cell::RefCell<HBRUSH> = const { std::cell::RefCell::new(HBRUSH(0)) };
+    // (LUID key, name) of the adapters in the GPU dropdown, after its "all GPUs" entry
+    static GPU_CHOICES: std::cell::RefCell<Vec<(String, String)>> = const { std::cell::RefCell::new(Vec::new()) };
+    // Font names in the font dropdown, in order
+    static FONT_LIST: std::cell::RefCell<Vec<String>> = const { std::cell::RefCell::new(Vec::new()) };
+    // DPI the window is laid out for (96 = 100% scaling)
+    static GUI_DPI: std::cell::Cell<u32> = const { std::cell::Cell::new(96) };
+}
+
+/// A coordinate of the 96 DPI layout, scaled to the window's DPI
+fn px(value: i32) -> i32 {
+    value * GUI_DPI.with(|d| d.get()) as i32 / 96
 }
 
 pub fn is_open() -> bool {
     GUI_OPEN.load(Ordering::SeqCst)
 }
 
-pub fn open(settings: Settings, on_save: impl FnOnce(Settings) + Send + 'static) {
+/// What happened in the settings window
+pub enum GuiEvent {
+    /// The controls changed: apply for a live preview, but don't persist
+    Preview(Settings),
+    /// New global settings (already written to settings.json)
+    Saved(Settings),
+    /// New profile for a game (already written to profiles.json)
+    ProfileSaved { game: String, settings: Settings },
+    /// Closed without saving: undo the preview
+    Discarded,
+}
+
+type EventCallback = Box<dyn FnMut(GuiEvent) + Send>;
+
+/// Open the settings window. With `game` set, the current settings can also be saved
+/// as that game's profile. `on_event` receives previews while the window is open, then
+/// exactly one of `Saved`, `ProfileSaved` or `Discarded`.
+pub fn open(settings: Settings, game: Option<String>, on_event: impl FnMut(GuiEvent) + Send + 'static) {
     if GUI_OPEN.swap(true, Ordering::SeqCst) {
         return;
     }
     
     std::thread::spawn(move || {
         CURRENT_SETTINGS.with(|s| *s.borrow_mut() = Some(settings));
-        SAVE_CALLBACK.with(|c| *c.borrow_mut() = Some(Box::new(on_save)));
+        CURRENT_GAME.with(|g| *g.borrow_mut() = game);
+        EVENT_CALLBACK.with(|c| *c.borrow_mut() = Some(Box::new(on_event)));
         
         unsafe {
             create_settings_window();
@@ -97,7 +190,7 @@ unsafe fn create_settings_window() {
         cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
         style: CS_HREDRAW | CS_VREDRAW,
         lpfnWndProc: Some(settings_wndproc),
-        hbrBackground: CreateSolidBrush(COLORREF(COL_BLACK)),
+        // Painted in WM_ERASEBKGND, the class outlives a theme change
         lpszClassName: class_name,
         ..Default::default()
     };
@@ -105,15 +198,20 @@ unsafe fn create_settings_window() {
     RegisterClassExW(&wc);
     
     // Inizializza i pennelli
-    BRUSH_BLACK.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(COL_BLACK)));
-    BRUSH_DARK_GRAY.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(COL_DARK_GRAY)));
-    BRUSH_RED.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(COL_RED)));
+    let theme = CURRENT_SETTINGS.with(|s| s.borrow().as_ref().map(|s| s.gui_theme)).unwrap_or_default();
+    create_theme_brushes(Theme::from_setting(theme));
+
+    // Layout coordinates are at 96 DPI, scaled to the primary monitor's DPI
+    GUI_DPI.with(|d| d.set(windows::Win32::UI::HiDpi::GetDpiForSystem().max(96)));
 
     // Calcolo posizione centrale schermo
     let screen_w = GetSystemMetrics(SM_CXSCREEN);
     let screen_h = GetSystemMetrics(SM_CYSCREEN);
-    let win_w = 360; 
-    let win_h = 400; // Increased height for Opacity Slider
+    let win_w = px(360); 
+    // Increased height for Opacity Slider, the RAM, GPU temperature, hotkey, PresentMon, font,
+    // font style, click-through, playtime and export/import rows (and the profile button)
+    let has_game = CURRENT_GAME.with(|g| g.borrow().is_some());
+    let win_h = px(if has_game { 765 } else { 725 });
     let pos_x = (screen_w - win_w) / 2;
     let pos_y = (screen_h - win_h) / 2;
 
@@ -139,9 +237,307 @@ unsafe fn create_settings_window() {
     }
 
     // Pulizia pennelli alla chiusura
-    let _ = BRUSH_BLACK.with(|b| DeleteObject(*b.borrow()));
-    let _ = BRUSH_DARK_GRAY.with(|b| DeleteObject(*b.borrow()));
-    let _ = BRUSH_RED.with(|b| DeleteObject(*b.borrow()));
+    delete_theme_brushes();
+    let _ = BRUSH_SWATCH.with(|b| DeleteObject(*b.borrow()));
+    let _ = BRUSH_STATS_SWATCH.with(|b| DeleteObject(*b.borrow()));
+}
+
+/// Show the results of a finished benchmark run in a window of its own, with the same
+/// look as the settings window. Each run gets its window, closed by the user.
+pub fn show_benchmark_results(stats: FrameStats, game: Option<String>, locale: NumberLocale, theme: GuiTheme) {
+    std::thread::spawn(move || unsafe {
+        create_benchmark_window(&stats, game, locale, theme);
+    });
+}
+
+const BENCHMARK_WIDTH: i32 = 280;
+
+unsafe fn create_benchmark_window(stats: &FrameStats, game: Option<String>, locale: NumberLocale, theme: GuiTheme) {
+    let class_name = windows::core::w!("EasyFPS_Benchmark");
+
+    let wc = WNDCLASSEXW {
+        cbSize: std::mem::size_of::<WNDCLASSEXW>() as u32,
+        style: CS_HREDRAW | CS_VREDRAW,
+        lpfnWndProc: Some(benchmark_wndproc),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+
+    RegisterClassExW(&wc);
+
+    // This thread's brushes, the settings window has its own
+    create_theme_brushes(Theme::from_setting(theme));
+    GUI_DPI.with(|d| d.set(windows::Win32::UI::HiDpi::GetDpiForSystem().max(96)));
+
+    let n = |value: f64, decimals: usize| locale.format(value, decimals);
+    let secs = (stats.duration_ms / 1000.0).round() as u64;
+    let mut rows = vec![
+        (Text::AverageFps, n(stats.avg_fps, 1)),
+        (Text::OnePercentLow, n(stats.one_percent_low, 1)),
+        (Text::PointOnePercentLow, n(stats.point_one_percent_low, 1)),
+        (Text::MinFps, n(stats.min_fps, 1)),
+        (Text::MaxFps, n(stats.max_fps, 1)),
+        (Text::Frames, n(stats.frames as f64, 0)),
+        (Text::Duration, format!("{}:{:02}", secs / 60, secs % 60)),
+    ];
+    if let Some(game) = game {
+        rows.insert(0, (Text::Game, game));
+    }
+
+    // Title bar, one row per value, then the close button
+    let rows_top = 45;
+    let row_height = 24;
+    let close_y = rows_top + rows.len() as i32 * row_height + 10;
+    let win_w = px(BENCHMARK_WIDTH);
+    let win_h = px(close_y + 40);
+    let pos_x = (GetSystemMetrics(SM_CXSCREEN) - win_w) / 2;
+    let pos_y = (GetSystemMetrics(SM_CYSCREEN) - win_h) / 2;
+
+    let hwnd = CreateWindowExW(
+        WS_EX_TOPMOST,
+        class_name,
+        PCWSTR(wide(tr(Text::BenchmarkTitle)).as_ptr()),
+        WS_POPUP | WS_BORDER,
+        pos_x, pos_y,
+        win_w, win_h,
+        None, None, None, None,
+    );
+    if hwnd.0 == 0 {
+        return;
+    }
+    set_window_dpi(hwnd);
+
+    let static_class = windows::core::w!("STATIC");
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        static_class,
+        PCWSTR(wide(&format!("   {}", tr(Text::BenchmarkTitle))).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTERIMAGE),
+        0, 0, px(BENCHMARK_WIDTH), px(30),
+        hwnd, HMENU(ID_TITLE_BAR as _), None, None,
+    );
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        static_class,
+        windows::core::w!("✕"),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTER | SS_NOTIFY | SS_CENTERIMAGE),
+        px(BENCHMARK_WIDTH - 30), 0, px(30), px(30),
+        hwnd, HMENU(ID_CLOSE_BTN as _), None, None,
+    );
+
+    for (i, (label, value)) in rows.iter().enumerate() {
+        let y = rows_top + i as i32 * row_height;
+        create_label(hwnd, static_class, tr(*label), 20, y, 130, 20);
+        create_label(hwnd, static_class, value, 160, y, BENCHMARK_WIDTH - 180, 20);
+    }
+
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        windows::core::w!("BUTTON"),
+        PCWSTR(wide(tr(Text::Close)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_DEFPUSHBUTTON as u32),
+        px((BENCHMARK_WIDTH - 100) / 2), px(close_y), px(100), px(26),
+        hwnd, HMENU(ID_CANCEL as _), None, None,
+    );
+
+    let _ = ShowWindow(hwnd, SW_SHOW);
+    let _ = UpdateWindow(hwnd);
+
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+        let _ = TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+
+    delete_theme_brushes();
+}
+
+unsafe extern "system" fn benchmark_wndproc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_LBUTTONDOWN => {
+            if on_close_button(hwnd, lparam) {
+                let _ = DestroyWindow(hwnd);
+                return LRESULT(0);
+            }
+            let _ = ReleaseCapture();
+            SendMessageW(hwnd, WM_NCLBUTTONDOWN, WPARAM(HTCAPTION as _), LPARAM(0));
+            LRESULT(0)
+        }
+        WM_DPICHANGED => apply_dpi_change(hwnd, wparam, lparam),
+        WM_ERASEBKGND => erase_background(hwnd, HDC(wparam.0 as _)),
+        WM_CTLCOLORSTATIC | WM_CTLCOLORBTN => {
+            let ctrl_id = GetDlgCtrlID(HWND(lparam.0 as isize));
+            theme_color(ctrl_id, HDC(wparam.0 as _))
+        }
+        WM_COMMAND => {
+            let id = (wparam.0 & 0xFFFF) as i32;
+            if id == ID_CLOSE_BTN || id == ID_CANCEL {
+                let _ = DestroyWindow(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+/// RGB tuple to a Win32 COLORREF (0x00BBGGRR)
+fn rgb_to_colorref((r, g, b): (u8, u8, u8)) -> COLORREF {
+    COLORREF((b as u32) << 16 | (g as u32) << 8 | r as u32)
+}
+
+/// Remember the picked color and repaint the preview swatch with it
+unsafe fn set_picked_color(hwnd: HWND, color: FpsColor) {
+    PICKED_COLOR.with(|c| c.set(color));
+    paint_swatch(hwnd, ID_COLOR_SWATCH, &BRUSH_SWATCH, color);
+    // A stats color that follows the FPS color previews it too
+    if PICKED_STATS_COLOR.with(|c| c.get()).is_none() {
+        paint_swatch(hwnd, ID_STATS_COLOR_SWATCH, &BRUSH_STATS_SWATCH, color);
+    }
+}
+
+/// Same for the stats color; None follows the FPS color
+unsafe fn set_picked_stats_color(hwnd: HWND, color: Option<FpsColor>) {
+    PICKED_STATS_COLOR.with(|c| c.set(color));
+    let shown = color.unwrap_or_else(|| PICKED_COLOR.with(|c| c.get()));
+    paint_swatch(hwnd, ID_STATS_COLOR_SWATCH, &BRUSH_STATS_SWATCH, shown);
+}
+
+/// Replace a swatch's brush with one of `color` and repaint it
+unsafe fn paint_swatch(
+    hwnd: HWND,
+    swatch_id: i32,
+    brush: &'static std::thread::LocalKey<std::cell::RefCell<HBRUSH>>,
+    color: FpsColor,
+) {
+    brush.with(|b| {
+        let old = b.replace(CreateSolidBrush(rgb_to_colorref(color.to_rgb())));
+        if old.0 != 0 {
+            let _ = DeleteObject(old);
+        }
+    });
+    let swatch = GetDlgItem(hwnd, swatch_id);
+    if swatch.0 != 0 {
+        let _ = InvalidateRect(swatch, None, true);
+    }
+}
+
+/// Open the standard color dialog seeded with `current`, None when it's cancelled.
+/// White and the classic bright green are offered as custom colors.
+unsafe fn pick_color(hwnd: HWND, current: FpsColor) -> Option<FpsColor> {
+    use windows::Win32::UI::Controls::Dialogs::{ChooseColorW, CHOOSECOLORW, CC_FULLOPEN, CC_RGBINIT};
+
+    let mut custom_colors = [COLORREF(0); 16];
+    custom_colors[0] = rgb_to_colorref(FpsColor::White.to_rgb());
+    custom_colors[1] = rgb_to_colorref(FpsColor::Green.to_rgb());
+
+    let mut cc = CHOOSECOLORW {
+        lStructSize: std::mem::size_of::<CHOOSECOLORW>() as u32,
+        hwndOwner: hwnd,
+        rgbResult: rgb_to_colorref(current.to_rgb()),
+        lpCustColors: custom_colors.as_mut_ptr(),
+        Flags: CC_RGBINIT | CC_FULLOPEN,
+        ..Default::default()
+    };
+
+    if !ChooseColorW(&mut cc).as_bool() {
+        return None;
+    }
+    let c = cc.rgbResult.0;
+    let (r, g, b) = ((c & 0xFF) as u8, ((c >> 8) & 0xFF) as u8, ((c >> 16) & 0xFF) as u8);
+    Some(FpsColor::Custom { r, g, b })
+}
+
+/// Remember the chosen PresentMon.exe and show it next to the Browse button
+unsafe fn set_presentmon_path(hwnd: HWND, path: Option<std::path::PathBuf>) {
+    let text = match &path {
+        Some(p) => p.display().to_string(),
+        None => tr(Text::AutomaticPath).to_string(),
+    };
+    let text_wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    let _ = SetWindowTextW(GetDlgItem(hwnd, ID_PRESENTMON_PATH), PCWSTR(text_wide.as_ptr()));
+    PICKED_PRESENTMON.with(|p| *p.borrow_mut() = path);
+}
+
+/// Standard open file dialog, or save dialog when `save` gives the default extension
+/// (added when the user types a name without one). None when it's cancelled.
+/// `filter` pairs are null-separated and the list ends with a double null.
+unsafe fn file_dialog(hwnd: HWND, filter: &str, save: Option<&str>) -> Option<std::path::PathBuf> {
+    use windows::Win32::UI::Controls::Dialogs::{
+        GetOpenFileNameW, GetSaveFileNameW, OFN_FILEMUSTEXIST, OFN_NOCHANGEDIR, OFN_OVERWRITEPROMPT,
+        OFN_PATHMUSTEXIST, OPENFILENAMEW,
+    };
+
+    let filter: Vec<u16> = filter.encode_utf16().collect();
+    let default_ext = save.map(wide);
+    let mut file = [0u16; 260];
+
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        hwndOwner: hwnd,
+        lpstrFilter: PCWSTR(filter.as_ptr()),
+        lpstrFile: windows::core::PWSTR(file.as_mut_ptr()),
+        nMaxFile: file.len() as u32,
+        lpstrDefExt: default_ext.as_ref().map_or(PCWSTR::null(), |ext| PCWSTR(ext.as_ptr())),
+        Flags: OFN_PATHMUSTEXIST | OFN_NOCHANGEDIR,
+        ..Default::default()
+    };
+
+    let picked = if save.is_some() {
+        ofn.Flags |= OFN_OVERWRITEPROMPT;
+        GetSaveFileNameW(&mut ofn).as_bool()
+    } else {
+        ofn.Flags |= OFN_FILEMUSTEXIST;
+        GetOpenFileNameW(&mut ofn).as_bool()
+    };
+    if !picked {
+        return None;
+    }
+    let len = file.iter().position(|&c| c == 0).unwrap_or(file.len());
+    Some(std::path::PathBuf::from(String::from_utf16_lossy(&file[..len])))
+}
+
+/// Let the user pick the PresentMon executable with the standard open dialog
+unsafe fn browse_presentmon(hwnd: HWND) {
+    if let Some(path) = file_dialog(hwnd, "PresentMon (*.exe)\0*.exe\0\0", None) {
+        set_presentmon_path(hwnd, Some(path));
+    }
+}
+
+const SETTINGS_FILTER: &str = "EasyFPS settings (*.json)\0*.json\0\0";
+
+/// Save what the window shows to a file of the user's choice
+unsafe fn export_settings(hwnd: HWND) {
+    let Some(path) = file_dialog(hwnd, SETTINGS_FILTER, Some("json")) else {
+        return;
+    };
+    if let Err(e) = read_controls(hwnd).export_to(&path) {
+        show_error(hwnd, &format!("{}:\n{}", tr(Text::ExportFailed), e));
+    }
+}
+
+/// Load a settings file into the window (applied like any change, saved with Save).
+/// A malformed file is reported and leaves the window as it was.
+unsafe fn import_settings(hwnd: HWND) {
+    let Some(path) = file_dialog(hwnd, SETTINGS_FILTER, None) else {
+        return;
+    };
+    match Settings::import_from(&path) {
+        Ok(settings) => {
+            // Options without a control come from the file too
+            CURRENT_SETTINGS.with(|s| *s.borrow_mut() = Some(settings.clone()));
+            apply_to_controls(hwnd, &settings);
+        }
+        Err(e) => show_error(hwnd, &format!("{}:\n{}", tr(Text::ImportFailed), e)),
+    }
 }
 
 unsafe fn create_controls(hwnd: HWND) {
@@ -154,9 +550,9 @@ unsafe fn create_controls(hwnd: HWND) {
     let _ = CreateWindowExW(
         WINDOW_EX_STYLE::default(),
         static_class,
-        windows::core::w!("   EasyFPS - Options"), 
+        PCWSTR(wide(&format!("   {}", tr(Text::WindowTitle))).as_ptr()), 
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTERIMAGE),
-        0, 0, 360, 30, 
+        0, 0, px(360), px(30), 
         hwnd, HMENU(ID_TITLE_BAR as _), None, None,
     );
 
@@ -166,55 +562,125 @@ unsafe fn create_controls(hwnd: HWND) {
         static_class,
         windows::core::w!("✕"),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_CENTER | SS_NOTIFY | SS_CENTERIMAGE),
-        360 - 30, 0, 30, 30, 
+        px(360 - 30), 0, px(30), px(30), 
         hwnd, HMENU(ID_CLOSE_BTN as _), None, None,
     );
 
     let offset_y = 35; 
 
     // Position
-    create_label(hwnd, static_class, "Position:", 20, 10 + offset_y, 80, 20);
-    create_radio(hwnd, button_class, "Right", ID_POS_RIGHT, 110, 10 + offset_y, 80, 20, 
-                 settings.position == OverlayPosition::TopRight, true);
-    create_radio(hwnd, button_class, "Left", ID_POS_LEFT, 200, 10 + offset_y, 80, 20,
-                 settings.position == OverlayPosition::TopLeft, false);
+    create_label(hwnd, static_class, tr(Text::Position), 20, 10 + offset_y, 80, 20);
+    // A percentage position checks its nearest corner (and the proportional box below)
+    let corner = settings.position.nearest_corner();
+    create_radio(hwnd, button_class, tr(Text::TopRight), ID_POS_RIGHT, 110, 10 + offset_y, 58, 20, 
+                 corner == Some(OverlayPosition::TopRight), true);
+    create_radio(hwnd, button_class, tr(Text::TopLeft), ID_POS_LEFT, 170, 10 + offset_y, 58, 20,
+                 corner == Some(OverlayPosition::TopLeft), false);
+    create_radio(hwnd, button_class, tr(Text::BottomRight), ID_POS_BOTTOM_RIGHT, 230, 10 + offset_y, 58, 20,
+                 corner == Some(OverlayPosition::BottomRight), false);
+    create_radio(hwnd, button_class, tr(Text::BottomLeft), ID_POS_BOTTOM_LEFT, 290, 10 + offset_y, 58, 20,
+                 corner == Some(OverlayPosition::BottomLeft), false);
     
     // Color
-    create_label(hwnd, static_class, "Color:", 20, 40 + offset_y, 80, 20);
-    create_radio(hwnd, button_class, "White", ID_COLOR_WHITE, 110, 40 + offset_y, 80, 20,
-                 settings.fps_color == FpsColor::White, true);
-    create_radio(hwnd, button_class, "Green", ID_COLOR_GREEN, 200, 40 + offset_y, 80, 20,
-                 settings.fps_color == FpsColor::Green, false);
+    create_label(hwnd, static_class, tr(Text::Color), 20, 40 + offset_y, 80, 20);
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::CustomColor)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(110), px(38 + offset_y), px(90), px(24),
+        hwnd, HMENU(ID_COLOR_PICK as _), None, None,
+    );
+    // Preview of the current color
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        static_class,
+        windows::core::w!(""),
+        WS_CHILD | WS_VISIBLE,
+        px(210), px(40 + offset_y), px(40), px(20),
+        hwnd, HMENU(ID_COLOR_SWATCH as _), None, None,
+    );
+    // Stats values color, next to the FPS one
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::StatsColor)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(258), px(38 + offset_y), px(70), px(24),
+        hwnd, HMENU(ID_STATS_COLOR_PICK as _), None, None,
+    );
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        static_class,
+        windows::core::w!(""),
+        WS_CHILD | WS_VISIBLE,
+        px(334), px(40 + offset_y), px(18), px(20),
+        hwnd, HMENU(ID_STATS_COLOR_SWATCH as _), None, None,
+    );
+    set_picked_stats_color(hwnd, settings.stats_color);
+    set_picked_color(hwnd, settings.fps_color);
     
     // Size (CORRETTO QUI)
-    create_label(hwnd, static_class, "Size:", 20, 70 + offset_y, 80, 20);
+    create_label(hwnd, static_class, tr(Text::Size), 20, 70 + offset_y, 80, 20);
     
     // Small: invariato
-    create_radio(hwnd, button_class, "Small", ID_SIZE_SMALL, 110, 70 + offset_y, 65, 20,
+    create_radio(hwnd, button_class, tr(Text::Small), ID_SIZE_SMALL, 110, 70 + offset_y, 65, 20,
                  settings.size == OverlaySize::Small, true);
                  
     // Medium: Spostato leggermente e allargato (da 75 a 85px di larghezza)
-    create_radio(hwnd, button_class, "Medium", ID_SIZE_MEDIUM, 180, 70 + offset_y, 85, 20,
+    create_radio(hwnd, button_class, tr(Text::Medium), ID_SIZE_MEDIUM, 180, 70 + offset_y, 85, 20,
                  settings.size == OverlaySize::Medium, false);
                  
     // Large: Spostato più a destra (da 260 a 270) per non sovrapporsi a Medium
-    create_radio(hwnd, button_class, "Large", ID_SIZE_LARGE, 270, 70 + offset_y, 70, 20,
+    create_radio(hwnd, button_class, tr(Text::Large), ID_SIZE_LARGE, 270, 70 + offset_y, 70, 20,
                  settings.size == OverlaySize::Large, false);
     
     // Checkboxes
-    create_checkbox(hwnd, button_class, "Show 1% Low FPS", ID_SHOW_1LOW, 20, 110 + offset_y, 200, 20,
+    create_checkbox(hwnd, button_class, tr(Text::ShowOnePercentLow), ID_SHOW_1LOW, 20, 110 + offset_y, 160, 20,
                      settings.show_1_percent_low);
-    create_checkbox(hwnd, button_class, "Show CPU Usage", ID_SHOW_CPU, 20, 140 + offset_y, 200, 20,
+    create_checkbox(hwnd, button_class, tr(Text::FpsAtBottom), ID_FPS_BOTTOM, 190, 110 + offset_y, 150, 20,
+                     settings.fps_on_bottom);
+    create_checkbox(hwnd, button_class, tr(Text::ShowCpu), ID_SHOW_CPU, 20, 140 + offset_y, 160, 20,
                      settings.show_cpu_usage);
-    create_checkbox(hwnd, button_class, "Show GPU Usage", ID_SHOW_GPU, 20, 170 + offset_y, 200, 20,
+    create_checkbox(hwnd, button_class, tr(Text::TrackPlaytime), ID_TRACK_PLAYTIME, 190, 140 + offset_y, 150, 20,
+                     settings.track_playtime);
+    create_checkbox(hwnd, button_class, tr(Text::ShowGpu), ID_SHOW_GPU, 20, 170 + offset_y, 160, 20,
                      settings.show_gpu_usage);
-    create_checkbox(hwnd, button_class, "Start with Windows", ID_STARTUP, 20, 200 + offset_y, 200, 20,
+    create_checkbox(hwnd, button_class, tr(Text::AlertOnly), ID_ALERT_ONLY, 190, 170 + offset_y, 150, 20,
+                     settings.alert_only_mode);
+    create_checkbox(hwnd, button_class, tr(Text::StartWithWindows), ID_STARTUP, 20, 200 + offset_y, 160, 20,
                      settings.start_with_windows);
+    create_checkbox(hwnd, button_class, tr(Text::ShowPointOneLow), ID_SHOW_POINT1LOW, 190, 200 + offset_y, 150, 20,
+                     settings.show_point_one_low);
+    
+    create_checkbox(hwnd, button_class, tr(Text::ShowRam), ID_SHOW_RAM, 20, 230 + offset_y, 160, 20,
+                     settings.show_ram_usage);
+    create_checkbox(hwnd, button_class, tr(Text::FrametimeGraph), ID_FRAMETIME_GRAPH, 190, 230 + offset_y, 150, 20,
+                     settings.show_frametime_graph);
+    create_checkbox(hwnd, button_class, tr(Text::ShowGpuTemp), ID_SHOW_GPU_TEMP, 20, 260 + offset_y, 160, 20,
+                     settings.show_gpu_temp);
+
+    // GPU for the "GPU" line, only worth offering with more than one
+    let adapters = crate::monitor::list_gpu_adapters();
+    if adapters.len() > 1 {
+        let selected = match &settings.gpu_selection {
+            GpuSelection::MaxAll => 0,
+            GpuSelection::Adapter { luid, name } => adapters
+                .iter()
+                .position(|(key, _)| key == luid)
+                .or_else(|| adapters.iter().position(|(_, adapter)| adapter == name))
+                .map_or(0, |i| i + 1),
+        };
+        let mut items = vec![tr(Text::AllGpus)];
+        items.extend(adapters.iter().map(|(_, name)| name.as_str()));
+        create_combo(hwnd, ID_GPU_SELECT, 190, 257 + offset_y, 150, &items, selected);
+    }
+    GPU_CHOICES.with(|c| *c.borrow_mut() = adapters);
     
     // Opacity Slider
-    create_label(hwnd, static_class, "Opacity:", 20, 230 + offset_y, 60, 20);
+    create_label(hwnd, static_class, tr(Text::Opacity), 20, 290 + offset_y, 60, 20);
     // Range 40-100
-    create_trackbar(hwnd, ID_OPACITY_SLIDER, 90, 230 + offset_y, 200, 30, settings.overlay_opacity);
+    create_trackbar(hwnd, ID_OPACITY_SLIDER, 90, 290 + offset_y, 200, 30, settings.overlay_opacity);
     
     // Opacity Value Label
     let val_str = format!("{}%", settings.overlay_opacity);
@@ -224,28 +690,198 @@ unsafe fn create_controls(hwnd: HWND) {
         static_class,
         PCWSTR(val_wide.as_ptr()),
         WS_CHILD | WS_VISIBLE,
-        300, 230 + offset_y, 40, 20,
+        px(300), px(290 + offset_y), px(40), px(20),
         hwnd, HMENU(ID_OPACITY_VAL as _), None, None,
     );
 
+    // Toggle hotkey (read-only, set in settings.json)
+    create_label(hwnd, static_class, &format!("{} {}", tr(Text::ToggleHotkey), settings.toggle_hotkey), 20, 330 + offset_y, 170, 20);
+
+    // Language (applied to the tray menu on save, to this window the next time it opens)
+    create_label(hwnd, static_class, tr(Text::Language), 195, 330 + offset_y, 65, 20);
+    let languages: Vec<&str> = Lang::ALL.iter().map(|lang| lang.native_name()).collect();
+    let current = Lang::ALL.iter().position(|&l| l == settings.language).unwrap_or(0);
+    create_combo(hwnd, ID_LANGUAGE, 260, 327 + offset_y, 85, &languages, current);
+
+    // PresentMon executable
+    create_label(hwnd, static_class, "PresentMon:", 20, 357 + offset_y, 80, 20);
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        static_class,
+        windows::core::w!(""),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(SS_PATHELLIPSIS),
+        px(100), px(357 + offset_y), px(140), px(20),
+        hwnd, HMENU(ID_PRESENTMON_PATH as _), None, None,
+    );
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::Browse)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(245), px(355 + offset_y), px(70), px(24),
+        hwnd, HMENU(ID_PRESENTMON_BROWSE as _), None, None,
+    );
+    // Back to the automatic search
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        windows::core::w!("✕"),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(318), px(355 + offset_y), px(24), px(24),
+        hwnd, HMENU(ID_PRESENTMON_RESET as _), None, None,
+    );
+    set_presentmon_path(hwnd, settings.presentmon_path.clone());
+
+    // Overlay font, and the fixed-pitch override that keeps the numbers from shifting
+    create_label(hwnd, static_class, tr(Text::Font), 20, 390 + offset_y, 75, 20);
+    FONT_LIST.with(|f| *f.borrow_mut() = FONT_CHOICES.iter().map(|font| font.to_string()).collect());
+    create_combo(hwnd, ID_FONT, 100, 387 + offset_y, 130, &FONT_CHOICES, 0);
+    select_font(hwnd, &settings.font_family);
+    create_checkbox(hwnd, button_class, tr(Text::MonospaceDigits), ID_MONOSPACE_DIGITS, 240, 390 + offset_y, 105, 20,
+                     settings.monospace_digits);
+
+    // Weight and italics of the overlay font
+    create_label(hwnd, static_class, tr(Text::FontWeight), 20, 420 + offset_y, 75, 20);
+    let weights = [tr(Text::WeightLight), tr(Text::WeightRegular), tr(Text::WeightSemibold), tr(Text::WeightBold)];
+    create_combo(hwnd, ID_FONT_WEIGHT, 100, 417 + offset_y, 130, &weights, 0);
+    select_font_weight(hwnd, settings.font_weight);
+    create_checkbox(hwnd, button_class, tr(Text::Italic), ID_FONT_ITALIC, 240, 420 + offset_y, 105, 20,
+                     settings.font_italic);
+
+    create_checkbox(hwnd, button_class, tr(Text::ClickThrough), ID_CLICKTHROUGH, 20, 450 + offset_y, 160, 20,
+                     settings.overlay_clickthrough);
+    // Corner at a percentage of the screen instead of overlay_margin pixels
+    create_checkbox(hwnd, button_class, tr(Text::ProportionalPosition), ID_POS_PROPORTIONAL, 190, 450 + offset_y, 155, 20,
+                     matches!(settings.position, OverlayPosition::Percent { .. }));
+    // Hidden from OBS/Game Bar recordings, still visible on screen
+    create_checkbox(hwnd, button_class, tr(Text::ExcludeFromCapture), ID_EXCLUDE_FROM_CAPTURE, 20, 480 + offset_y, 220, 20,
+                     settings.exclude_from_capture);
+    // Total playtime of the current game, recorded with "Track playtime"
+    create_checkbox(hwnd, button_class, tr(Text::ShowPlaytime), ID_SHOW_PLAYTIME, 20, 510 + offset_y, 220, 20,
+                     settings.show_playtime);
+
+    // Decimals of the FPS values and of the CPU/GPU load, the examples show what each does
+    create_label(hwnd, static_class, tr(Text::Decimals), 20, 540 + offset_y, 75, 20);
+    create_combo(hwnd, ID_FPS_DECIMALS, 100, 537 + offset_y, 115, &["FPS 142", "FPS 142.3"], settings.fps_decimals as usize);
+    create_combo(hwnd, ID_USAGE_DECIMALS, 225, 537 + offset_y, 115, &["CPU 45%", "CPU 45.2%"], settings.usage_decimals as usize);
+
     // Buttons
     let _ = CreateWindowExW(
         WINDOW_EX_STYLE::default(),
         button_class,
-        windows::core::w!("Save"),
+        PCWSTR(wide(tr(Text::Save)).as_ptr()),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
-        80, 280 + offset_y, 90, 30, // Lowered y position
+        px(30), px(575 + offset_y), px(90), px(30), // Lowered y position
         hwnd, HMENU(ID_SAVE as _), None, None,
     );
     
     let _ = CreateWindowExW(
         WINDOW_EX_STYLE::default(),
         button_class,
-        windows::core::w!("Cancel"),
+        PCWSTR(wide(tr(Text::Cancel)).as_ptr()),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
-        190, 280 + offset_y, 90, 30, // Lowered y position
+        px(135), px(575 + offset_y), px(90), px(30), // Lowered y position
         hwnd, HMENU(ID_CANCEL as _), None, None,
     );
+
+    // Back to the defaults (not saved until Save is pressed)
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::ResetDefaults)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(240), px(575 + offset_y), px(90), px(30),
+        hwnd, HMENU(ID_RESET as _), None, None,
+    );
+
+    // Settings file of the user's choice, for backups and sharing
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::ExportSettings)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(30), px(615 + offset_y), px(145), px(30),
+        hwnd, HMENU(ID_EXPORT as _), None, None,
+    );
+    let _ = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        button_class,
+        PCWSTR(wide(tr(Text::ImportSettings)).as_ptr()),
+        WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+        px(185), px(615 + offset_y), px(145), px(30),
+        hwnd, HMENU(ID_IMPORT as _), None, None,
+    );
+
+    // Per-game profile, only while a game is running
+    if let Some(game) = CURRENT_GAME.with(|g| g.borrow().clone()) {
+        let text = wide(&format!("{} {}", tr(Text::SaveProfileFor), game));
+        let _ = CreateWindowExW(
+            WINDOW_EX_STYLE::default(),
+            button_class,
+            PCWSTR(text.as_ptr()),
+            WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_PUSHBUTTON as u32),
+            px(40), px(655 + offset_y), px(280), px(30),
+            hwnd, HMENU(ID_SAVE_PROFILE as _), None, None,
+        );
+    }
+}
+
+/// Null-terminated UTF-16 copy of `text`
+fn wide(text: &str) -> Vec<u16> {
+    text.encode_utf16().chain(std::iter::once(0)).collect()
+}
+
+/// Drop-down list with `items`, `selected` preselected
+unsafe fn create_combo(hwnd: HWND, id: i32, x: i32, y: i32, w: i32, items: &[&str], selected: usize) {
+    let combo = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        windows::core::w!("COMBOBOX"),
+        windows::core::w!(""),
+        WS_CHILD | WS_VISIBLE | WS_VSCROLL | WINDOW_STYLE(CBS_DROPDOWNLIST),
+        // The height includes the open list
+        px(x), px(y), px(w), px(120),
+        hwnd, HMENU(id as _), None, None,
+    );
+    for item in items {
+        let text = wide(item);
+        SendMessageW(combo, CB_ADDSTRING, WPARAM(0), LPARAM(text.as_ptr() as isize));
+    }
+    SendMessageW(combo, CB_SETCURSEL, WPARAM(selected), LPARAM(0));
+}
+
+/// Select `family` in the font dropdown. A font set in settings.json that isn't offered is
+/// added as an extra item, so saving from the window keeps it.
+unsafe fn select_font(hwnd: HWND, family: &str) {
+    let combo = GetDlgItem(hwnd, ID_FONT);
+    if combo.0 == 0 {
+        return;
+    }
+    let index = FONT_LIST.with(|f| {
+        let mut fonts = f.borrow_mut();
+        fonts.iter().position(|font| font.eq_ignore_ascii_case(family)).unwrap_or_else(|| {
+            let text = wide(family);
+            SendMessageW(combo, CB_ADDSTRING, WPARAM(0), LPARAM(text.as_ptr() as isize));
+            fonts.push(family.to_string());
+            fonts.len() - 1
+        })
+    });
+    SendMessageW(combo, CB_SETCURSEL, WPARAM(index), LPARAM(0));
+}
+
+/// Select `weight` in the weight dropdown. A weight from settings.json that isn't offered
+/// leaves the dropdown empty, so saving from the window keeps it.
+unsafe fn select_font_weight(hwnd: HWND, weight: u32) {
+    let index = FONT_WEIGHTS.iter().position(|&w| w == weight).map_or(-1, |i| i as isize);
+    SendMessageW(GetDlgItem(hwnd, ID_FONT_WEIGHT), CB_SETCURSEL, WPARAM(index as usize), LPARAM(0));
+}
+
+/// Index of the selected combo item, None when nothing is selected or the combo wasn't created
+unsafe fn combo_selection(hwnd: HWND, id: i32) -> Option<usize> {
+    let combo = GetDlgItem(hwnd, id);
+    if combo.0 == 0 {
+        return None;
+    }
+    usize::try_from(SendMessageW(combo, CB_GETCURSEL, WPARAM(0), LPARAM(0)).0).ok()
 }
 
 unsafe fn create_label(hwnd: HWND, class: PCWSTR, text: &str, x: i32, y: i32, w: i32, h: i32) {
@@ -255,7 +891,7 @@ unsafe fn create_label(hwnd: HWND, class: PCWSTR, text: &str, x: i32, y: i32, w:
         class,
         PCWSTR(text_wide.as_ptr()),
         WS_CHILD | WS_VISIBLE,
-        x, y, w, h,
+        px(x), px(y), px(w), px(h),
         hwnd, None, None, None,
     );
 }
@@ -273,7 +909,7 @@ unsafe fn create_radio(hwnd: HWND, class: PCWSTR, text: &str, id: i32, x: i32, y
         class,
         PCWSTR(text_wide.as_ptr()),
         style,
-        x, y, w, h,
+        px(x), px(y), px(w), px(h),
         hwnd, HMENU(id as _), None, None,
     );
 
@@ -292,7 +928,7 @@ unsafe fn create_checkbox(hwnd: HWND, class: PCWSTR, text: &str, id: i32, x: i32
         class,
         PCWSTR(text_wide.as_ptr()),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(BS_AUTOCHECKBOX as u32),
-        x, y, w, h,
+        px(x), px(y), px(w), px(h),
         hwnd, HMENU(id as _), None, None,
     );
 
@@ -303,6 +939,14 @@ unsafe fn create_checkbox(hwnd: HWND, class: PCWSTR, text: &str, id: i32, x: i32
     }
 }
 
+unsafe fn set_checked(hwnd: HWND, id: i32, checked: bool) {
+    let ctrl = GetDlgItem(hwnd, id);
+    if ctrl.0 != 0 {
+        let state = if checked { BST_CHECKED_VAL } else { 0 };
+        SendMessageW(ctrl, BM_SETCHECK, WPARAM(state), LPARAM(0));
+    }
+}
+
 unsafe fn is_checked(hwnd: HWND, id: i32) -> bool {
     let ctrl = GetDlgItem(hwnd, id);
     if ctrl.0 != 0 {
@@ -312,45 +956,231 @@ unsafe fn is_checked(hwnd: HWND, id: i32) -> bool {
     }
 }
 
-unsafe fn save_settings(hwnd: HWND) {
-    let mut settings = Settings::default();
+/// Why saving from the settings window failed
+enum SaveError {
+    /// settings.json could not be written: nothing was applied
+    Settings(String),
+    /// settings.json was written but the "start with Windows" registry entry was not updated
+    Startup(String),
+    /// profiles.json could not be written
+    Profile(String),
+}
+
+impl std::fmt::Display for SaveError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SaveError::Settings(e) => write!(f, "{}:\n{}", tr(Text::SaveSettingsFailed), e),
+            SaveError::Startup(e) => write!(f, "{}:\n{}", tr(Text::StartupUpdateFailed), e),
+            SaveError::Profile(e) => write!(f, "{}:\n{}", tr(Text::SaveProfileFailed), e),
+        }
+    }
+}
+
+/// Settings as shown in the window.
+/// Starts from the settings the window was opened with so options without a control are kept.
+unsafe fn read_controls(hwnd: HWND) -> Settings {
+    let mut settings = CURRENT_SETTINGS.with(|s| s.borrow().clone().unwrap_or_default());
     
     settings.position = if is_checked(hwnd, ID_POS_LEFT) {
         OverlayPosition::TopLeft
-    } else {
+    } else if is_checked(hwnd, ID_POS_BOTTOM_RIGHT) {
+        OverlayPosition::BottomRight
+    } else if is_checked(hwnd, ID_POS_BOTTOM_LEFT) {
+        OverlayPosition::BottomLeft
+    } else if is_checked(hwnd, ID_POS_RIGHT) {
         OverlayPosition::TopRight
-    };
-    
-    settings.fps_color = if is_checked(hwnd, ID_COLOR_GREEN) {
-        FpsColor::Green
     } else {
-        FpsColor::White
+        // Dragged to a custom spot: no corner is checked
+        settings.position
     };
+    if is_checked(hwnd, ID_POS_PROPORTIONAL) {
+        // An unchanged corner keeps its exact percentages from settings.json
+        let current = CURRENT_SETTINGS.with(|s| s.borrow().as_ref().map(|s| s.position));
+        settings.position = match current {
+            Some(percent @ OverlayPosition::Percent { .. }) if percent.nearest_corner() == Some(settings.position) => percent,
+            _ => settings.position.proportional(),
+        };
+    }
+    
+    settings.fps_color = PICKED_COLOR.with(|c| c.get());
+    settings.stats_color = PICKED_STATS_COLOR.with(|c| c.get());
+    settings.presentmon_path = PICKED_PRESENTMON.with(|p| p.borrow().clone());
+    if let Some(&lang) = combo_selection(hwnd, ID_LANGUAGE).and_then(|i| Lang::ALL.get(i)) {
+        settings.language = lang;
+    }
+    // Item 0 is "all GPUs", the others follow GPU_CHOICES
+    if let Some(index) = combo_selection(hwnd, ID_GPU_SELECT) {
+        settings.gpu_selection = match GPU_CHOICES.with(|c| c.borrow().get(index.wrapping_sub(1)).cloned()) {
+            Some((luid, name)) => GpuSelection::Adapter { luid, name },
+            None => GpuSelection::MaxAll,
+        };
+    }
     
     settings.size = if is_checked(hwnd, ID_SIZE_SMALL) {
         OverlaySize::Small
     } else if is_checked(hwnd, ID_SIZE_LARGE) {
         OverlaySize::Large
-    } else {
+    } else if is_checked(hwnd, ID_SIZE_MEDIUM) {
         OverlaySize::Medium
+    } else {
+        // A Custom size from settings.json checks none of the presets, and stays
+        settings.size
     };
     
     settings.show_1_percent_low = is_checked(hwnd, ID_SHOW_1LOW);
+    settings.show_point_one_low = is_checked(hwnd, ID_SHOW_POINT1LOW);
     settings.show_cpu_usage = is_checked(hwnd, ID_SHOW_CPU);
     settings.show_gpu_usage = is_checked(hwnd, ID_SHOW_GPU);
+    settings.show_ram_usage = is_checked(hwnd, ID_SHOW_RAM);
+    settings.show_gpu_temp = is_checked(hwnd, ID_SHOW_GPU_TEMP);
+    settings.monospace_digits = is_checked(hwnd, ID_MONOSPACE_DIGITS);
+    settings.overlay_clickthrough = is_checked(hwnd, ID_CLICKTHROUGH);
+    settings.exclude_from_capture = is_checked(hwnd, ID_EXCLUDE_FROM_CAPTURE);
+    settings.show_playtime = is_checked(hwnd, ID_SHOW_PLAYTIME);
+    if let Some(font) = combo_selection(hwnd, ID_FONT).and_then(|i| FONT_LIST.with(|f| f.borrow().get(i).cloned())) {
+        settings.font_family = font;
+    }
+    if let Some(weight) = combo_selection(hwnd, ID_FONT_WEIGHT).and_then(|i| FONT_WEIGHTS.get(i)) {
+        settings.font_weight = *weight;
+    }
+    settings.font_italic = is_checked(hwnd, ID_FONT_ITALIC);
+    if let Some(decimals) = combo_selection(hwnd, ID_FPS_DECIMALS) {
+        settings.fps_decimals = decimals as u8;
+    }
+    if let Some(decimals) = combo_selection(hwnd, ID_USAGE_DECIMALS) {
+        settings.usage_decimals = decimals as u8;
+    }
+    settings.show_frametime_graph = is_checked(hwnd, ID_FRAMETIME_GRAPH);
+    settings.fps_on_bottom = is_checked(hwnd, ID_FPS_BOTTOM);
+    settings.track_playtime = is_checked(hwnd, ID_TRACK_PLAYTIME);
+    settings.alert_only_mode = is_checked(hwnd, ID_ALERT_ONLY);
     settings.start_with_windows = is_checked(hwnd, ID_STARTUP);
     settings.overlay_opacity = get_trackbar_pos(hwnd, ID_OPACITY_SLIDER);
+    settings
+}
+
+/// Show `settings` in the controls; the inverse of `read_controls`
+unsafe fn apply_to_controls(hwnd: HWND, settings: &Settings) {
+    let corner = settings.position.nearest_corner();
+    set_checked(hwnd, ID_POS_RIGHT, corner == Some(OverlayPosition::TopRight));
+    set_checked(hwnd, ID_POS_LEFT, corner == Some(OverlayPosition::TopLeft));
+    set_checked(hwnd, ID_POS_BOTTOM_RIGHT, corner == Some(OverlayPosition::BottomRight));
+    set_checked(hwnd, ID_POS_BOTTOM_LEFT, corner == Some(OverlayPosition::BottomLeft));
+    set_checked(hwnd, ID_POS_PROPORTIONAL, matches!(settings.position, OverlayPosition::Percent { .. }));
+    set_picked_stats_color(hwnd, settings.stats_color);
+    set_picked_color(hwnd, settings.fps_color);
+    set_checked(hwnd, ID_SIZE_SMALL, settings.size == OverlaySize::Small);
+    set_checked(hwnd, ID_SIZE_MEDIUM, settings.size == OverlaySize::Medium);
+    set_checked(hwnd, ID_SIZE_LARGE, settings.size == OverlaySize::Large);
+
+    set_checked(hwnd, ID_SHOW_1LOW, settings.show_1_percent_low);
+    set_checked(hwnd, ID_SHOW_POINT1LOW, settings.show_point_one_low);
+    set_checked(hwnd, ID_SHOW_CPU, settings.show_cpu_usage);
+    set_checked(hwnd, ID_SHOW_GPU, settings.show_gpu_usage);
+    set_checked(hwnd, ID_SHOW_RAM, settings.show_ram_usage);
+    set_checked(hwnd, ID_SHOW_GPU_TEMP, settings.show_gpu_temp);
+    set_checked(hwnd, ID_MONOSPACE_DIGITS, settings.monospace_digits);
+    set_checked(hwnd, ID_CLICKTHROUGH, settings.overlay_clickthrough);
+    set_checked(hwnd, ID_EXCLUDE_FROM_CAPTURE, settings.exclude_from_capture);
+    set_checked(hwnd, ID_SHOW_PLAYTIME, settings.show_playtime);
+    set_checked(hwnd, ID_FRAMETIME_GRAPH, settings.show_frametime_graph);
+    set_checked(hwnd, ID_FPS_BOTTOM, settings.fps_on_bottom);
+    set_checked(hwnd, ID_TRACK_PLAYTIME, settings.track_playtime);
+    set_checked(hwnd, ID_ALERT_ONLY, settings.alert_only_mode);
+    set_checked(hwnd, ID_STARTUP, settings.start_with_windows);
+
+    let slider = GetDlgItem(hwnd, ID_OPACITY_SLIDER);
+    if slider.0 != 0 {
+        SendMessageW(slider, TBM_SETPOS, WPARAM(1), LPARAM(settings.overlay_opacity as isize));
+    }
+    set_opacity_label(hwnd, settings.overlay_opacity as isize);
+
+    let language = Lang::ALL.iter().position(|&l| l == settings.language).unwrap_or(0);
+    SendMessageW(GetDlgItem(hwnd, ID_LANGUAGE), CB_SETCURSEL, WPARAM(language), LPARAM(0));
+    let gpu = match &settings.gpu_selection {
+        GpuSelection::MaxAll => 0,
+        GpuSelection::Adapter { luid, .. } => GPU_CHOICES
+            .with(|c| c.borrow().iter().position(|(key, _)| key == luid))
+            .map_or(0, |i| i + 1),
+    };
+    let gpu_combo = GetDlgItem(hwnd, ID_GPU_SELECT);
+    if gpu_combo.0 != 0 {
+        SendMessageW(gpu_combo, CB_SETCURSEL, WPARAM(gpu), LPARAM(0));
+    }
+    select_font(hwnd, &settings.font_family);
+    select_font_weight(hwnd, settings.font_weight);
+    set_checked(hwnd, ID_FONT_ITALIC, settings.font_italic);
+    for (id, decimals) in [(ID_FPS_DECIMALS, settings.fps_decimals), (ID_USAGE_DECIMALS, settings.usage_decimals)] {
+        SendMessageW(GetDlgItem(hwnd, id), CB_SETCURSEL, WPARAM(decimals as usize), LPARAM(0));
+    }
+    set_presentmon_path(hwnd, settings.presentmon_path.clone());
+}
+
+/// Show the opacity slider's value next to it
+unsafe fn set_opacity_label(hwnd: HWND, value: isize) {
+    let label_hwnd = GetDlgItem(hwnd, ID_OPACITY_VAL);
+    if label_hwnd.0 != 0 {
+        let _ = SetWindowTextW(label_hwnd, PCWSTR(wide(&format!("{}%", value)).as_ptr()));
+    }
+}
+
+unsafe fn save_settings(hwnd: HWND) -> Result<(), SaveError> {
+    let settings = read_controls(hwnd);
+    
+    // Command-line overrides are for this session, settings.json keeps its own values
+    let mut to_save = settings.clone();
+    crate::cli::restore_saved(&mut to_save, &Settings::load());
+    to_save.save().map_err(SaveError::Settings)?;
+    // settings.json is written: apply it even if the registry update below fails
+    let startup = settings.set_startup_registry();
+    finish(GuiEvent::Saved(settings));
+    startup.map_err(SaveError::Startup)
+}
+
+/// Save the window's settings as the current game's profile (global settings are untouched)
+unsafe fn save_profile(hwnd: HWND) -> Result<(), SaveError> {
+    let Some(game) = CURRENT_GAME.with(|g| g.borrow().clone()) else {
+        return Ok(());
+    };
+    let settings = read_controls(hwnd);
     
-    let _ = settings.save();
-    let _ = settings.set_startup_registry();
+    let mut profiles = Profiles::load();
+    profiles.insert(&game, settings.clone());
+    profiles.save().map_err(SaveError::Profile)?;
     
-    SAVE_CALLBACK.with(|c| {
-        if let Some(callback) = c.borrow_mut().take() {
-            callback(settings);
+    finish(GuiEvent::ProfileSaved { game, settings });
+    Ok(())
+}
+
+/// Send the window's final event; nothing is sent after it
+fn finish(event: GuiEvent) {
+    let callback = EVENT_CALLBACK.with(|c| c.borrow_mut().take());
+    if let Some(mut callback) = callback {
+        callback(event);
+    }
+}
+
+/// Live preview of the controls' current state
+unsafe fn preview(hwnd: HWND) {
+    let settings = read_controls(hwnd);
+    EVENT_CALLBACK.with(|c| {
+        if let Some(callback) = c.borrow_mut().as_mut() {
+            callback(GuiEvent::Preview(settings));
         }
     });
 }
 
+/// Report a failed save; the settings window stays open so the user can retry or cancel
+unsafe fn show_save_error(hwnd: HWND, err: &SaveError) {
+    show_error(hwnd, &err.to_string());
+}
+
+unsafe fn show_error(hwnd: HWND, message: &str) {
+    let msg = wide(message);
+    let title = wide(tr(Text::ErrorTitle));
+    MessageBoxW(hwnd, PCWSTR(msg.as_ptr()), PCWSTR(title.as_ptr()), MB_OK | MB_ICONERROR);
+}
+
 unsafe extern "system" fn settings_wndproc(
     hwnd: HWND,
     msg: u32,
@@ -359,34 +1189,38 @@ unsafe extern "system" fn settings_wndproc(
 ) -> LRESULT {
     match msg {
         WM_CREATE => {
+            set_window_dpi(hwnd);
             create_controls(hwnd);
             LRESULT(0)
         }
+        WM_ERASEBKGND => erase_background(hwnd, HDC(wparam.0 as _)),
         WM_LBUTTONDOWN => {
+            // The close button's own clicks don't always reach it (it sits under the title
+            // bar), so a click in its area closes instead of starting a drag
+            if on_close_button(hwnd, lparam) {
+                let _ = DestroyWindow(hwnd);
+                return LRESULT(0);
+            }
             let _ = ReleaseCapture(); // <--- Corretto con let _ =
             SendMessageW(hwnd, WM_NCLBUTTONDOWN, WPARAM(HTCAPTION as _), LPARAM(0));
             LRESULT(0)
         }
+        WM_DPICHANGED => apply_dpi_change(hwnd, wparam, lparam),
         WM_CTLCOLORSTATIC | WM_CTLCOLORBTN => {
             let ctrl_id = GetDlgCtrlID(HWND(lparam.0 as isize));
             let hdc = HDC(wparam.0 as _);
             
-            if ctrl_id == ID_CLOSE_BTN {
-                SetTextColor(hdc, COLORREF(COL_WHITE));
-                SetBkColor(hdc, COLORREF(COL_RED));
-                let brush = BRUSH_RED.with(|b| *b.borrow());
-                return LRESULT(brush.0 as _);
-            } else if ctrl_id == ID_TITLE_BAR {
-                SetTextColor(hdc, COLORREF(COL_WHITE));
-                SetBkColor(hdc, COLORREF(COL_DARK_GRAY));
-                let brush = BRUSH_DARK_GRAY.with(|b| *b.borrow());
+            // The color preview is just filled with the picked color
+            if ctrl_id == ID_COLOR_SWATCH {
+                let brush = BRUSH_SWATCH.with(|b| *b.borrow());
                 return LRESULT(brush.0 as _);
-            } else {
-                SetTextColor(hdc, COLORREF(COL_WHITE));
-                SetBkColor(hdc, COLORREF(COL_BLACK));
-                let brush = BRUSH_BLACK.with(|b| *b.borrow());
+            }
+            if ctrl_id == ID_STATS_COLOR_SWATCH {
+                let brush = BRUSH_STATS_SWATCH.with(|b| *b.borrow());
                 return LRESULT(brush.0 as _);
             }
+            
+            theme_color(ctrl_id, hdc)
         }
         WM_COMMAND => {
             let id = (wparam.0 & 0xFFFF) as i32;
@@ -396,15 +1230,47 @@ unsafe extern "system" fn settings_wndproc(
             }
 
             match id {
-                ID_SAVE => {
-                    save_settings(hwnd);
-                    let _ = DestroyWindow(hwnd);
-                }
+                ID_SAVE => match save_settings(hwnd) {
+                    Ok(()) => {
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    // The settings were saved and applied, there's nothing left to cancel
+                    Err(e @ SaveError::Startup(_)) => {
+                        show_save_error(hwnd, &e);
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    Err(e) => show_save_error(hwnd, &e),
+                },
+                ID_SAVE_PROFILE => match save_profile(hwnd) {
+                    Ok(()) => {
+                        let _ = DestroyWindow(hwnd);
+                    }
+                    Err(e) => show_save_error(hwnd, &e),
+                },
                 ID_CANCEL => {
                     let _ = DestroyWindow(hwnd);
                 }
+                ID_COLOR_PICK => {
+                    if let Some(color) = pick_color(hwnd, PICKED_COLOR.with(|c| c.get())) {
+                        set_picked_color(hwnd, color);
+                    }
+                }
+                ID_STATS_COLOR_PICK => {
+                    let current = PICKED_STATS_COLOR.with(|c| c.get()).unwrap_or_else(|| PICKED_COLOR.with(|c| c.get()));
+                    if let Some(color) = pick_color(hwnd, current) {
+                        set_picked_stats_color(hwnd, Some(color));
+                    }
+                }
+                ID_PRESENTMON_BROWSE => browse_presentmon(hwnd),
+                ID_PRESENTMON_RESET => set_presentmon_path(hwnd, None),
+                ID_EXPORT => export_settings(hwnd),
+                ID_IMPORT => import_settings(hwnd),
+                ID_RESET => apply_to_controls(hwnd, &Settings::default()),
                 _ => {}
             }
+            if !matches!(id, ID_SAVE | ID_SAVE_PROFILE | ID_CANCEL) {
+                preview(hwnd);
+            }
             LRESULT(0)
         }
         WM_HSCROLL => {
@@ -414,19 +1280,15 @@ unsafe extern "system" fn settings_wndproc(
                 
                 if ctrl_id == ID_OPACITY_SLIDER {
                      let pos = SendMessageW(ctrl_hwnd, TBM_GETPOS, WPARAM(0), LPARAM(0)).0;
-                     
-                     let val_str = format!("{}%", pos);
-                     let val_wide: Vec<u16> = val_str.encode_utf16().chain(std::iter::once(0)).collect();
-                     
-                     let label_hwnd = GetDlgItem(hwnd, ID_OPACITY_VAL);
-                     if label_hwnd.0 != 0 {
-                         let _ = SetWindowTextW(label_hwnd, PCWSTR(val_wide.as_ptr()));
-                     }
+                     set_opacity_label(hwnd, pos);
+                     preview(hwnd);
                 }
             }
             LRESULT(0)
         }
         WM_DESTROY => {
+            // Cancel, the close button and anything else that isn't a save
+            finish(GuiEvent::Discarded);
             PostQuitMessage(0);
             LRESULT(0)
         }
@@ -434,6 +1296,121 @@ unsafe extern "system" fn settings_wndproc(
     }
 }
 
+/// Lay the window out for the DPI of the monitor it's on (the system DPI is the primary
+/// monitor's, which may not be this one)
+unsafe fn set_window_dpi(hwnd: HWND) {
+    let dpi = windows::Win32::UI::HiDpi::GetDpiForWindow(hwnd);
+    if dpi != 0 {
+        GUI_DPI.with(|d| d.set(dpi.max(96)));
+    }
+}
+
+/// Whether a click at `lparam` (client coordinates) falls on the close button
+unsafe fn on_close_button(hwnd: HWND, lparam: LPARAM) -> bool {
+    let mut point = windows::Win32::Foundation::POINT {
+        x: (lparam.0 & 0xFFFF) as i16 as i32,
+        y: ((lparam.0 >> 16) & 0xFFFF) as i16 as i32,
+    };
+    let mut button = windows::Win32::Foundation::RECT::default();
+    if GetWindowRect(GetDlgItem(hwnd, ID_CLOSE_BTN), &mut button).is_err() {
+        return false;
+    }
+    let _ = ClientToScreen(hwnd, &mut point);
+    PtInRect(&button, point).as_bool()
+}
+
+/// The window moved to a monitor with another DPI: take the size Windows suggests and
+/// scale every control's position and size to match, keeping what's been typed in them
+unsafe fn apply_dpi_change(hwnd: HWND, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    unsafe extern "system" fn scale_child(child: HWND, lparam: LPARAM) -> windows::Win32::Foundation::BOOL {
+        let (parent, old_dpi, new_dpi) = *(lparam.0 as *const (HWND, i32, i32));
+        // Only the window's own controls, not the parts of a combo box
+        if GetParent(child) != parent {
+            return true.into();
+        }
+        let mut rect = windows::Win32::Foundation::RECT::default();
+        let mut class = [0u16; 16];
+        let len = GetClassNameW(child, &mut class) as usize;
+        if String::from_utf16_lossy(&class[..len]).eq_ignore_ascii_case("ComboBox") {
+            // The window rect is just the closed box, the dropped rect includes the list height
+            SendMessageW(child, CB_GETDROPPEDCONTROLRECT, WPARAM(0), LPARAM(&mut rect as *mut _ as isize));
+        } else {
+            let _ = GetWindowRect(child, &mut rect);
+        }
+        let mut corners = [
+            windows::Win32::Foundation::POINT { x: rect.left, y: rect.top },
+            windows::Win32::Foundation::POINT { x: rect.right, y: rect.bottom },
+        ];
+        MapWindowPoints(HWND(0), parent, &mut corners);
+        let scale = |value: i32| value * new_dpi / old_dpi;
+        let _ = MoveWindow(
+            child,
+            scale(corners[0].x),
+            scale(corners[0].y),
+            scale(corners[1].x - corners[0].x),
+            scale(corners[1].y - corners[0].y),
+            true,
+        );
+        true.into()
+    }
+
+    let new_dpi = (((wparam.0 >> 16) & 0xFFFF) as u32).max(96);
+    let old_dpi = GUI_DPI.with(|d| d.replace(new_dpi));
+    if old_dpi != new_dpi {
+        let mut scaling = (hwnd, old_dpi as i32, new_dpi as i32);
+        let _ = EnumChildWindows(hwnd, Some(scale_child), LPARAM(&mut scaling as *mut _ as isize));
+    }
+    let suggested = &*(lparam.0 as *const windows::Win32::Foundation::RECT);
+    let _ = SetWindowPos(
+        hwnd,
+        None,
+        suggested.left,
+        suggested.top,
+        suggested.right - suggested.left,
+        suggested.bottom - suggested.top,
+        SWP_NOZORDER | SWP_NOACTIVATE,
+    );
+    LRESULT(0)
+}
+
+/// Make `theme` the calling thread's window colors and create its brushes.
+/// Called on window creation, so a theme change applies to the next window.
+unsafe fn create_theme_brushes(theme: Theme) {
+    THEME.with(|t| t.set(theme));
+    BRUSH_BACKGROUND.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(theme.background)));
+    BRUSH_ACCENT.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(theme.accent)));
+    BRUSH_CLOSE.with(|b| *b.borrow_mut() = CreateSolidBrush(COLORREF(theme.close_button)));
+}
+
+unsafe fn delete_theme_brushes() {
+    for brush in [&BRUSH_BACKGROUND, &BRUSH_ACCENT, &BRUSH_CLOSE] {
+        let _ = brush.with(|b| DeleteObject(b.replace(HBRUSH(0))));
+    }
+}
+
+/// Fill the window with the theme background
+unsafe fn erase_background(hwnd: HWND, hdc: HDC) -> LRESULT {
+    let mut rect = windows::Win32::Foundation::RECT::default();
+    let _ = GetClientRect(hwnd, &mut rect);
+    FillRect(hdc, &rect, BRUSH_BACKGROUND.with(|b| *b.borrow()));
+    LRESULT(1)
+}
+
+/// Control colors of the theme: close button, title bar, and text on the background
+/// elsewhere. The brushes are the calling thread's, created with its window.
+unsafe fn theme_color(ctrl_id: i32, hdc: HDC) -> LRESULT {
+    let theme = THEME.with(|t| t.get());
+    let (background, brush) = match ctrl_id {
+        ID_CLOSE_BTN => (theme.close_button, &BRUSH_CLOSE),
+        ID_TITLE_BAR => (theme.accent, &BRUSH_ACCENT),
+        _ => (theme.background, &BRUSH_BACKGROUND),
+    };
+    // White stays readable on the red close button in both themes
+    SetTextColor(hdc, COLORREF(if ctrl_id == ID_CLOSE_BTN { 0xFFFFFF } else { theme.text }));
+    SetBkColor(hdc, COLORREF(background));
+    LRESULT(brush.with(|b| *b.borrow()).0 as _)
+}
+
 unsafe fn create_trackbar(hwnd: HWND, id: i32, x: i32, y: i32, w: i32, h: i32, value: u8) {
     let trackbar_class = windows::core::w!("msctls_trackbar32");
     
@@ -442,7 +1419,7 @@ unsafe fn create_trackbar(hwnd: HWND, id: i32, x: i32, y: i32, w: i32, h: i32, v
         trackbar_class,
         windows::core::w!("Scale"),
         WS_CHILD | WS_VISIBLE | WINDOW_STYLE(TBS_AUTOTICKS | TBS_HORZ),
-        x, y, w, h,
+        px(x), px(y), px(w), px(h),
         hwnd, HMENU(id as _), None, None,
     );
     
@@ -0,0 +1,58 @@
+use crate::settings::{Hotkey, Settings, MOD_ALT, MOD_CONTROL};
+use parking_lot::Mutex;
+use windows::Win32::Foundation::HWND;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_NOREPEAT, VK_OEM_4, VK_OEM_6,
+};
+
+// Hotkey IDs (wParam of WM_HOTKEY)
+pub const HOTKEY_OPACITY_DOWN: i32 = 1;
+pub const HOTKEY_OPACITY_UP: i32 = 2;
+pub const HOTKEY_SUMMARY: i32 = 3;
+pub const HOTKEY_SETTINGS: i32 = 4;
+pub const HOTKEY_EXIT: i32 = 5;
+pub const HOTKEY_TOGGLE_OVERLAY: i32 = 6;
+pub const HOTKEY_CYCLE_POSITION: i32 = 7;
+pub const HOTKEY_BENCHMARK: i32 = 8;
+
+// IDs registered by this thread, so they can be released on shutdown
+static REGISTERED: once_cell::sync::Lazy<Mutex<Vec<i32>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Vec::new()));
+
+/// Register a global hotkey. WM_HOTKEY is posted to the calling thread's message queue,
+/// so this must be called from the thread running the main message loop.
+pub fn register(id: i32, hotkey: Hotkey) -> Result<(), String> {
+    unsafe {
+        RegisterHotKey(HWND(0), id, HOT_KEY_MODIFIERS(hotkey.modifiers) | MOD_NOREPEAT, hotkey.vk)
+            .map_err(|e| format!("RegisterHotKey failed: {}", e))?;
+    }
+    REGISTERED.lock().push(id);
+    Ok(())
+}
+
+/// Register the built-in hotkeys (Ctrl+Alt+[ / ] for opacity) and the configurable ones.
+/// Errors if the settings or exit hotkey could not be bound: without a tray icon those
+/// are the only way to reach the app.
+pub fn register_all(settings: &Settings) -> Result<(), String> {
+    // A key already taken by another app is not fatal, the feature is just unavailable
+    let ctrl_alt = MOD_CONTROL | MOD_ALT;
+    let _ = register(HOTKEY_OPACITY_DOWN, Hotkey { modifiers: ctrl_alt, vk: VK_OEM_4.0 as u32 });
+    let _ = register(HOTKEY_OPACITY_UP, Hotkey { modifiers: ctrl_alt, vk: VK_OEM_6.0 as u32 });
+    let _ = register(HOTKEY_SUMMARY, settings.summary_hotkey);
+    let settings_bound = register(HOTKEY_SETTINGS, settings.settings_hotkey);
+    let exit_bound = register(HOTKEY_EXIT, settings.exit_hotkey);
+    let _ = register(HOTKEY_TOGGLE_OVERLAY, settings.toggle_hotkey);
+    let _ = register(HOTKEY_CYCLE_POSITION, settings.position_hotkey);
+    let _ = register(HOTKEY_BENCHMARK, settings.benchmark_hotkey);
+    settings_bound.and(exit_bound)
+}
+
+/// Unregister every hotkey registered by `register`
+pub fn unregister_all() {
+    let mut ids = REGISTERED.lock();
+    for id in ids.drain(..) {
+        unsafe {
+            let _ = UnregisterHotKey(HWND(0), id);
+        }
+    }
+}
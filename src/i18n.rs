@@ -0,0 +1,526 @@
+//! User-facing strings of the tray menu, the settings window, toasts and startup errors.
+//! The active language is global so any module can look strings up with `tr`.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// UI language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Lang {
+    English,
+    Italian,
+    Spanish,
+}
+
+// Primary language IDs (low 10 bits of a LANGID)
+const LANG_ITALIAN: u16 = 0x10;
+const LANG_SPANISH: u16 = 0x0A;
+
+impl Lang {
+    /// Every language, in the order shown in the settings dropdown
+    pub const ALL: [Lang; 3] = [Lang::English, Lang::Italian, Lang::Spanish];
+
+    /// The Windows display language, English when it isn't one we have
+    pub fn detect() -> Self {
+        let lang_id = unsafe { windows::Win32::Globalization::GetUserDefaultUILanguage() };
+        match lang_id & 0x3FF {
+            LANG_ITALIAN => Lang::Italian,
+            LANG_SPANISH => Lang::Spanish,
+            _ => Lang::English,
+        }
+    }
+
+    /// Name of the language in itself, for the dropdown
+    pub fn native_name(self) -> &'static str {
+        match self {
+            Lang::English => "English",
+            Lang::Italian => "Italiano",
+            Lang::Spanish => "Español",
+        }
+    }
+}
+
+impl Default for Lang {
+    fn default() -> Self {
+        Self::detect()
+    }
+}
+
+/// A translatable string
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Text {
+    // Tray
+    MenuSettings,
+    MenuBenchmark,
+    MenuLastBenchmark,
+    MenuResetStats,
+    MenuLockPosition,
+    MenuOverlayShown,
+    MenuOverlayHidden,
+    MenuPauseCapture,
+    MenuResumeCapture,
+    MenuExcludeGame,
+    MenuTargetProcess,
+    MenuTargetAuto,
+    MenuPreviewOverlay,
+    MenuHideOverlay,
+    MenuResetPosition,
+    MenuResetOverlayPosition,
+    MenuExit,
+    TrayTooltip,
+    // Errors
+    ErrorTitle,
+    AlreadyRunning,
+    InitTrayFailed,
+    InitOverlayFailed,
+    InitSummaryFailed,
+    InitCaptureFailed,
+    SaveSettingsFailed,
+    StartupUpdateFailed,
+    SaveProfileFailed,
+    RelaunchAsAdmin,
+    // Settings window
+    WindowTitle,
+    Position,
+    TopRight,
+    TopLeft,
+    BottomRight,
+    BottomLeft,
+    Color,
+    CustomColor,
+    StatsColor,
+    Size,
+    Small,
+    Medium,
+    Large,
+    ShowOnePercentLow,
+    FpsAtBottom,
+    ShowCpu,
+    TrackPlaytime,
+    ShowGpu,
+    AlertOnly,
+    StartWithWindows,
+    ShowPointOneLow,
+    ShowRam,
+    FrametimeGraph,
+    ShowGpuTemp,
+    ShowPlaytime,
+    AllGpus,
+    Opacity,
+    ToggleHotkey,
+    Font,
+    MonospaceDigits,
+    FontWeight,
+    Decimals,
+    WeightLight,
+    WeightRegular,
+    WeightSemibold,
+    WeightBold,
+    Italic,
+    ClickThrough,
+    ProportionalPosition,
+    ExcludeFromCapture,
+    Language,
+    Browse,
+    AutomaticPath,
+    Save,
+    Cancel,
+    ResetDefaults,
+    ExportSettings,
+    ImportSettings,
+    ExportFailed,
+    ImportFailed,
+    SaveProfileFor,
+    // Benchmark results window
+    BenchmarkTitle,
+    AverageFps,
+    OnePercentLow,
+    PointOnePercentLow,
+    MinFps,
+    MaxFps,
+    Frames,
+    Duration,
+    Game,
+    NoBenchmark,
+    Close,
+    // Toast notifications
+    SettingsNotReloaded,
+    BenchmarkStarted,
+    BenchmarkFramesSavedTo,
+    BenchmarkStopHint,
+    BenchmarkStartedNoCsv,
+    BenchmarkNotSaved,
+    CannotWrite,
+    TargetNotSaved,
+    ExclusionNotSaved,
+    PositionNotSaved,
+    CsvLogStarted,
+    // Capture errors
+    PresentMonNotFound,
+    PresentMonBlocked,
+    AddExclusionFor,
+    PresentMonNeedsAdmin,
+    PresentMonExtractFailed,
+    PresentMonExtractHint,
+    PresentMonStartFailed,
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+/// Switch the language used by `tr`
+pub fn set_language(lang: Lang) {
+    let index = Lang::ALL.iter().position(|&l| l == lang).unwrap_or(0);
+    CURRENT.store(index as u8, Ordering::Relaxed);
+}
+
+pub fn language() -> Lang {
+    Lang::ALL.get(CURRENT.load(Ordering::Relaxed) as usize).copied().unwrap_or(Lang::English)
+}
+
+/// `text` in the current language
+pub fn tr(text: Text) -> &'static str {
+    match language() {
+        Lang::English => english(text),
+        Lang::Italian => italian(text),
+        Lang::Spanish => spanish(text),
+    }
+}
+
+fn english(text: Text) -> &'static str {
+    match text {
+        Text::MenuSettings => "Settings",
+        Text::MenuBenchmark => "Start/Stop benchmark",
+        Text::MenuLastBenchmark => "View last benchmark",
+        Text::MenuResetStats => "Reset stats",
+        Text::MenuLockPosition => "Lock overlay position",
+        Text::MenuOverlayShown => "Overlay: shown",
+        Text::MenuOverlayHidden => "Overlay: hidden",
+        Text::MenuPauseCapture => "Pause capture",
+        Text::MenuResumeCapture => "Resume capture",
+        Text::MenuExcludeGame => "Never show over this game",
+        Text::MenuTargetProcess => "Monitor process",
+        Text::MenuTargetAuto => "Automatic (fullscreen game)",
+        Text::MenuPreviewOverlay => "Preview overlay",
+        Text::MenuHideOverlay => "Hide overlay",
+        Text::MenuResetPosition => "Reset position",
+        Text::MenuResetOverlayPosition => "Reset overlay position",
+        Text::MenuExit => "Exit",
+        Text::TrayTooltip => "EasyFPS - Double click = Settings",
+        Text::ErrorTitle => "EasyFPS Error",
+        Text::AlreadyRunning => "EasyFPS is already running (see the tray icon)",
+        Text::InitTrayFailed => "Tray initialization failed",
+        Text::InitOverlayFailed => "Overlay initialization failed",
+        Text::InitSummaryFailed => "Summary panel initialization failed",
+        Text::InitCaptureFailed => "FPS capture initialization failed",
+        Text::SaveSettingsFailed => "Could not save the settings",
+        Text::StartupUpdateFailed => "Settings saved, but the Windows startup entry could not be updated",
+        Text::SaveProfileFailed => "Could not save the profile",
+        Text::RelaunchAsAdmin => "EasyFPS is not running as administrator, so PresentMon may not be able to read the FPS of games.\n\nRestart EasyFPS as administrator?",
+        Text::WindowTitle => "EasyFPS - Options",
+        Text::Position => "Position:",
+        Text::TopRight => "Top R",
+        Text::TopLeft => "Top L",
+        Text::BottomRight => "Bot R",
+        Text::BottomLeft => "Bot L",
+        Text::Color => "Color:",
+        Text::CustomColor => "Custom...",
+        Text::StatsColor => "Stats...",
+        Text::Size => "Size:",
+        Text::Small => "Small",
+        Text::Medium => "Medium",
+        Text::Large => "Large",
+        Text::ShowOnePercentLow => "Show 1% Low FPS",
+        Text::FpsAtBottom => "FPS at bottom",
+        Text::ShowCpu => "Show CPU Usage",
+        Text::TrackPlaytime => "Track playtime",
+        Text::ShowGpu => "Show GPU Usage",
+        Text::AlertOnly => "Only on FPS alerts",
+        Text::StartWithWindows => "Start with Windows",
+        Text::ShowPointOneLow => "Show 0.1% Low FPS",
+        Text::ShowRam => "Show RAM Usage",
+        Text::FrametimeGraph => "Frametime graph",
+        Text::ShowGpuTemp => "Show GPU temp",
+        Text::ShowPlaytime => "Show playtime",
+        Text::AllGpus => "All GPUs (max)",
+        Text::Opacity => "Opacity:",
+        Text::ToggleHotkey => "Toggle hotkey:",
+        Text::Font => "Font:",
+        Text::MonospaceDigits => "Fixed digits",
+        Text::FontWeight => "Weight:",
+        Text::Decimals => "Decimals:",
+        Text::WeightLight => "Light",
+        Text::WeightRegular => "Regular",
+        Text::WeightSemibold => "Semibold",
+        Text::WeightBold => "Bold",
+        Text::Italic => "Italic",
+        Text::ClickThrough => "Click-through overlay",
+        Text::ProportionalPosition => "Scale with screen",
+        Text::ExcludeFromCapture => "Hide from screen recordings",
+        Text::Language => "Language:",
+        Text::Browse => "Browse...",
+        Text::AutomaticPath => "(automatic)",
+        Text::Save => "Save",
+        Text::Cancel => "Cancel",
+        Text::ResetDefaults => "Defaults",
+        Text::ExportSettings => "Export...",
+        Text::ImportSettings => "Import...",
+        Text::ExportFailed => "Could not export the settings",
+        Text::ImportFailed => "Could not import the settings",
+        Text::SaveProfileFor => "Save as profile for",
+        Text::BenchmarkTitle => "Benchmark results",
+        Text::AverageFps => "Average FPS:",
+        Text::OnePercentLow => "1% low:",
+        Text::PointOnePercentLow => "0.1% low:",
+        Text::MinFps => "Min FPS:",
+        Text::MaxFps => "Max FPS:",
+        Text::Frames => "Frames:",
+        Text::Duration => "Duration:",
+        Text::Game => "Game:",
+        Text::NoBenchmark => "No benchmark recorded",
+        Text::Close => "Close",
+        Text::SettingsNotReloaded => "Settings not reloaded",
+        Text::BenchmarkStarted => "Benchmark started",
+        Text::BenchmarkFramesSavedTo => "Frames saved to",
+        Text::BenchmarkStopHint => "Stop it to see the results:",
+        Text::BenchmarkStartedNoCsv => "Benchmark started without CSV",
+        Text::BenchmarkNotSaved => "Benchmark results not saved",
+        Text::CannotWrite => "Cannot write",
+        Text::TargetNotSaved => "Monitored process not saved",
+        Text::ExclusionNotSaved => "Exclusion not saved",
+        Text::PositionNotSaved => "Overlay position not saved",
+        Text::CsvLogStarted => "CSV log started",
+        Text::PresentMonNotFound => "PresentMon.exe not found. Make sure it is in the same folder as the executable (or in the project root).",
+        Text::PresentMonBlocked => "PresentMon was blocked or quarantined by the antivirus.",
+        Text::AddExclusionFor => "Add an exclusion for:",
+        Text::PresentMonNeedsAdmin => "PresentMon needs administrator rights. Restart EasyFPS as administrator.",
+        Text::PresentMonExtractFailed => "Cannot extract the embedded PresentMon",
+        Text::PresentMonExtractHint => "Add an antivirus exclusion for this folder, or pick PresentMon.exe in the settings:",
+        Text::PresentMonStartFailed => "Cannot start PresentMon",
+    }
+}
+
+fn italian(text: Text) -> &'static str {
+    match text {
+        Text::MenuSettings => "Impostazioni",
+        Text::MenuBenchmark => "Avvia/Ferma benchmark",
+        Text::MenuLastBenchmark => "Ultimo benchmark",
+        Text::MenuResetStats => "Azzera statistiche",
+        Text::MenuLockPosition => "Blocca posizione overlay",
+        Text::MenuOverlayShown => "Overlay: visibile",
+        Text::MenuOverlayHidden => "Overlay: nascosto",
+        Text::MenuPauseCapture => "Sospendi rilevamento",
+        Text::MenuResumeCapture => "Riprendi rilevamento",
+        Text::MenuExcludeGame => "Non mostrare su questo gioco",
+        Text::MenuTargetProcess => "Processo monitorato",
+        Text::MenuTargetAuto => "Automatico (gioco a schermo intero)",
+        Text::MenuPreviewOverlay => "Anteprima overlay",
+        Text::MenuHideOverlay => "Nascondi overlay",
+        Text::MenuResetPosition => "Ripristina posizione",
+        Text::MenuResetOverlayPosition => "Ripristina posizione overlay",
+        Text::MenuExit => "Esci",
+        Text::TrayTooltip => "EasyFPS - Doppio click = Impostazioni",
+        Text::ErrorTitle => "Errore EasyFPS",
+        Text::AlreadyRunning => "EasyFPS è già in esecuzione (vedi l'icona nella tray)",
+        Text::InitTrayFailed => "Errore inizializzazione tray",
+        Text::InitOverlayFailed => "Errore inizializzazione overlay",
+        Text::InitSummaryFailed => "Errore inizializzazione riepilogo",
+        Text::InitCaptureFailed => "Errore inizializzazione FPS",
+        Text::SaveSettingsFailed => "Impossibile salvare le impostazioni",
+        Text::StartupUpdateFailed => "Impostazioni salvate, ma impossibile aggiornare l'avvio con Windows",
+        Text::SaveProfileFailed => "Impossibile salvare il profilo",
+        Text::RelaunchAsAdmin => "EasyFPS non è in esecuzione come amministratore, quindi PresentMon potrebbe non riuscire a leggere gli FPS dei giochi.\n\nRiavviare EasyFPS come amministratore?",
+        Text::WindowTitle => "EasyFPS - Opzioni",
+        Text::Position => "Posizione:",
+        Text::TopRight => "Alto D",
+        Text::TopLeft => "Alto S",
+        Text::BottomRight => "Basso D",
+        Text::BottomLeft => "Basso S",
+        Text::Color => "Colore:",
+        Text::CustomColor => "Scegli...",
+        Text::StatsColor => "Valori...",
+        Text::Size => "Dimensione:",
+        Text::Small => "Piccola",
+        Text::Medium => "Media",
+        Text::Large => "Grande",
+        Text::ShowOnePercentLow => "Mostra 1% Low",
+        Text::FpsAtBottom => "FPS in basso",
+        Text::ShowCpu => "Mostra uso CPU",
+        Text::TrackPlaytime => "Tempo di gioco",
+        Text::ShowGpu => "Mostra uso GPU",
+        Text::AlertOnly => "Solo con avvisi FPS",
+        Text::StartWithWindows => "Avvia con Windows",
+        Text::ShowPointOneLow => "Mostra 0.1% Low",
+        Text::ShowRam => "Mostra uso RAM",
+        Text::FrametimeGraph => "Grafico frametime",
+        Text::ShowGpuTemp => "Mostra temp. GPU",
+        Text::ShowPlaytime => "Mostra tempo di gioco",
+        Text::AllGpus => "Tutte le GPU (max)",
+        Text::Opacity => "Opacità:",
+        Text::ToggleHotkey => "Mostra/nascondi:",
+        Text::Font => "Carattere:",
+        Text::MonospaceDigits => "Cifre fisse",
+        Text::FontWeight => "Peso:",
+        Text::Decimals => "Decimali:",
+        Text::WeightLight => "Sottile",
+        Text::WeightRegular => "Normale",
+        Text::WeightSemibold => "Semigrassetto",
+        Text::WeightBold => "Grassetto",
+        Text::Italic => "Corsivo",
+        Text::ClickThrough => "Overlay trasparente ai clic",
+        Text::ProportionalPosition => "Scala con lo schermo",
+        Text::ExcludeFromCapture => "Nascondi dalle registrazioni",
+        Text::Language => "Lingua:",
+        Text::Browse => "Sfoglia...",
+        Text::AutomaticPath => "(automatico)",
+        Text::Save => "Salva",
+        Text::Cancel => "Annulla",
+        Text::ResetDefaults => "Predefiniti",
+        Text::ExportSettings => "Esporta...",
+        Text::ImportSettings => "Importa...",
+        Text::ExportFailed => "Impossibile esportare le impostazioni",
+        Text::ImportFailed => "Impossibile importare le impostazioni",
+        Text::SaveProfileFor => "Salva come profilo per",
+        Text::BenchmarkTitle => "Risultati benchmark",
+        Text::AverageFps => "FPS medi:",
+        Text::OnePercentLow => "1% low:",
+        Text::PointOnePercentLow => "0.1% low:",
+        Text::MinFps => "FPS minimi:",
+        Text::MaxFps => "FPS massimi:",
+        Text::Frames => "Frame:",
+        Text::Duration => "Durata:",
+        Text::Game => "Gioco:",
+        Text::NoBenchmark => "Nessun benchmark registrato",
+        Text::Close => "Chiudi",
+        Text::SettingsNotReloaded => "Impostazioni non ricaricate",
+        Text::BenchmarkStarted => "Benchmark avviato",
+        Text::BenchmarkFramesSavedTo => "Frame salvati in",
+        Text::BenchmarkStopHint => "Fermalo per vedere i risultati:",
+        Text::BenchmarkStartedNoCsv => "Benchmark avviato senza CSV",
+        Text::BenchmarkNotSaved => "Risultati benchmark non salvati",
+        Text::CannotWrite => "Impossibile scrivere",
+        Text::TargetNotSaved => "Processo monitorato non salvato",
+        Text::ExclusionNotSaved => "Esclusione non salvata",
+        Text::PositionNotSaved => "Posizione overlay non salvata",
+        Text::CsvLogStarted => "Log CSV avviato",
+        Text::PresentMonNotFound => "PresentMon.exe non trovato. Assicurati che sia nella stessa cartella dell'eseguibile (o nella root del progetto).",
+        Text::PresentMonBlocked => "PresentMon è stato bloccato o messo in quarantena dall'antivirus.",
+        Text::AddExclusionFor => "Aggiungi un'esclusione per:",
+        Text::PresentMonNeedsAdmin => "PresentMon richiede i privilegi di amministratore. Riavvia EasyFPS come amministratore.",
+        Text::PresentMonExtractFailed => "Impossibile estrarre PresentMon integrato",
+        Text::PresentMonExtractHint => "Aggiungi un'esclusione antivirus per questa cartella oppure indica PresentMon.exe nelle impostazioni:",
+        Text::PresentMonStartFailed => "Impossibile avviare PresentMon",
+    }
+}
+
+fn spanish(text: Text) -> &'static str {
+    match text {
+        Text::MenuSettings => "Configuración",
+        Text::MenuBenchmark => "Iniciar/Detener benchmark",
+        Text::MenuLastBenchmark => "Ver último benchmark",
+        Text::MenuResetStats => "Reiniciar estadísticas",
+        Text::MenuLockPosition => "Bloquear posición del overlay",
+        Text::MenuOverlayShown => "Overlay: visible",
+        Text::MenuOverlayHidden => "Overlay: oculto",
+        Text::MenuPauseCapture => "Pausar captura",
+        Text::MenuResumeCapture => "Reanudar captura",
+        Text::MenuExcludeGame => "No mostrar en este juego",
+        Text::MenuTargetProcess => "Proceso monitorizado",
+        Text::MenuTargetAuto => "Automático (juego a pantalla completa)",
+        Text::MenuPreviewOverlay => "Vista previa del overlay",
+        Text::MenuHideOverlay => "Ocultar overlay",
+        Text::MenuResetPosition => "Restablecer posición",
+        Text::MenuResetOverlayPosition => "Restablecer posición del overlay",
+        Text::MenuExit => "Salir",
+        Text::TrayTooltip => "EasyFPS - Doble clic = Configuración",
+        Text::ErrorTitle => "Error de EasyFPS",
+        Text::AlreadyRunning => "EasyFPS ya se está ejecutando (mira el icono de la bandeja)",
+        Text::InitTrayFailed => "Error al iniciar el icono de bandeja",
+        Text::InitOverlayFailed => "Error al iniciar el overlay",
+        Text::InitSummaryFailed => "Error al iniciar el panel de resumen",
+        Text::InitCaptureFailed => "Error al iniciar la captura de FPS",
+        Text::SaveSettingsFailed => "No se pudo guardar la configuración",
+        Text::StartupUpdateFailed => "Configuración guardada, pero no se pudo actualizar el inicio con Windows",
+        Text::SaveProfileFailed => "No se pudo guardar el perfil",
+        Text::RelaunchAsAdmin => "EasyFPS no se está ejecutando como administrador, así que PresentMon podría no leer los FPS de los juegos.\n\n¿Reiniciar EasyFPS como administrador?",
+        Text::WindowTitle => "EasyFPS - Opciones",
+        Text::Position => "Posición:",
+        Text::TopRight => "Arr D",
+        Text::TopLeft => "Arr I",
+        Text::BottomRight => "Abj D",
+        Text::BottomLeft => "Abj I",
+        Text::Color => "Color:",
+        Text::CustomColor => "Elegir...",
+        Text::StatsColor => "Valores...",
+        Text::Size => "Tamaño:",
+        Text::Small => "Pequeño",
+        Text::Medium => "Mediano",
+        Text::Large => "Grande",
+        Text::ShowOnePercentLow => "Mostrar 1% Low",
+        Text::FpsAtBottom => "FPS abajo",
+        Text::ShowCpu => "Mostrar uso CPU",
+        Text::TrackPlaytime => "Tiempo de juego",
+        Text::ShowGpu => "Mostrar uso GPU",
+        Text::AlertOnly => "Solo con alertas FPS",
+        Text::StartWithWindows => "Iniciar con Windows",
+        Text::ShowPointOneLow => "Mostrar 0.1% Low",
+        Text::ShowRam => "Mostrar uso RAM",
+        Text::FrametimeGraph => "Gráfico frametime",
+        Text::ShowGpuTemp => "Mostrar temp. GPU",
+        Text::ShowPlaytime => "Mostrar tiempo de juego",
+        Text::AllGpus => "Todas las GPU (máx.)",
+        Text::Opacity => "Opacidad:",
+        Text::ToggleHotkey => "Mostrar/ocultar:",
+        Text::Font => "Fuente:",
+        Text::MonospaceDigits => "Dígitos fijos",
+        Text::FontWeight => "Grosor:",
+        Text::Decimals => "Decimales:",
+        Text::WeightLight => "Fina",
+        Text::WeightRegular => "Normal",
+        Text::WeightSemibold => "Seminegrita",
+        Text::WeightBold => "Negrita",
+        Text::Italic => "Cursiva",
+        Text::ClickThrough => "Overlay sin clics",
+        Text::ProportionalPosition => "Escalar con pantalla",
+        Text::ExcludeFromCapture => "Ocultar en grabaciones",
+        Text::Language => "Idioma:",
+        Text::Browse => "Examinar...",
+        Text::AutomaticPath => "(automático)",
+        Text::Save => "Guardar",
+        Text::Cancel => "Cancelar",
+        Text::ResetDefaults => "Restablecer",
+        Text::ExportSettings => "Exportar...",
+        Text::ImportSettings => "Importar...",
+        Text::ExportFailed => "No se pudo exportar la configuración",
+        Text::ImportFailed => "No se pudo importar la configuración",
+        Text::SaveProfileFor => "Guardar como perfil para",
+        Text::BenchmarkTitle => "Resultados del benchmark",
+        Text::AverageFps => "FPS medios:",
+        Text::OnePercentLow => "1% low:",
+        Text::PointOnePercentLow => "0.1% low:",
+        Text::MinFps => "FPS mínimos:",
+        Text::MaxFps => "FPS máximos:",
+        Text::Frames => "Fotogramas:",
+        Text::Duration => "Duración:",
+        Text::Game => "Juego:",
+        Text::NoBenchmark => "Ningún benchmark registrado",
+        Text::Close => "Cerrar",
+        Text::SettingsNotReloaded => "Configuración no recargada",
+        Text::BenchmarkStarted => "Benchmark iniciado",
+        Text::BenchmarkFramesSavedTo => "Fotogramas guardados en",
+        Text::BenchmarkStopHint => "Detenlo para ver los resultados:",
+        Text::BenchmarkStartedNoCsv => "Benchmark iniciado sin CSV",
+        Text::BenchmarkNotSaved => "Resultados del benchmark no guardados",
+        Text::CannotWrite => "No se puede escribir",
+        Text::TargetNotSaved => "Proceso supervisado no guardado",
+        Text::ExclusionNotSaved => "Exclusión no guardada",
+        Text::PositionNotSaved => "Posición del overlay no guardada",
+        Text::CsvLogStarted => "Registro CSV iniciado",
+        Text::PresentMonNotFound => "No se encontró PresentMon.exe. Asegúrate de que esté en la misma carpeta que el ejecutable (o en la raíz del proyecto).",
+        Text::PresentMonBlocked => "El antivirus bloqueó o puso en cuarentena PresentMon.",
+        Text::AddExclusionFor => "Añade una exclusión para:",
+        Text::PresentMonNeedsAdmin => "PresentMon necesita privilegios de administrador. Reinicia EasyFPS como administrador.",
+        Text::PresentMonExtractFailed => "No se pudo extraer el PresentMon integrado",
+        Text::PresentMonExtractHint => "Añade una exclusión del antivirus para esta carpeta o elige PresentMon.exe en la configuración:",
+        Text::PresentMonStartFailed => "No se pudo iniciar PresentMon",
+    }
+}
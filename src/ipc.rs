@@ -0,0 +1,177 @@
+//! Optional named-pipe server so other tools (stream deck plugins, scripts) can read the
+//! live stats. Each client that connects to `\\.\pipe\EasyFPS` gets one JSON line with the
+//! latest values and is then disconnected (once it closes its end, or after CLIENT_TIMEOUT);
+//! the next client is served the same way.
+
+use parking_lot::Mutex;
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::JoinHandle;
+use std::time::Duration;
+use windows::core::{w, PCWSTR};
+use windows::Win32::Foundation::{
+    CloseHandle, ERROR_IO_PENDING, ERROR_PIPE_CONNECTED, GENERIC_READ, HANDLE, WAIT_OBJECT_0,
+};
+use windows::Win32::Storage::FileSystem::{
+    CreateFileW, ReadFile, WriteFile, FILE_FLAGS_AND_ATTRIBUTES, FILE_FLAG_FIRST_PIPE_INSTANCE,
+    FILE_FLAG_OVERLAPPED, FILE_SHARE_NONE, OPEN_EXISTING, PIPE_ACCESS_DUPLEX,
+};
+use windows::Win32::System::Pipes::{
+    ConnectNamedPipe, CreateNamedPipeW, DisconnectNamedPipe, PIPE_REJECT_REMOTE_CLIENTS,
+    PIPE_TYPE_BYTE, PIPE_WAIT,
+};
+use windows::Win32::System::Threading::{CreateEventW, WaitForSingleObject, INFINITE};
+use windows::Win32::System::IO::{CancelIoEx, GetOverlappedResult, OVERLAPPED};
+
+const PIPE_NAME: PCWSTR = w!(r"\\.\pipe\EasyFPS");
+// Output buffer of the pipe, a stats line is far smaller
+const PIPE_BUFFER: u32 = 4096;
+// How long a client gets to read its line and close; one that doesn't is disconnected
+// anyway, so it can't hold up the next client or shutdown
+const CLIENT_TIMEOUT_MS: u32 = 1000;
+
+/// What a client reads: the stats the overlay is currently showing
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Snapshot {
+    pub fps: f64,
+    pub one_percent_low: f64,
+    pub cpu: f32,
+    pub gpu: f32,
+    /// Executable of the tracked game (null when no game is running)
+    pub process: Option<String>,
+}
+
+static LATEST: once_cell::sync::Lazy<Mutex<Snapshot>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Snapshot::default()));
+// Requested state, so a server that gave up isn't restarted on every update
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static SERVER: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Replace the stats served to the next client
+pub fn publish(snapshot: Snapshot) {
+    *LATEST.lock() = snapshot;
+}
+
+/// Start or stop the pipe server to match the `enable_ipc` setting
+pub fn set_enabled(enabled: bool) {
+    if ENABLED.swap(enabled, Ordering::SeqCst) == enabled {
+        return;
+    }
+    if enabled {
+        RUNNING.store(true, Ordering::SeqCst);
+        *SERVER.lock() = Some(std::thread::spawn(run_server));
+    } else {
+        shutdown();
+    }
+}
+
+/// Stop the pipe server and wait for its thread
+pub fn shutdown() {
+    ENABLED.store(false, Ordering::SeqCst);
+    if !RUNNING.swap(false, Ordering::SeqCst) {
+        // Not started, or the server already gave up
+        if let Some(handle) = SERVER.lock().take() {
+            let _ = handle.join();
+        }
+        return;
+    }
+    let Some(handle) = SERVER.lock().take() else {
+        return;
+    };
+    // The server is blocked waiting for a client: connect to wake it up, again if it was
+    // between two pipe instances when we tried
+    while !handle.is_finished() {
+        unsafe {
+            if let Ok(client) = CreateFileW(
+                PIPE_NAME,
+                GENERIC_READ.0,
+                FILE_SHARE_NONE,
+                None,
+                OPEN_EXISTING,
+                FILE_FLAGS_AND_ATTRIBUTES(0),
+                None,
+            ) {
+                let _ = CloseHandle(client);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let _ = handle.join();
+}
+
+fn run_server() {
+    while RUNNING.load(Ordering::SeqCst) {
+        let pipe = unsafe {
+            CreateNamedPipeW(
+                PIPE_NAME,
+                // Fails when another EasyFPS already serves the pipe. Duplex only so the
+                // server can wait for the client to close its end (a pending read ends).
+                PIPE_ACCESS_DUPLEX | FILE_FLAG_FIRST_PIPE_INSTANCE | FILE_FLAG_OVERLAPPED,
+                PIPE_TYPE_BYTE | PIPE_WAIT | PIPE_REJECT_REMOTE_CLIENTS,
+                1,
+                PIPE_BUFFER,
+                0,
+                0,
+                None,
+            )
+        };
+        if pipe.is_invalid() {
+            RUNNING.store(false, Ordering::SeqCst);
+            return;
+        }
+
+        unsafe {
+            if let Ok(event) = CreateEventW(None, true, false, PCWSTR::null()) {
+                let mut overlapped = OVERLAPPED { hEvent: event, ..Default::default() };
+                // Waits until a client (or shutdown's wake-up connection) arrives.
+                // ERROR_PIPE_CONNECTED: the client connected before we started waiting.
+                let connected = match ConnectNamedPipe(pipe, Some(&mut overlapped)) {
+                    Ok(()) => true,
+                    Err(e) if e.code() == ERROR_PIPE_CONNECTED.to_hresult() => true,
+                    Err(e) => finish_io(pipe, &overlapped, e, INFINITE),
+                };
+                if connected && RUNNING.load(Ordering::SeqCst) {
+                    serve_client(pipe, &mut overlapped);
+                }
+                let _ = CloseHandle(event);
+            }
+            let _ = DisconnectNamedPipe(pipe);
+            let _ = CloseHandle(pipe);
+        }
+    }
+}
+
+/// Write the latest stats as one JSON line, then give the client CLIENT_TIMEOUT_MS to read
+/// it and close its end (disconnecting earlier would discard the unread line)
+unsafe fn serve_client(pipe: HANDLE, overlapped: &mut OVERLAPPED) {
+    let snapshot = LATEST.lock().clone();
+    let Ok(mut line) = serde_json::to_string(&snapshot) else {
+        return;
+    };
+    line.push('\n');
+    let written = match WriteFile(pipe, Some(line.as_bytes()), None, Some(overlapped)) {
+        Ok(()) => true,
+        Err(e) => finish_io(pipe, overlapped, e, CLIENT_TIMEOUT_MS),
+    };
+    if written {
+        // Ends with an error once the client closes its end
+        let mut byte = [0u8; 1];
+        if let Err(e) = ReadFile(pipe, Some(&mut byte), None, Some(overlapped)) {
+            finish_io(pipe, overlapped, e, CLIENT_TIMEOUT_MS);
+        }
+    }
+}
+
+/// Wait up to `timeout_ms` for an overlapped operation that returned `started`, cancelling
+/// it on timeout. True if it completed successfully.
+unsafe fn finish_io(pipe: HANDLE, overlapped: &OVERLAPPED, started: windows::core::Error, timeout_ms: u32) -> bool {
+    if started.code() != ERROR_IO_PENDING.to_hresult() {
+        return false;
+    }
+    if WaitForSingleObject(overlapped.hEvent, timeout_ms) != WAIT_OBJECT_0 {
+        let _ = CancelIoEx(pipe, Some(overlapped));
+    }
+    let mut transferred = 0;
+    GetOverlappedResult(pipe, overlapped, &mut transferred, true).is_ok()
+}
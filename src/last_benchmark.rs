@@ -0,0 +1,67 @@
+use crate::fps_capture::FrameStats;
+use crate::settings::Settings;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// Summary of the most recent benchmark run, kept across restarts in last_benchmark.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LastBenchmark {
+    /// Game the run was captured on (None when its name couldn't be read)
+    pub process: Option<String>,
+    pub frames: usize,
+    pub duration_ms: f64,
+    pub avg_fps: f64,
+    pub one_percent_low: f64,
+    pub point_one_percent_low: f64,
+    pub min_fps: f64,
+    pub max_fps: f64,
+}
+
+impl LastBenchmark {
+    fn file_path() -> PathBuf {
+        Settings::config_dir().join("last_benchmark.json")
+    }
+
+    pub fn new(stats: &FrameStats, process: Option<String>) -> Self {
+        Self {
+            process,
+            frames: stats.frames,
+            duration_ms: stats.duration_ms,
+            avg_fps: stats.avg_fps,
+            one_percent_low: stats.one_percent_low,
+            point_one_percent_low: stats.point_one_percent_low,
+            min_fps: stats.min_fps,
+            max_fps: stats.max_fps,
+        }
+    }
+
+    /// The saved run, None when there's none or the file can't be read
+    pub fn load() -> Option<Self> {
+        let content = fs::read_to_string(Self::file_path()).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::file_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize benchmark: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// The values the results window shows
+    pub fn stats(&self) -> FrameStats {
+        FrameStats {
+            frames: self.frames,
+            duration_ms: self.duration_ms,
+            avg_fps: self.avg_fps,
+            one_percent_low: self.one_percent_low,
+            point_one_percent_low: self.point_one_percent_low,
+            min_fps: self.min_fps,
+            max_fps: self.max_fps,
+            ..Default::default()
+        }
+    }
+}
@@ -0,0 +1,19 @@
+//! EasyFPS core: PresentMon frame capture, system monitoring, fullscreen game detection
+//! and the settings they read. The tray/overlay app in `main.rs` is built on top of this;
+//! other tools can use it without any of the UI.
+//!
+//! ```no_run
+//! let capture = easyfps::fps_capture::Capture::new();
+//! capture.init(None)?;
+//! if let Some(game) = easyfps::fullscreen::get_fullscreen_app() {
+//!     println!("{:?}", capture.get_fps_for_process(game.process_id));
+//! }
+//! # Ok::<(), easyfps::fps_capture::CaptureError>(())
+//! ```
+
+pub mod fps_capture;
+pub mod fullscreen;
+pub mod i18n;
+pub mod log;
+pub mod monitor;
+pub mod settings;
@@ -0,0 +1,82 @@
+//! Leveled logging to `%LOCALAPPDATA%\EasyFPS\debug.log`. The app is a GUI subsystem
+//! program with no console, so this file is where diagnostics end up.
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+// debug.log size before it's rotated to debug.old.log
+const MAX_LOG_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Most verbose level written to the log, set by the `log_level` setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, Default)]
+pub enum LogLevel {
+    Error,
+    #[default]
+    Warn,
+    /// Verbose: capture start/stop, target changes, detected PresentMon format
+    Info,
+}
+
+impl LogLevel {
+    fn tag(self) -> &'static str {
+        match self {
+            LogLevel::Error => "ERROR",
+            LogLevel::Warn => "WARN",
+            LogLevel::Info => "INFO",
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Warn as u8);
+// Held while appending or rotating, so lines from different threads don't interleave
+static FILE_LOCK: Mutex<()> = Mutex::new(());
+
+/// Apply the `log_level` setting
+pub fn set_level(level: LogLevel) {
+    LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are written
+pub fn enabled(level: LogLevel) -> bool {
+    level as u8 <= LEVEL.load(Ordering::Relaxed)
+}
+
+pub fn error(msg: &str) {
+    write(LogLevel::Error, msg);
+}
+
+pub fn warn(msg: &str) {
+    write(LogLevel::Warn, msg);
+}
+
+pub fn info(msg: &str) {
+    write(LogLevel::Info, msg);
+}
+
+fn write(level: LogLevel, msg: &str) {
+    if !enabled(level) {
+        return;
+    }
+    let Some(mut path) = dirs::data_local_dir() else {
+        return;
+    };
+    path.push("EasyFPS");
+    let _ = std::fs::create_dir_all(&path);
+    path.push("debug.log");
+
+    let _guard = FILE_LOCK.lock();
+    if std::fs::metadata(&path).is_ok_and(|meta| meta.len() >= MAX_LOG_BYTES) {
+        let _ = std::fs::rename(&path, path.with_file_name("debug.old.log"));
+    }
+    if let Ok(mut file) = std::fs::OpenOptions::new().create(true).append(true).open(&path) {
+        let _ = writeln!(file, "[{}] {:5} {}", timestamp(), level.tag(), msg);
+    }
+}
+
+fn timestamp() -> String {
+    let now = std::time::SystemTime::now();
+    let dur = now.duration_since(std::time::UNIX_EPOCH).unwrap_or_default();
+    format!("{}", dur.as_secs())
+}
@@ -1,19 +1,30 @@
 #![windows_subsystem = "windows"]
 
-mod fps_capture;
-mod fullscreen;
+mod alert;
+mod cli;
+mod csv_log;
+mod elevation;
 mod gui;
-mod monitor;
+mod hotkey;
+mod ipc;
+mod last_benchmark;
+mod notify;
 mod overlay;
-mod settings;
+mod playtime;
+mod summary;
+mod template;
 mod tray;
 
+use easyfps::{fps_capture, fullscreen, i18n, log, monitor, settings};
+use i18n::{tr, Text};
 use parking_lot::Mutex;
-use settings::Settings;
+use settings::{
+    CsvColumn, OverlayLayout, OverlayMode, Profiles, Settings, TargetSpec, OVERLAY_UPDATE_HZ_MAX, OVERLAY_UPDATE_HZ_MIN,
+};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use windows::Win32::UI::WindowsAndMessaging::{
-    DispatchMessageW, PeekMessageW, TranslateMessage, MSG, PM_REMOVE,
+    DispatchMessageW, PeekMessageW, TranslateMessage, MSG, MB_ICONWARNING, PM_REMOVE, WM_HOTKEY,
 };
 
 fn main() {
@@ -24,43 +35,148 @@ fn main() {
         // ma puliamo le risorse critiche.
         fps_capture::shutdown();
         overlay::shutdown();
+        summary::shutdown();
+        notify::shutdown();
+        ipc::shutdown();
         tray::shutdown();
         std::process::exit(0);
     }).expect("Error setting Ctrl-C handler");
 
-    // Load settings
-    let settings = Arc::new(Mutex::new(Settings::load()));
+    // A panic (on any thread: overlay, capture reader) is logged and ends the app cleanly,
+    // so PresentMon and its ETW session aren't left running behind a half-dead EasyFPS
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        log::error(&format!("Panic: {}", info));
+        // The hook runs before unwinding, so the panicking thread may still hold a lock the
+        // cleanup needs: run it on its own thread and don't wait forever (the job object
+        // still ends PresentMon when we exit). The overlay window and tray icon belong to
+        // their threads and go away with the process.
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            fps_capture::shutdown();
+            let _ = done_tx.send(());
+        });
+        let _ = done_rx.recv_timeout(PANIC_CLEANUP_TIMEOUT);
+        default_hook(info);
+        std::process::exit(101);
+    }));
+
+    // Per-monitor DPI awareness (also declared in the manifest; this covers builds without it).
+    // Fails harmlessly when the manifest already set it.
+    unsafe {
+        use windows::Win32::UI::HiDpi::{SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2};
+        let _ = SetProcessDpiAwarenessContext(DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2);
+    }
+
+    // Load settings, command-line flags override them for this session
+    let mut loaded_settings = Settings::load();
+    i18n::set_language(loaded_settings.language);
+    let args = match cli::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            show_error_message(&e);
+            return;
+        }
+    };
+    cli::apply(&mut loaded_settings);
+    let settings = Arc::new(Mutex::new(loaded_settings));
+    let profiles = Arc::new(Mutex::new(Profiles::load()));
+    let mut applied_language = settings.lock().language;
     
-    // Initialize tray first (needs to be on main thread)
-    if let Err(e) = tray::init() {
-        show_error_message(&format!("Errore inizializzazione tray: {}", e));
+    let mut no_tray = args.no_tray;
+    
+    // PresentMon needs administrator rights for ETW: offer a restart as administrator,
+    // once. Declining it (here or at the UAC prompt) keeps going in limited mode.
+    if !args.relaunched && !elevation::is_elevated() && ask_yes_no(tr(Text::RelaunchAsAdmin)) && elevation::relaunch_as_admin() {
+        return;
+    }
+    
+    // One EasyFPS at a time: two would fight over the PresentMon ETW session and
+    // both show a tray icon. Taken after the elevation relaunch, whose parent exits.
+    let Some(instance) = SingleInstance::acquire() else {
+        show_error_message(tr(Text::AlreadyRunning));
         return;
+    };
+    
+    // Global hotkeys are bound to this thread's message queue. With --no-tray they are
+    // the only way to open the settings or exit: if either is taken, keep the tray.
+    if let Err(e) = hotkey::register_all(&settings.lock()) {
+        if no_tray {
+            log::error(&format!("Hotkeys unavailable ({}), showing the tray icon anyway", e));
+            no_tray = false;
+        }
+    }
+    
+    // Initialize tray first (needs to be on main thread)
+    if !no_tray {
+        if let Err(e) = tray::init() {
+            show_error_message(&format!("{}: {}", tr(Text::InitTrayFailed), e));
+            return;
+        }
     }
     
     // Initialize overlay
     if let Err(e) = overlay::init() {
-        show_error_message(&format!("Errore inizializzazione overlay: {}", e));
+        show_error_message(&format!("{}: {}", tr(Text::InitOverlayFailed), e));
+        return;
+    }
+    
+    // Initialize the on-demand summary panel
+    if let Err(e) = summary::init() {
+        show_error_message(&format!("{}: {}", tr(Text::InitSummaryFailed), e));
         return;
     }
     
-    // Initialize FPS capture
-    if let Err(e) = fps_capture::init() {
-        // Se fallisce (es. no admin), mostriamo errore ma proviamo a continuare
-        show_error_message(&format!("Errore inizializzazione FPS (Admin richiesto?): {}", e));
+    log::set_level(settings.lock().log_level);
+    // Initialize FPS capture, with the backend picked in the settings
+    fps_capture::configure(&settings.lock());
+    let presentmon_path = settings.lock().presentmon_path.clone();
+    if let Err(e) = fps_capture::init(presentmon_path.as_deref()) {
+        // Se fallisce (es. no admin, antivirus), mostriamo errore ma proviamo a continuare
+        show_error_message(&format!("{}: {}", tr(Text::InitCaptureFailed), e));
     }
     
-    // Clone settings for the callback
-    let settings_for_callback = Arc::clone(&settings);
+    // --benchmark: the run is stopped from the tray like one started there
+    if let Some(path) = &args.benchmark {
+        if let Err(e) = fps_capture::start_benchmark(path) {
+            notify::notify(tr(Text::BenchmarkStartedNoCsv), &format!("{} {}: {}", tr(Text::CannotWrite), path.display(), e));
+        }
+    }
     
     // Initialize System Monitor
     let mut sys_monitor = monitor::SystemMonitor::new();
     let mut last_stats_update = Instant::now();
 
     let mut last_update = Instant::now();
+    let mut playtime_tracker = playtime::PlaytimeTracker::load();
+    let mut current_playtime: Option<u64> = None;
+    let mut game_name: Option<(u32, String)> = None;
+    // Executable of the fullscreen app, for profiles and the settings window
+    let mut current_game: Option<String> = None;
+    let mut alert_gate = alert::AlertGate::default();
+    let mut fps_band = alert::FpsBandGate::default();
+    let mut low_fps_alarm = alert::LowFpsAlarm::default();
+    let mut fullscreen_hold = fullscreen::FullscreenHold::default();
+    let mut capture_target = CaptureTarget::default();
+    let mut target_override = TargetOverride::default();
+    // When a game was last in front, for hide_delay_ms
+    let mut last_game_seen: Option<Instant> = None;
+    let mut stats_logger = csv_log::StatsLogger::default();
+    let mut last_capture_error: Option<fps_capture::CaptureError> = None;
+    let mut settings_watcher = settings::SettingsWatcher::new();
+    let mut refresh_cache: Option<RefreshRateCache> = None;
+    let mut capture_paused = false;
+    // Capture stopped by idle_capture_timeout_secs, restarted when a game appears
+    let mut capture_idle = false;
+    // When the last game went away (None while one is running)
+    let mut no_game_since: Option<Instant> = None;
+    // When the tray's overlay preview was started (None = not previewing)
+    let mut preview_started: Option<Instant> = None;
     
     // Main message loop
     loop {
         // Process Windows messages (required for tray icon to work)
+        let mut pressed_hotkeys: Vec<i32> = Vec::new();
         unsafe {
             let mut msg = MSG::default();
             // PeekMessage non blocca, permette al loop di girare
@@ -68,67 +184,435 @@ fn main() {
                 if msg.message == windows::Win32::UI::WindowsAndMessaging::WM_QUIT {
                     break;
                 }
+                if msg.message == WM_HOTKEY {
+                    pressed_hotkeys.push(msg.wParam.0 as i32);
+                    continue;
+                }
                 let _ = TranslateMessage(&msg);
                 DispatchMessageW(&msg);
             }
         }
         
+        let mut exit_requested = false;
+        
+        // Handle global hotkeys
+        for id in pressed_hotkeys {
+            match id {
+                hotkey::HOTKEY_OPACITY_DOWN | hotkey::HOTKEY_OPACITY_UP => {
+                    // Live change only: it is persisted if the user saves from the settings window
+                    let mut s = settings.lock();
+                    s.overlay_opacity = settings::step_opacity(s.overlay_opacity, id == hotkey::HOTKEY_OPACITY_UP);
+                }
+                hotkey::HOTKEY_SUMMARY => {
+                    if summary::is_visible() {
+                        summary::hide();
+                    } else {
+                        summary::show(&fps_capture::get_stats(), current_playtime, settings.lock().number_locale);
+                    }
+                }
+                hotkey::HOTKEY_SETTINGS => open_settings(&settings, &profiles, current_game.clone()),
+                hotkey::HOTKEY_EXIT => exit_requested = true,
+                hotkey::HOTKEY_TOGGLE_OVERLAY => {
+                    overlay::toggle_enabled();
+                    tray::refresh_menu();
+                }
+                hotkey::HOTKEY_BENCHMARK => toggle_benchmark(&settings, current_game.clone()),
+                hotkey::HOTKEY_CYCLE_POSITION => {
+                    // The next show() moves the overlay there
+                    let position = {
+                        let mut s = settings.lock();
+                        s.position = s.position.next();
+                        s.position
+                    };
+                    save_position(position);
+                }
+                _ => {}
+            }
+        }
+        
         // Check for tray menu events
-        if let Some(menu_id) = tray::check_menu_event() {
+        let (double_click_ms, single_click) = {
+            let s = settings.lock();
+            (s.tray_double_click_ms, s.tray_single_click_action)
+        };
+        // The overlay's right-click menu reuses the tray's entries
+        let menu_event = tray::check_menu_event(double_click_ms, single_click)
+            .or_else(|| overlay::take_menu_command().map(str::to_string));
+        if let Some(menu_id) = menu_event {
             match menu_id.as_str() {
-                tray::MENU_SETTINGS => {
-                    if !gui::is_open() {
-                        let current_settings = settings.lock().clone();
-                        let settings_clone = Arc::clone(&settings_for_callback);
-                        
-                        gui::open(current_settings, move |new_settings| {
-                            let mut s = settings_clone.lock();
-                            *s = new_settings;
-                        });
+                tray::MENU_SETTINGS => open_settings(&settings, &profiles, current_game.clone()),
+                tray::MENU_BENCHMARK => toggle_benchmark(&settings, current_game.clone()),
+                tray::MENU_LAST_BENCHMARK => match last_benchmark::LastBenchmark::load() {
+                    Some(last) => {
+                        let (locale, theme) = {
+                            let s = settings.lock();
+                            (s.number_locale, s.gui_theme)
+                        };
+                        gui::show_benchmark_results(last.stats(), last.process, locale, theme);
+                    }
+                    // Deleted or broken since the menu was built
+                    None => notify::notify(tr(Text::MenuLastBenchmark), tr(Text::NoBenchmark)),
+                },
+                tray::MENU_RESET_STATS => fps_capture::reset_stats(),
+                overlay::MENU_RESET_POSITION => {
+                    let position = Settings::default().position;
+                    cli::clear_position();
+                    settings.lock().position = position;
+                    save_position(position);
+                }
+                tray::MENU_TOGGLE_OVERLAY => {
+                    overlay::toggle_enabled();
+                    tray::refresh_menu();
+                }
+                // Stops PresentMon altogether, to free its resources while away
+                tray::MENU_PAUSE_CAPTURE => {
+                    capture_paused = !capture_paused;
+                    capture_idle = false;
+                    no_game_since = None;
+                    if capture_paused {
+                        fps_capture::shutdown();
+                    } else if let Err(e) = fps_capture::init(settings.lock().presentmon_path.as_deref()) {
+                        show_error_message(&format!("{}: {}", tr(Text::InitCaptureFailed), e));
                     }
+                    tray::set_capture_paused(capture_paused);
+                }
+                // Toggles the preview, which shows while no game is running
+                tray::MENU_PREVIEW_OVERLAY => {
+                    preview_started = match preview_started {
+                        Some(_) => None,
+                        None => Some(Instant::now()),
+                    };
+                }
+                // Adds the game the overlay is showing over to process_blacklist
+                tray::MENU_EXCLUDE_GAME => {
+                    if let Some(game) = current_game.clone() {
+                        settings.lock().process_blacklist.push(game.clone());
+                        let mut saved = Settings::load();
+                        saved.process_blacklist.push(game);
+                        if let Err(e) = saved.save() {
+                            notify::notify(tr(Text::ExclusionNotSaved), &e);
+                        }
+                    }
+                }
+                tray::MENU_TARGET_AUTO => save_target_override(&settings, None),
+                id if id.starts_with(tray::MENU_TARGET_PREFIX) => {
+                    let name = id[tray::MENU_TARGET_PREFIX.len()..].to_string();
+                    save_target_override(&settings, Some(TargetSpec::Name(name)));
                 }
+                // The menu item's check mark flips by itself
+                tray::MENU_LOCK_POSITION => overlay::set_position_locked(!overlay::is_position_locked()),
                 tray::MENU_EXIT => {
                     // L'utente ha cliccato Exit, usciamo dal loop pulitamente
-                    break; 
+                    exit_requested = true;
                 }
                 _ => {}
             }
         }
         
-        // Update overlay every ~16ms (circa 60 update al secondo per l'UI)
-        if last_update.elapsed() >= Duration::from_millis(16) {
+        if exit_requested {
+            break;
+        }
+        
+        // Surface PresentMon start failures (AV quarantine, admin) once per distinct error.
+        // They stop the capture, so they get a message box rather than a toast the rate
+        // limiter could drop; it runs on its own thread so the overlay keeps updating.
+        if let Some(err) = fps_capture::take_error() {
+            if last_capture_error.as_ref() != Some(&err) {
+                let msg = err.to_string();
+                std::thread::spawn(move || show_error_message(&msg));
+                last_capture_error = Some(err);
+            }
+        }
+        
+        // The overlay was dragged somewhere new while unlocked
+        if let Some((x, y)) = overlay::take_dropped_position() {
+            let position = settings::OverlayPosition::Custom { x, y };
+            cli::clear_position();
+            settings.lock().position = position;
+            save_position(position);
+        }
+        
+        // Pick up settings.json edited outside the app. Not polled while the settings window
+        // is open (its previews own the shared settings until it closes), so an edit made
+        // meanwhile is noticed, and loaded, once the window is closed.
+        let reloaded = if gui::is_open() { None } else { settings_watcher.poll() };
+        if let Some(reloaded) = reloaded {
+            match reloaded {
+                Ok(mut new_settings) => {
+                    cli::apply(&mut new_settings);
+                    hotkey::unregister_all();
+                    if let Err(e) = hotkey::register_all(&new_settings) {
+                        if no_tray {
+                            log::error(&format!("Hotkeys unavailable ({}), showing the tray icon", e));
+                            match tray::init() {
+                                Ok(()) => no_tray = false,
+                                Err(e) => log::error(&format!("Tray init failed: {}", e)),
+                            }
+                        }
+                    }
+                    *settings.lock() = new_settings;
+                }
+                // Half-written or broken file: keep running with the current settings
+                Err(e) => notify::notify(tr(Text::SettingsNotReloaded), &e),
+            }
+        }
+        
+        // Update overlay overlay_update_hz times a second (60 = every ~16ms).
+        // Lower rates cut the CPU spent here and on repaints; the FPS shown is still
+        // the windowed average, so at 10 Hz it only lags by up to 100ms more.
+        // The once-a-second stats (stats_tick) don't depend on it.
+        let update_hz = settings.lock().overlay_update_hz.clamp(OVERLAY_UPDATE_HZ_MIN, OVERLAY_UPDATE_HZ_MAX);
+        if last_update.elapsed() >= Duration::from_millis(1000 / update_hz as u64) {
             last_update = Instant::now();
             
             let current_settings = settings.lock().clone();
+            // The game's profile (game seen last tick), if it has one, decides what's measured and shown: capture
+            // config, monitor readings, alerts, the CSV log and the overlay. Detection,
+            // filters and the rest of the app keep the global settings.
+            let game_settings = current_game
+                .as_deref()
+                .and_then(|game| profiles.lock().get(game).cloned())
+                .unwrap_or_else(|| current_settings.clone());
+            fps_capture::configure(&game_settings);
+            log::set_level(current_settings.log_level);
+            ipc::set_enabled(current_settings.enable_ipc);
+
+            // Language changed from the settings window
+            if current_settings.language != applied_language {
+                applied_language = current_settings.language;
+                i18n::set_language(applied_language);
+                if !no_tray {
+                    tray::refresh_menu();
+                }
+            }
             
             // Update stats every 1 second
-            if last_stats_update.elapsed() >= Duration::from_millis(1000) {
-                sys_monitor.update(current_settings.show_cpu_usage, current_settings.show_gpu_usage);
+            let stats_tick = last_stats_update.elapsed() >= Duration::from_millis(1000);
+            if stats_tick {
+                // Alert-only mode needs CPU/GPU readings even when those lines are hidden,
+                // the Stream layout always shows both, a text template may use either
+                let template = template::Template::parse(&game_settings.overlay_template).ok();
+                let in_template = |token| template.as_ref().is_some_and(|t| t.uses(token));
+                let usage_alerts = game_settings.alert_only_mode && game_settings.alert_only_usage_above > 0;
+                let stream_layout = game_settings.layout == OverlayLayout::Stream;
+                let log_column = |c| game_settings.log_stats_csv && game_settings.csv_columns.contains(&c);
+                sys_monitor.set_gpu_selection(&game_settings.gpu_selection);
+                sys_monitor.set_gpu_aggregation(game_settings.gpu_aggregation);
+                sys_monitor.set_smoothing(game_settings.stats_smoothing);
+                sys_monitor.update(monitor::MonitorRequest {
+                    cpu: game_settings.show_cpu_usage
+                        || usage_alerts
+                        || stream_layout
+                        || log_column(CsvColumn::Cpu)
+                        || in_template(template::Token::Cpu),
+                    gpu: game_settings.show_gpu_usage
+                        || usage_alerts
+                        || stream_layout
+                        || log_column(CsvColumn::Gpu)
+                        || in_template(template::Token::Gpu),
+                    ram: game_settings.show_ram_usage || log_column(CsvColumn::Ram),
+                    per_core: game_settings.show_per_core_cpu,
+                    gpu_adapters: game_settings.show_gpu_usage && game_settings.show_all_gpus,
+                    gpu_temp: game_settings.show_gpu_temp,
+                    battery: game_settings.show_battery,
+                    network: game_settings.show_network,
+                    disk: game_settings.show_disk_usage,
+                    hardware_names: game_settings.show_hardware_names,
+                });
                 last_stats_update = Instant::now();
             }
 
+            // Keep the tray's "Monitor process" list current
+            if stats_tick && !no_tray {
+                let processes = fullscreen::list_windowed_processes();
+                let current = match &current_settings.target_override {
+                    Some(TargetSpec::Name(name)) => Some(name.clone()),
+                    Some(TargetSpec::Pid(pid)) => fullscreen::get_process_name(*pid),
+                    None => None,
+                };
+                tray::set_target_processes(processes.into_iter().map(|(_, name)| name).collect(), current);
+            }
+
+            fullscreen::set_detect_borderless(current_settings.detect_borderless);
+            let fullscreen_app = match &current_settings.target_override {
+                // Picked by hand: shown over it fullscreen or not, and never filtered out
+                Some(spec) => target_override.resolve(spec, stats_tick),
+                None => {
+                    let app = match current_settings.overlay_mode {
+                        OverlayMode::FullscreenOnly => fullscreen::select_fullscreen_app(current_settings.multi_fullscreen_policy),
+                        OverlayMode::Always => fullscreen::get_foreground_app(),
+                    };
+                    // A blacklisted (or, with a whitelist, unlisted) process counts as no game at all
+                    let app = app.filter(|app| {
+                        current_settings.allows_process(cached_process_name(&mut game_name, app.process_id).as_deref())
+                    });
+                    // A game that stops looking fullscreen for a moment keeps its overlay
+                    fullscreen_hold.update(app)
+                }
+            };
+
+            // Accumulate playtime for the running game on the same 1s cadence
+            if stats_tick {
+                let name = if current_settings.track_playtime {
+                    fullscreen_app.as_ref().and_then(|app| cached_process_name(&mut game_name, app.process_id))
+                } else {
+                    None
+                };
+                playtime_tracker.tick(name.as_deref());
+                current_playtime = name.map(|n| playtime_tracker.total_secs(&n));
+                overlay::set_playtime(if game_settings.show_playtime { current_playtime } else { None });
+                
+                // Keep the summary panel live while it's open
+                if summary::is_visible() {
+                    summary::show(&fps_capture::get_stats(), current_playtime, current_settings.number_locale);
+                }
+            }
+
+            // The preview runs for PREVIEW_DURATION, or until a real game takes the overlay
+            if fullscreen_app.is_some() || preview_started.is_some_and(|started| started.elapsed() >= PREVIEW_DURATION) {
+                preview_started = None;
+            }
+
             // Check for fullscreen app
-            if let Some(app) = fullscreen::get_fullscreen_app() {
+            let hide_delay = Duration::from_millis(current_settings.hide_delay_ms as u64);
+            if let Some(app) = fullscreen_app {
+                no_game_since = None;
+                last_game_seen = Some(Instant::now());
+                // init() returns early when the capture is already up, and detects (or
+                // re-extracts) PresentMon again otherwise
+                if capture_idle {
+                    capture_idle = false;
+                    log::info("Game detected, restarting the idle capture");
+                    if let Err(e) = fps_capture::init(current_settings.presentmon_path.as_deref()) {
+                        log::error(&format!("Capture restart failed: {}", e));
+                    }
+                }
                 // Get FPS for the fullscreen app
                 // Qui chiamiamo la funzione che abbiamo sistemato in fps_capture.rs
-                let fps_data = fps_capture::get_fps_for_process(app.process_id);
-                
-                let (fps, one_percent_low) = match fps_data {
-                    Some(data) => (data.fps, data.one_percent_low),
-                    None => (0.0, 0.0), // Se non abbiamo dati (ancora), mostriamo 0
+                // Se non abbiamo dati (ancora), mostriamo 0
+                let fps_data = if capture_paused {
+                    // Shown as "PAUSED", like a game that stopped rendering
+                    fps_capture::FpsData { stalled: true, ..Default::default() }
+                } else if capture_target.follow(app.process_id) {
+                    fps_capture::get_fps_for_process(app.process_id).unwrap_or_default()
+                } else {
+                    // Another app briefly in front: PresentMon stays on the game, no reading for this one
+                    fps_capture::FpsData::default()
                 };
+                current_game = cached_process_name(&mut game_name, app.process_id);
+                let (fps, one_percent_low) = (fps_data.fps, fps_data.one_percent_low);
+                if stats_tick {
+                    tray::set_icon_color(fps_icon_color(fps, &fps_data, &current_settings));
+                    let fps_text = current_settings.number_locale.format(fps, 0);
+                    tray::set_tooltip(&match &current_game {
+                        Some(game) => format!("EasyFPS — {} FPS ({})", fps_text, game),
+                        None => format!("EasyFPS — {} FPS", fps_text),
+                    });
+                }
+                if current_settings.enable_ipc {
+                    ipc::publish(ipc::Snapshot {
+                        fps,
+                        one_percent_low,
+                        cpu: sys_monitor.get_cpu_usage(),
+                        gpu: sys_monitor.get_gpu_usage(),
+                        process: current_game.clone(),
+                    });
+                }
                 
-                // Show overlay with FPS and Stats
-                overlay::show(
-                    fps, 
-                    one_percent_low, 
-                    sys_monitor.get_cpu_usage(), 
-                    sys_monitor.get_gpu_usage(), 
-                    &current_settings
+                // Per-second stats log
+                if !game_settings.log_stats_csv {
+                    stats_logger.close();
+                } else if stats_tick {
+                    let game = if game_settings.csv_columns.contains(&CsvColumn::Game) {
+                        cached_process_name(&mut game_name, app.process_id)
+                    } else {
+                        None
+                    };
+                    stats_logger.log(&game_settings.csv_columns, &csv_log::StatsRow {
+                        fps,
+                        one_percent_low,
+                        cpu: sys_monitor.get_cpu_usage(),
+                        gpu: sys_monitor.get_gpu_usage(),
+                        ram: sys_monitor.get_ram_usage().percent,
+                        game: game.as_deref(),
+                    });
+                }
+                
+                // In alert-only mode the overlay appears only while something is wrong
+                // (always shown while the settings window is open, to preview changes)
+                let alert_visible = alert_gate.update(
+                    fps,
+                    fps_data.stutter_count,
+                    sys_monitor.get_cpu_usage(),
+                    sys_monitor.get_gpu_usage(),
+                    &game_settings,
                 );
+                // hide_above_fps/show_below_fps: only within the wanted FPS range
+                let in_fps_band = fps_band.update(fps, &game_settings);
+                // alert_below_fps: a sustained drop flashes the border (and beeps if enabled)
+                if low_fps_alarm.update(fps, &game_settings) {
+                    overlay::flash_border();
+                    if game_settings.alert_beep {
+                        unsafe {
+                            let _ = windows::Win32::System::Diagnostics::Debug::MessageBeep(MB_ICONWARNING);
+                        }
+                    }
+                }
+                
+                // Game name and resolution are only needed by the overlay text template,
+                // the Stream layout header, the process name and the resolution line
+                if !game_settings.overlay_template.is_empty()
+                    || game_settings.layout == OverlayLayout::Stream
+                    || game_settings.show_process_name
+                    || game_settings.show_resolution
+                {
+                    overlay::set_app_info(
+                        cached_process_name(&mut game_name, app.process_id),
+                        Some((app.width, app.height)),
+                        cached_refresh_rate(&mut refresh_cache, &app),
+                    );
+                }
+                
+                if ((!game_settings.alert_only_mode || alert_visible) && in_fps_band) || gui::is_open() {
+                    // Show overlay with FPS and Stats
+                    overlay::show(&fps_data, &sys_monitor, &game_settings, app.display);
+                    if stats_tick && game_settings.auto_contrast {
+                        overlay::sample_backdrop();
+                    }
+                } else {
+                    overlay::hide();
+                }
+            } else if preview_started.is_none() && last_game_seen.is_some_and(|seen| seen.elapsed() < hide_delay) {
+                // Alt-tabbed out for a moment: the last values stay up, dimmed
+                overlay::dim(&game_settings);
             } else {
-                // No fullscreen app, hide overlay
-                overlay::hide();
+                // No fullscreen app, hide overlay (unless previewing it)
+                current_game = None;
+                match preview_started {
+                    Some(started) => overlay::show_preview(started.elapsed(), &sys_monitor, &current_settings),
+                    None => overlay::hide(),
+                }
+                tray::set_tooltip(tr(Text::TrayTooltip));
+                tray::set_icon_color(tray::ICON_IDLE);
+                ipc::publish(ipc::Snapshot::default());
+                // A new log file is started when the next game session begins
+                stats_logger.close();
+
+                // Release PresentMon and its ETW session while nothing is being played
+                let idle_since = *no_game_since.get_or_insert_with(Instant::now);
+                let timeout = current_settings.idle_capture_timeout_secs;
+                if timeout > 0
+                    && !capture_idle
+                    && !capture_paused
+                    && !fps_capture::is_benchmarking()
+                    && idle_since.elapsed() >= Duration::from_secs(timeout as u64)
+                {
+                    log::info("No game for a while, stopping the capture");
+                    fps_capture::shutdown();
+                    capture_idle = true;
+                    // Whatever starts next is followed right away
+                    capture_target = CaptureTarget::default();
+                }
             }
         }
         
@@ -138,9 +622,272 @@ fn main() {
     }
     
     // <<< PULIZIA FINALE: Questa parte viene eseguita quando il loop finisce (Break)
+    playtime_tracker.tick(None);
+    playtime_tracker.save();
+    stats_logger.close();
+    hotkey::unregister_all();
     fps_capture::shutdown(); // Spegni ETW
     overlay::shutdown();     // Spegni Overlay DX11
+    summary::shutdown();
+    notify::shutdown();
+    ipc::shutdown();
     tray::shutdown();        // Rimuovi icona
+    drop(instance);
+}
+
+// Longest wait for the capture to shut down after a panic
+const PANIC_CLEANUP_TIMEOUT: Duration = Duration::from_secs(2);
+
+// Session-wide name of the single-instance mutex
+const INSTANCE_MUTEX_NAME: &str = "Local\\EasyFPS.SingleInstance";
+
+/// Named mutex held while this EasyFPS runs, closed on drop
+struct SingleInstance(windows::Win32::Foundation::HANDLE);
+
+impl SingleInstance {
+    /// None when another instance already holds the mutex
+    fn acquire() -> Option<Self> {
+        use windows::Win32::Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS};
+        use windows::Win32::System::Threading::CreateMutexW;
+        use windows::core::PCWSTR;
+
+        let name: Vec<u16> = INSTANCE_MUTEX_NAME.encode_utf16().chain(std::iter::once(0)).collect();
+        unsafe {
+            // An instance running elevated makes this fail with access denied: also taken
+            let handle = CreateMutexW(None, false, PCWSTR(name.as_ptr())).ok()?;
+            if GetLastError().is_err_and(|e| e.code() == ERROR_ALREADY_EXISTS.to_hresult()) {
+                let _ = CloseHandle(handle);
+                return None;
+            }
+            Some(Self(handle))
+        }
+    }
+}
+
+impl Drop for SingleInstance {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = windows::Win32::Foundation::CloseHandle(self.0);
+        }
+    }
+}
+
+/// Open the settings window (if not already open); saved settings replace the shared ones,
+/// a saved profile is added for `game`
+fn open_settings(settings: &Arc<Mutex<Settings>>, profiles: &Arc<Mutex<Profiles>>, game: Option<String>) {
+    if gui::is_open() {
+        return;
+    }
+    let current_settings = settings.lock().clone();
+    let settings_clone = Arc::clone(settings);
+    let profiles_clone = Arc::clone(profiles);
+    // Previews go straight into the shared settings; this is what Cancel goes back to
+    let snapshot = current_settings.clone();
+    
+    gui::open(current_settings, game, move |event| match event {
+        gui::GuiEvent::Preview(new_settings) | gui::GuiEvent::Saved(new_settings) => {
+            let mut s = settings_clone.lock();
+            *s = new_settings;
+        }
+        gui::GuiEvent::ProfileSaved { game, settings } => {
+            *settings_clone.lock() = snapshot.clone();
+            profiles_clone.lock().insert(&game, settings);
+        }
+        gui::GuiEvent::Discarded => *settings_clone.lock() = snapshot.clone(),
+    });
+}
+
+/// Persist a new overlay position. Only the position is saved, other live changes
+/// (previews, opacity hotkeys) aren't.
+fn save_position(position: settings::OverlayPosition) {
+    let mut saved = Settings::load();
+    saved.position = position;
+    if let Err(e) = saved.save() {
+        notify::notify(tr(Text::PositionNotSaved), &e);
+    }
+}
+
+/// Start a benchmark run, or stop the running one and show its results in a window of their own
+fn toggle_benchmark(settings: &Arc<Mutex<Settings>>, game: Option<String>) {
+    if fps_capture::is_benchmarking() {
+        if let Some(stats) = fps_capture::stop_benchmark() {
+            // Kept for "View last benchmark", even after a restart
+            if let Err(e) = last_benchmark::LastBenchmark::new(&stats, game.clone()).save() {
+                notify::notify(tr(Text::BenchmarkNotSaved), &e);
+            }
+            tray::refresh_menu();
+            let (locale, theme) = {
+                let s = settings.lock();
+                (s.number_locale, s.gui_theme)
+            };
+            gui::show_benchmark_results(stats, game, locale, theme);
+        }
+    } else {
+        summary::hide();
+        let path = csv_log::benchmark_path();
+        let hotkey = settings.lock().benchmark_hotkey;
+        match fps_capture::start_benchmark(&path) {
+            Ok(()) => notify::notify(
+                tr(Text::BenchmarkStarted),
+                &format!(
+                    "{} {}\n{} {} / \"{}\"",
+                    tr(Text::BenchmarkFramesSavedTo),
+                    path.display(),
+                    tr(Text::BenchmarkStopHint),
+                    hotkey,
+                    tr(Text::MenuBenchmark)
+                ),
+            ),
+            // The run still works, only the CSV is missing
+            Err(e) => notify::notify(
+                tr(Text::BenchmarkStartedNoCsv),
+                &format!("{} {}: {}", tr(Text::CannotWrite), path.display(), e),
+            ),
+        }
+    }
+}
+
+/// Set (or clear) `target_override` from the tray, live and in settings.json
+fn save_target_override(settings: &Arc<Mutex<Settings>>, target: Option<TargetSpec>) {
+    settings.lock().target_override = target.clone();
+    let mut saved = Settings::load();
+    saved.target_override = target;
+    if let Err(e) = saved.save() {
+        notify::notify(tr(Text::TargetNotSaved), &e);
+    }
+}
+
+/// Resolve the process name for `pid`, reusing the cached value while the PID doesn't change
+fn cached_process_name(cache: &mut Option<(u32, String)>, pid: u32) -> Option<String> {
+    if let Some((cached_pid, name)) = cache {
+        if *cached_pid == pid {
+            return Some(name.clone());
+        }
+    }
+    let name = fullscreen::get_process_name(pid)?;
+    *cache = Some((pid, name.clone()));
+    Some(name)
+}
+
+/// (window, monitor) the refresh rate was read for, and the rate
+type RefreshRateCache = ((isize, Option<fullscreen::DisplayRect>), Option<u32>);
+
+/// Refresh rate of the monitor `app` is on, only queried again when the window or monitor changes
+fn cached_refresh_rate(cache: &mut Option<RefreshRateCache>, app: &fullscreen::FullscreenApp) -> Option<u32> {
+    let key = (app.hwnd, app.display);
+    if let Some((cached_key, hz)) = cache {
+        if *cached_key == key {
+            return *hz;
+        }
+    }
+    let hz = fullscreen::refresh_rate(app.hwnd);
+    *cache = Some((key, hz));
+    hz
+}
+
+// How long a newly detected app must stay in front before PresentMon moves to it
+const RETARGET_DELAY: Duration = Duration::from_secs(3);
+
+/// The game PresentMon follows, kept through brief switches to other apps. Retargeting
+/// restarts PresentMon, so alt-tabbing to another window and back shouldn't do it twice.
+#[derive(Default)]
+struct CaptureTarget {
+    pid: Option<u32>,
+    // Another app in front since, and when it got there
+    candidate: Option<(u32, Instant)>,
+}
+
+impl CaptureTarget {
+    /// Whether `pid` is (now) the target. The first game, or one replacing a game that has
+    /// exited, is taken at once; any other app has to stay for RETARGET_DELAY.
+    fn follow(&mut self, pid: u32) -> bool {
+        if let Some(target) = self.pid.filter(|&target| target != pid) {
+            if fullscreen::get_process_name(target).is_some() {
+                match self.candidate {
+                    Some((candidate, since)) if candidate == pid => {
+                        if since.elapsed() < RETARGET_DELAY {
+                            return false;
+                        }
+                    }
+                    _ => {
+                        self.candidate = Some((pid, Instant::now()));
+                        return false;
+                    }
+                }
+            }
+            log::info(&format!("Capture target moves from PID {} to {}", target, pid));
+        }
+        self.pid = Some(pid);
+        self.candidate = None;
+        true
+    }
+}
+
+/// The process `target_override` points at. The PID is looked up again when the setting
+/// changes or the process exits, its window once a second.
+#[derive(Default)]
+struct TargetOverride {
+    spec: Option<TargetSpec>,
+    pid: Option<u32>,
+    app: Option<fullscreen::FullscreenApp>,
+}
+
+impl TargetOverride {
+    /// The app to monitor for `spec`; `refresh` re-checks the process and its window
+    fn resolve(&mut self, spec: &TargetSpec, refresh: bool) -> Option<fullscreen::FullscreenApp> {
+        let changed = self.spec.as_ref() != Some(spec);
+        let stale = refresh && self.pid.is_none_or(|pid| fullscreen::get_process_name(pid).is_none());
+        if changed || stale {
+            self.spec = Some(spec.clone());
+            self.pid = match spec {
+                TargetSpec::Pid(pid) => fullscreen::get_process_name(*pid).map(|_| *pid),
+                TargetSpec::Name(name) => fullscreen::find_process_id(name),
+            };
+            if let Some(pid) = self.pid {
+                log::info(&format!("Target override {:?} resolved to PID {}", spec, pid));
+            }
+        }
+        if changed || refresh {
+            // A process without a visible window still gets its FPS read, the overlay
+            // goes on the primary monitor
+            self.app = self.pid.map(|pid| {
+                fullscreen::get_process_app(pid).unwrap_or(fullscreen::FullscreenApp {
+                    hwnd: 0,
+                    process_id: pid,
+                    width: 0,
+                    height: 0,
+                    on_primary: true,
+                    display: None,
+                })
+            });
+        }
+        self.app.clone()
+    }
+}
+
+// How long the tray's overlay preview runs
+const PREVIEW_DURATION: Duration = Duration::from_secs(10);
+
+// FPS bands of the tray icon, unless the overlay uses threshold colors (then its thresholds)
+const TRAY_LOW_FPS: u32 = 30;
+const TRAY_HIGH_FPS: u32 = 60;
+
+/// Tray icon color for the tracked game's FPS; idle while there's no real reading
+fn fps_icon_color(fps: f64, fps_data: &fps_capture::FpsData, settings: &Settings) -> (u8, u8, u8) {
+    if fps_data.stalled || fps <= 0.0 {
+        return tray::ICON_IDLE;
+    }
+    let (low, high) = match settings.fps_color_mode {
+        settings::FpsColorMode::Threshold { low, high } => (low, high),
+        settings::FpsColorMode::Static => (TRAY_LOW_FPS, TRAY_HIGH_FPS),
+    };
+    if fps < low as f64 {
+        tray::ICON_POOR
+    } else if fps < high as f64 {
+        tray::ICON_FAIR
+    } else {
+        tray::ICON_GOOD
+    }
 }
 
 fn show_error_message(message: &str) {
@@ -149,7 +896,7 @@ fn show_error_message(message: &str) {
     
     // Converti stringa Rust in stringa Wide (Windows Unicode)
     let msg: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
-    let title: Vec<u16> = "EasyFPS Error".encode_utf16().chain(std::iter::once(0)).collect();
+    let title: Vec<u16> = tr(Text::ErrorTitle).encode_utf16().chain(std::iter::once(0)).collect();
     
     unsafe {
         MessageBoxW(
@@ -159,4 +906,15 @@ fn show_error_message(message: &str) {
             MB_OK | MB_ICONERROR,
         );
     }
-}
\ No newline at end of file
+}
+
+/// Yes/No question box, true for Yes
+fn ask_yes_no(message: &str) -> bool {
+    use windows::Win32::UI::WindowsAndMessaging::{MessageBoxW, IDYES, MB_ICONQUESTION, MB_YESNO};
+    use windows::core::PCWSTR;
+
+    let msg: Vec<u16> = message.encode_utf16().chain(std::iter::once(0)).collect();
+    let title: Vec<u16> = "EasyFPS".encode_utf16().chain(std::iter::once(0)).collect();
+
+    unsafe { MessageBoxW(None, PCWSTR(msg.as_ptr()), PCWSTR(title.as_ptr()), MB_YESNO | MB_ICONQUESTION) == IDYES }
+}
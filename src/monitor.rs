@@ -1,28 +1,159 @@
+use crate::settings::{GpuAggregation, GpuSelection, Smoothing};
+use windows::core::{w, PCWSTR};
 use windows::Win32::System::Performance::{
-    PdhAddEnglishCounterW, PdhCollectQueryData, PdhGetFormattedCounterValue,
-    PdhOpenQueryW, PDH_FMT_DOUBLE,
+    PdhAddEnglishCounterW, PdhCollectQueryData, PdhGetFormattedCounterArrayW,
+    PdhGetFormattedCounterValue, PdhOpenQueryW, PdhRemoveCounter, PDH_FMT_COUNTERVALUE_ITEM_W, PDH_FMT_DOUBLE,
 };
 
+/// Which readings `SystemMonitor::update` should refresh
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MonitorRequest {
+    pub cpu: bool,
+    pub gpu: bool,
+    pub ram: bool,
+    /// Per-logical-core CPU load
+    pub per_core: bool,
+    /// GPU load of each adapter (needs `gpu`)
+    pub gpu_adapters: bool,
+    /// GPU core temperature
+    pub gpu_temp: bool,
+    /// Battery level and AC power
+    pub battery: bool,
+    /// Download/upload rate over the physical network adapters
+    pub network: bool,
+    /// Disk busy time (% Disk Time over all physical disks)
+    pub disk: bool,
+    /// CPU and GPU model names (queried once, then cached)
+    pub hardware_names: bool,
+}
+
+/// Add `path` to the query when `wanted` and not added yet, remove it when no longer wanted.
+/// `counter` is 0 while the counter isn't in the query.
+unsafe fn sync_counter(query: isize, counter: &mut isize, wanted: bool, path: PCWSTR) {
+    if wanted && *counter == 0 {
+        if PdhAddEnglishCounterW(query, path, 0, counter) != 0 {
+            *counter = 0;
+        }
+    } else if !wanted && *counter != 0 {
+        let _ = PdhRemoveCounter(*counter);
+        *counter = 0;
+    }
+}
+
+/// Smoothed value of a once-a-second reading: `alpha` of the new sample, the rest from
+/// the previous value
+fn ema(previous: f32, sample: f32, alpha: f32) -> f32 {
+    previous + alpha * (sample - previous)
+}
+
+/// Most adapters reported per-adapter; more than this is unusual and would crowd the overlay
+const MAX_GPU_ADAPTERS: usize = 4;
+
+/// Load of one GPU adapter
+#[derive(Debug, Clone, Default)]
+pub struct GpuAdapterUsage {
+    /// DXGI adapter description, None when the adapter couldn't be matched
+    pub name: Option<String>,
+    pub usage: f32,
+}
+
+/// Network throughput in bytes per second, summed over the physical adapters
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NetworkRate {
+    pub down: f64,
+    pub up: f64,
+}
+
+/// Network Interface instances with one of these in their name are loopback, tunnels or
+/// virtual switches, whose traffic is already counted on a physical adapter (or isn't real)
+const VIRTUAL_ADAPTER_HINTS: [&str; 9] =
+    ["loopback", "isatap", "teredo", "virtual", "vethernet", "vmware", "hyper-v", "wan miniport", "pseudo"];
+
+/// System memory in use
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RamUsage {
+    pub percent: f32,
+    pub used_mb: u64,
+    pub total_mb: u64,
+}
+
 pub struct SystemMonitor {
     cpu_usage: f32,
     gpu_usage: f32,
+    ram_usage: RamUsage,
+    core_usage: Vec<f32>,
+    gpu_adapters: Vec<GpuAdapterUsage>,
+    gpu_temp: Option<f32>,
+    battery_percent: Option<u8>,
+    on_ac: bool,
+    network: NetworkRate,
+    disk_usage: f32,
+    // Model names, queried on first request and kept since they never change
+    cpu_name: Option<String>,
+    gpu_name: Option<String>,
+    hardware_names_tried: bool,
+    gpu_selection: GpuSelection,
+    gpu_aggregation: GpuAggregation,
+    // EMA factor for CPU/GPU/RAM/disk load, None = raw readings
+    smoothing_alpha: Option<f32>,
+    // Previous update's request: a reading that was off has nothing to smooth against
+    last_request: MonitorRequest,
+    // Temperature source, loaded on first request (None after a failed attempt)
+    nvml: Option<Nvml>,
+    nvml_tried: bool,
+    // (LUID key, description) of the hardware adapters, enumerated on first per-adapter request
+    adapter_names: Option<Vec<(String, String)>>,
     pdh_query: isize,
     cpu_counter: isize,
     gpu_counter: isize,
+    core_counter: isize, // Added on first per-core request
+    net_down_counter: isize, // Both added on first network request
+    net_up_counter: isize,
+    disk_counter: isize,
+
     counter_buffer: Vec<u8>,
 }
 
 unsafe impl Send for SystemMonitor {}
 
+impl Default for SystemMonitor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
 impl SystemMonitor {
     pub fn new() -> Self {
         // We don't initialize PDH here anymore to save memory at startup
         Self {
             cpu_usage: 0.0,
             gpu_usage: 0.0,
+            ram_usage: RamUsage::default(),
+            core_usage: Vec::new(),
+            gpu_adapters: Vec::new(),
+            gpu_temp: None,
+            battery_percent: None,
+            on_ac: false,
+            network: NetworkRate::default(),
+            disk_usage: 0.0,
+            cpu_name: None,
+            gpu_name: None,
+            hardware_names_tried: false,
+            gpu_selection: GpuSelection::MaxAll,
+            gpu_aggregation: GpuAggregation::Max,
+            smoothing_alpha: None,
+            last_request: MonitorRequest::default(),
+            nvml: None,
+            nvml_tried: false,
+            adapter_names: None,
             pdh_query: 0,
             cpu_counter: 0,
             gpu_counter: 0,
+            core_counter: 0,
+            net_down_counter: 0,
+            net_up_counter: 0,
+            disk_counter: 0,
             counter_buffer: Vec::new(), // Empty initially
         }
     }
@@ -39,25 +170,8 @@ impl SystemMonitor {
             }
             self.pdh_query = pdh_query;
 
-            // CPU Counter: \Processor(_Total)\% Processor Time
-            let _ = PdhAddEnglishCounterW(
-                self.pdh_query,
-                windows::core::w!("\\Processor(_Total)\\% Processor Time"),
-                0,
-                &mut self.cpu_counter,
-            );
-
-            // GPU Counter: \GPU Engine(*)\Utilization Percentage
-            let _ = PdhAddEnglishCounterW(
-                self.pdh_query,
-                windows::core::w!("\\GPU Engine(*)\\Utilization Percentage"),
-                0,
-                &mut self.gpu_counter,
-            );
-            
-            // Initial collect to prime counters
-            let _ = PdhCollectQueryData(self.pdh_query);
-            
+            // Counters are added by `update` as they're requested
+
             // Pre-allocate buffer only when needed
             self.counter_buffer = Vec::with_capacity(16384);
         }
@@ -73,18 +187,64 @@ impl SystemMonitor {
             self.pdh_query = 0;
             self.cpu_counter = 0;
             self.gpu_counter = 0;
+            self.core_counter = 0;
+            self.net_down_counter = 0;
+            self.net_up_counter = 0;
+            self.disk_counter = 0;
             // Free the buffer memory
             self.counter_buffer = Vec::new();
             self.counter_buffer.shrink_to_fit();
         }
     }
 
-    pub fn update(&mut self, show_cpu: bool, show_gpu: bool) {
-        // If neither is needed, cleanup and return
-        if !show_cpu && !show_gpu {
+    pub fn update(&mut self, request: MonitorRequest) {
+        let previous = (self.cpu_usage, self.gpu_usage, self.ram_usage, self.disk_usage);
+        let last_request = std::mem::replace(&mut self.last_request, request);
+        self.read(request);
+
+        // PDH samples a second apart swing a lot (GPU 60% -> 95% -> 70%), smooth the
+        // load numbers so the overlay doesn't flicker
+        let Some(alpha) = self.smoothing_alpha else {
+            return;
+        };
+        if request.cpu && last_request.cpu {
+            self.cpu_usage = ema(previous.0, self.cpu_usage, alpha);
+        }
+        if request.gpu && last_request.gpu {
+            self.gpu_usage = ema(previous.1, self.gpu_usage, alpha);
+        }
+        if request.ram && last_request.ram {
+            self.ram_usage.percent = ema(previous.2.percent, self.ram_usage.percent, alpha);
+            self.ram_usage.used_mb = ema(previous.2.used_mb as f32, self.ram_usage.used_mb as f32, alpha).round() as u64;
+        }
+        if request.disk && last_request.disk {
+            self.disk_usage = ema(previous.3, self.disk_usage, alpha);
+        }
+    }
+
+    /// Take this update's raw readings
+    fn read(&mut self, request: MonitorRequest) {
+        // RAM doesn't use PDH, so it works even when the query below is cleaned up
+        self.ram_usage = if request.ram { Self::query_ram() } else { RamUsage::default() };
+        self.update_gpu_temp(request.gpu_temp);
+        (self.battery_percent, self.on_ac) = if request.battery { Self::query_battery() } else { (None, false) };
+        if request.hardware_names && !self.hardware_names_tried {
+            self.hardware_names_tried = true;
+            self.cpu_name = query_cpu_name();
+            // DXGI lists the adapter driving the primary display first
+            let names = self.adapter_names.get_or_insert_with(|| unsafe { dxgi_adapter_names() });
+            self.gpu_name = names.first().map(|(_, name)| name.trim().to_string());
+        }
+
+        // If none is needed, cleanup and return
+        if !request.cpu && !request.gpu && !request.per_core && !request.network && !request.disk {
             self.cleanup();
             self.cpu_usage = 0.0;
             self.gpu_usage = 0.0;
+            self.core_usage.clear();
+            self.gpu_adapters.clear();
+            self.network = NetworkRate::default();
+            self.disk_usage = 0.0;
             return;
         }
 
@@ -93,11 +253,24 @@ impl SystemMonitor {
             return;
         }
 
+        // Counters follow the request: added when a stat is turned on, removed when it's
+        // turned off, so e.g. a disk-only overlay doesn't pay for the GPU wildcard array.
+        // A newly added counter is primed by the collect below and valid from the next update on.
+        unsafe {
+            let query = self.pdh_query;
+            sync_counter(query, &mut self.cpu_counter, request.cpu, w!("\\Processor(_Total)\\% Processor Time"));
+            sync_counter(query, &mut self.gpu_counter, request.gpu, w!("\\GPU Engine(*)\\Utilization Percentage"));
+            sync_counter(query, &mut self.disk_counter, request.disk, w!("\\PhysicalDisk(_Total)\\% Disk Time"));
+            sync_counter(query, &mut self.core_counter, request.per_core, w!("\\Processor(*)\\% Processor Time"));
+            sync_counter(query, &mut self.net_down_counter, request.network, w!("\\Network Interface(*)\\Bytes Received/sec"));
+            sync_counter(query, &mut self.net_up_counter, request.network, w!("\\Network Interface(*)\\Bytes Sent/sec"));
+        }
+
         if self.pdh_query != 0 {
             unsafe {
                 if PdhCollectQueryData(self.pdh_query) == 0 {
                     // Update CPU
-                    if show_cpu {
+                    if request.cpu && self.cpu_counter != 0 {
                         let mut counter_type: u32 = 0;
                         let mut value = Default::default();
                         
@@ -113,61 +286,253 @@ impl SystemMonitor {
                         self.cpu_usage = 0.0;
                     }
 
-                    // Update GPU (Wildcard handling)
-                    if show_gpu {
-                        use windows::Win32::System::Performance::{
-                            PdhGetFormattedCounterArrayW, PDH_FMT_COUNTERVALUE_ITEM_W,
-                        };
-                        
-                        let mut required_size = 0;
-                        let mut item_count = 0;
-                        
-                        // First call to get size
-                        let _ = PdhGetFormattedCounterArrayW(
-                            self.gpu_counter,
-                            PDH_FMT_DOUBLE,
-                            &mut required_size,
-                            &mut item_count,
-                            None,
-                        );
-                        
-                        if required_size > 0 {
-                            // Resize buffer if needed
-                            if self.counter_buffer.len() < required_size as usize {
-                                 self.counter_buffer.resize(required_size as usize, 0);
-                            }
-    
-                            let items_ptr = self.counter_buffer.as_mut_ptr() as *mut PDH_FMT_COUNTERVALUE_ITEM_W;
-                            
-                            if PdhGetFormattedCounterArrayW(
-                                self.gpu_counter,
-                                PDH_FMT_DOUBLE,
-                                &mut required_size,
-                                &mut item_count,
-                                Some(items_ptr),
-                            ) == 0 {
-                                 let items = std::slice::from_raw_parts(items_ptr, item_count as usize);
-                                 let mut max_load = 0.0;
-                                 
-                                 for item in items {
-                                     if item.FmtValue.CStatus == 0 { 
-                                         let val = item.FmtValue.Anonymous.doubleValue;
-                                         if val > max_load {
-                                             max_load = val;
-                                         }
-                                     }
-                                 }
-                                 self.gpu_usage = max_load as f32;
+                    // Update GPU (Wildcard handling): the busiest engine
+                    if request.gpu && self.gpu_counter != 0 {
+                        if let Some(items) = self.read_counter_array(self.gpu_counter) {
+                            // Only the chosen adapter's engines; all of them if it's gone
+                            let selected_luid = self.selected_luid();
+                            let is_selected = |instance: &str| {
+                                selected_luid.as_deref().is_none_or(|selected| {
+                                    luid_key(instance).is_some_and(|luid| luid.eq_ignore_ascii_case(selected))
+                                })
+                            };
+                            let matched = items.iter().any(|(instance, _)| is_selected(instance));
+                            let selected: Vec<(String, f64)> = items
+                                .iter()
+                                .filter(|(instance, _)| !matched || is_selected(instance))
+                                .cloned()
+                                .collect();
+                            self.gpu_usage = aggregate_gpu_usage(&selected, self.gpu_aggregation) as f32;
+                            if request.gpu_adapters {
+                                self.gpu_adapters = self.group_by_adapter(&items);
+                            } else {
+                                self.gpu_adapters.clear();
                             }
                         }
                     } else {
                         self.gpu_usage = 0.0;
+                        self.gpu_adapters.clear();
+                    }
+
+                    // Per-core CPU: instances are "0", "1", ... plus "_Total"
+                    if request.per_core && self.core_counter != 0 {
+                        if let Some(items) = self.read_counter_array(self.core_counter) {
+                            let mut cores: Vec<(u32, f32)> = items
+                                .iter()
+                                .filter_map(|(name, val)| Some((name.parse::<u32>().ok()?, *val as f32)))
+                                .collect();
+                            cores.sort_by_key(|(index, _)| *index);
+                            self.core_usage = cores.into_iter().map(|(_, val)| val).collect();
+                        }
+                    } else {
+                        self.core_usage.clear();
+                    }
+
+                    if request.network && self.net_down_counter != 0 {
+                        self.network = NetworkRate {
+                            down: self.read_network_counter(self.net_down_counter),
+                            up: self.read_network_counter(self.net_up_counter),
+                        };
+                    } else {
+                        self.network = NetworkRate::default();
+                    }
+
+                    // % Disk Time adds up queued requests and goes past 100 on a busy disk
+                    if request.disk && self.disk_counter != 0 {
+                        let mut value = Default::default();
+                        if PdhGetFormattedCounterValue(self.disk_counter, PDH_FMT_DOUBLE, None, &mut value) == 0 {
+                            self.disk_usage = (value.Anonymous.doubleValue as f32).clamp(0.0, 100.0);
+                        }
+                    } else {
+                        self.disk_usage = 0.0;
                     }
                 }
             }
         }
     }
 
+    /// Read every instance of a wildcard counter as (instance name, value).
+    /// Instances whose value isn't valid are skipped.
+    unsafe fn read_counter_array(&mut self, counter: isize) -> Option<Vec<(String, f64)>> {
+        let mut required_size = 0;
+        let mut item_count = 0;
+        
+        // First call to get size
+        let _ = PdhGetFormattedCounterArrayW(
+            counter,
+            PDH_FMT_DOUBLE,
+            &mut required_size,
+            &mut item_count,
+            None,
+        );
+        
+        if required_size == 0 {
+            return None;
+        }
+
+        // Resize buffer if needed
+        if self.counter_buffer.len() < required_size as usize {
+             self.counter_buffer.resize(required_size as usize, 0);
+        }
+
+        let items_ptr = self.counter_buffer.as_mut_ptr() as *mut PDH_FMT_COUNTERVALUE_ITEM_W;
+        
+        if PdhGetFormattedCounterArrayW(
+            counter,
+            PDH_FMT_DOUBLE,
+            &mut required_size,
+            &mut item_count,
+            Some(items_ptr),
+        ) != 0 {
+            return None;
+        }
+
+        let items = std::slice::from_raw_parts(items_ptr, item_count as usize);
+        Some(
+            items
+                .iter()
+                .filter(|item| item.FmtValue.CStatus == 0)
+                .map(|item| {
+                    let name = item.szName.to_string().unwrap_or_default();
+                    (name, item.FmtValue.Anonymous.doubleValue)
+                })
+                .collect(),
+        )
+    }
+
+    /// Sum of a Network Interface counter over the physical adapters
+    unsafe fn read_network_counter(&mut self, counter: isize) -> f64 {
+        let Some(items) = self.read_counter_array(counter) else {
+            return 0.0;
+        };
+        items
+            .iter()
+            .filter(|(name, _)| {
+                let name = name.to_ascii_lowercase();
+                !VIRTUAL_ADAPTER_HINTS.iter().any(|hint| name.contains(hint))
+            })
+            .map(|(_, val)| val)
+            .sum()
+    }
+
+    /// Busiest engine of each adapter. Engine instances are named like
+    /// "pid_1234_luid_0x00000000_0x0000D1C3_phys_0_eng_0_engtype_3D"; the LUID identifies the adapter.
+    /// Adapters known to DXGI come first, in DXGI order (primary first).
+    fn group_by_adapter(&mut self, items: &[(String, f64)]) -> Vec<GpuAdapterUsage> {
+        let mut adapters: Vec<(String, f64)> = Vec::new();
+        for (instance, val) in items {
+            let Some(luid) = luid_key(instance) else { continue };
+            match adapters.iter_mut().find(|(key, _)| key.eq_ignore_ascii_case(luid)) {
+                Some(adapter) => adapter.1 = adapter.1.max(*val),
+                None => adapters.push((luid.to_string(), *val)),
+            }
+        }
+
+        let names = self.adapter_names.get_or_insert_with(|| unsafe { dxgi_adapter_names() });
+        let position = |luid: &str| names.iter().position(|(key, _)| key.eq_ignore_ascii_case(luid));
+        adapters.sort_by_key(|(luid, _)| position(luid).unwrap_or(usize::MAX));
+        adapters
+            .into_iter()
+            .take(MAX_GPU_ADAPTERS)
+            .map(|(luid, usage)| GpuAdapterUsage {
+                name: position(&luid).map(|i| names[i].1.clone()),
+                usage: usage as f32,
+            })
+            .collect()
+    }
+
+    /// PDH has no GPU temperature counter (Thermal Zone Information only covers ACPI zones),
+    /// so it comes from NVML on NVIDIA cards. Other vendors get None ("N/A").
+    fn update_gpu_temp(&mut self, requested: bool) {
+        if !requested {
+            // Unload NVML until the option is turned back on
+            self.nvml = None;
+            self.nvml_tried = false;
+            self.gpu_temp = None;
+            return;
+        }
+        if !self.nvml_tried {
+            self.nvml_tried = true;
+            self.nvml = unsafe { Nvml::load() };
+        }
+        self.gpu_temp = self.nvml.as_ref().and_then(|nvml| unsafe { nvml.temperature() });
+    }
+
+    /// Report only this GPU on the "GPU" line (applied from the next update)
+    pub fn set_gpu_selection(&mut self, selection: &GpuSelection) {
+        if self.gpu_selection != *selection {
+            self.gpu_selection = selection.clone();
+        }
+    }
+
+    /// How engine readings are combined into the "GPU" number (applied from the next update)
+    pub fn set_gpu_aggregation(&mut self, aggregation: GpuAggregation) {
+        self.gpu_aggregation = aggregation;
+    }
+
+    /// Smoothing of the CPU/GPU/RAM/disk load (applied from the next update).
+    /// Alpha is kept within 0.05-1.0.
+    pub fn set_smoothing(&mut self, smoothing: Smoothing) {
+        self.smoothing_alpha = match smoothing {
+            Smoothing::None => None,
+            Smoothing::Ema { alpha } => Some(alpha.clamp(0.05, 1.0)),
+        };
+    }
+
+    /// LUID key of the selected adapter, looked up by name when the saved LUID no longer exists
+    fn selected_luid(&mut self) -> Option<String> {
+        let GpuSelection::Adapter { luid, name } = &self.gpu_selection else {
+            return None;
+        };
+        let names = self.adapter_names.get_or_insert_with(|| unsafe { dxgi_adapter_names() });
+        if names.iter().any(|(key, _)| key.eq_ignore_ascii_case(luid)) {
+            return Some(luid.clone());
+        }
+        names
+            .iter()
+            .find(|(_, adapter)| adapter == name)
+            .map(|(key, _)| key.clone())
+            .or_else(|| Some(luid.clone()))
+    }
+
+    fn query_ram() -> RamUsage {
+        use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+
+        let mut status = MEMORYSTATUSEX {
+            dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+            ..Default::default()
+        };
+        if unsafe { GlobalMemoryStatusEx(&mut status) }.is_err() {
+            return RamUsage::default();
+        }
+        let total_mb = status.ullTotalPhys / (1024 * 1024);
+        let used_mb = total_mb.saturating_sub(status.ullAvailPhys / (1024 * 1024));
+        RamUsage {
+            percent: status.dwMemoryLoad as f32,
+            used_mb,
+            total_mb,
+        }
+    }
+
+    /// (charge %, on AC power). No percentage on desktops without a battery, or when
+    /// Windows doesn't know the charge.
+    fn query_battery() -> (Option<u8>, bool) {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        // BatteryFlag: 128 = no system battery, 255 = unknown status
+        const NO_BATTERY: u8 = 128;
+        const UNKNOWN: u8 = 255;
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        if unsafe { GetSystemPowerStatus(&mut status) }.is_err() {
+            return (None, false);
+        }
+        let on_ac = status.ACLineStatus == 1;
+        if status.BatteryFlag == UNKNOWN || status.BatteryFlag & NO_BATTERY != 0 || status.BatteryLifePercent == UNKNOWN {
+            return (None, on_ac);
+        }
+        (Some(status.BatteryLifePercent.min(100)), on_ac)
+    }
 
     pub fn get_cpu_usage(&self) -> f32 {
         self.cpu_usage
@@ -176,4 +541,294 @@ impl SystemMonitor {
     pub fn get_gpu_usage(&self) -> f32 {
         self.gpu_usage
     }
+
+    pub fn get_ram_usage(&self) -> RamUsage {
+        self.ram_usage
+    }
+
+    /// Load of each GPU adapter (empty unless requested, at most MAX_GPU_ADAPTERS)
+    pub fn get_gpu_adapters(&self) -> &[GpuAdapterUsage] {
+        &self.gpu_adapters
+    }
+
+    /// GPU core temperature in °C (None unless requested, or when no NVIDIA GPU/driver is found)
+    pub fn get_gpu_temp(&self) -> Option<f32> {
+        self.gpu_temp
+    }
+
+    /// Battery charge in % (None unless requested, or without a battery)
+    pub fn get_battery_percent(&self) -> Option<u8> {
+        self.battery_percent
+    }
+
+    /// Whether the machine runs on AC power (false unless requested)
+    pub fn is_on_ac(&self) -> bool {
+        self.on_ac
+    }
+
+    /// Network download/upload rate (zero unless requested)
+    pub fn get_network(&self) -> NetworkRate {
+        self.network
+    }
+
+    /// Disk busy time in % (0 unless requested)
+    pub fn get_disk_usage(&self) -> f32 {
+        self.disk_usage
+    }
+
+    /// CPU model name (None until requested, or when the registry doesn't have it)
+    pub fn get_cpu_name(&self) -> Option<&str> {
+        self.cpu_name.as_deref()
+    }
+
+    /// Name of the primary GPU (None until requested, or when DXGI is unavailable)
+    pub fn get_gpu_name(&self) -> Option<&str> {
+        self.gpu_name.as_deref()
+    }
+
+    /// Load of each logical core, in core order (empty unless requested)
+    pub fn get_per_core_usage(&self) -> &[f32] {
+        &self.core_usage
+    }
+}
+
+/// Combine GPU Engine readings, given as (instance name, utilization %), into one number.
+///
+/// `Max` is the busiest single engine. It under-reports when a workload keeps several engine
+/// types busy at once (e.g. 3D plus async Compute plus Copy), because those run in parallel.
+/// `SumClamped` takes the busiest engine of each type on an adapter, adds those up and clamps
+/// to 100. Engines of the same type are not added: they time-slice the same hardware and their
+/// readings overlap. With several adapters the busiest adapter's total is returned.
+pub fn aggregate_gpu_usage(items: &[(String, f64)], aggregation: GpuAggregation) -> f64 {
+    match aggregation {
+        GpuAggregation::Max => items.iter().map(|(_, val)| *val).fold(0.0, f64::max),
+        GpuAggregation::SumClamped => {
+            // (adapter, engine type, busiest engine of that type)
+            let mut busiest: Vec<(&str, &str, f64)> = Vec::new();
+            for (instance, val) in items {
+                let adapter = luid_key(instance).unwrap_or("");
+                let engine = engine_type(instance).unwrap_or(instance);
+                match busiest.iter_mut().find(|(a, e, _)| *a == adapter && *e == engine) {
+                    Some(entry) => entry.2 = entry.2.max(*val),
+                    None => busiest.push((adapter, engine, *val)),
+                }
+            }
+
+            let mut per_adapter: Vec<(&str, f64)> = Vec::new();
+            for (adapter, _, val) in busiest {
+                match per_adapter.iter_mut().find(|(a, _)| *a == adapter) {
+                    Some(entry) => entry.1 += val,
+                    None => per_adapter.push((adapter, val)),
+                }
+            }
+            per_adapter.iter().map(|(_, total)| *total).fold(0.0, f64::max).min(100.0)
+        }
+    }
+}
+
+/// The engine type ("3D", "Copy", "Compute_0"...) at the end of a GPU Engine instance name
+fn engine_type(instance: &str) -> Option<&str> {
+    let start = instance.find("engtype_")? + "engtype_".len();
+    Some(&instance[start..])
+}
+
+/// The "0xHIGH_0xLOW" LUID part of a GPU Engine instance name
+fn luid_key(instance: &str) -> Option<&str> {
+    let start = instance.find("luid_")? + "luid_".len();
+    let rest = &instance[start..];
+    // Two "0x"-prefixed parts joined by '_'
+    let high_end = rest.find('_')?;
+    let low_end = rest[high_end + 1..].find('_').map_or(rest.len(), |i| high_end + 1 + i);
+    Some(&rest[..low_end])
+}
+
+/// (LUID key, description) of the GPUs that can be selected for the "GPU" line
+pub fn list_gpu_adapters() -> Vec<(String, String)> {
+    unsafe { dxgi_adapter_names() }
+}
+
+/// CPU model as Windows reports it, e.g. "AMD Ryzen 7 5800X3D 8-Core Processor"
+fn query_cpu_name() -> Option<String> {
+    use windows::Win32::System::Registry::{RegGetValueW, HKEY_LOCAL_MACHINE, RRF_RT_REG_SZ};
+
+    let mut buffer = [0u16; 256];
+    let mut size = std::mem::size_of_val(&buffer) as u32;
+    unsafe {
+        RegGetValueW(
+            HKEY_LOCAL_MACHINE,
+            w!("HARDWARE\\DESCRIPTION\\System\\CentralProcessor\\0"),
+            w!("ProcessorNameString"),
+            RRF_RT_REG_SZ,
+            None,
+            Some(buffer.as_mut_ptr().cast()),
+            Some(&mut size),
+        )
+        .ok()?;
+    }
+    let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+    // Some CPUs pad the name with spaces
+    let name = String::from_utf16_lossy(&buffer[..len]).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// (LUID key, description) of every hardware adapter, in DXGI enumeration order.
+/// Empty if DXGI is unavailable; adapters are then labeled by index.
+unsafe fn dxgi_adapter_names() -> Vec<(String, String)> {
+    use windows::Win32::Graphics::Dxgi::{
+        CreateDXGIFactory1, IDXGIFactory1, DXGI_ADAPTER_DESC1, DXGI_ADAPTER_FLAG_SOFTWARE,
+    };
+
+    let Ok(factory) = CreateDXGIFactory1::<IDXGIFactory1>() else {
+        return Vec::new();
+    };
+    let mut names = Vec::new();
+    let mut index = 0;
+    while let Ok(adapter) = factory.EnumAdapters1(index) {
+        index += 1;
+        let mut desc = DXGI_ADAPTER_DESC1::default();
+        if adapter.GetDesc1(&mut desc).is_err() || desc.Flags & DXGI_ADAPTER_FLAG_SOFTWARE.0 as u32 != 0 {
+            continue;
+        }
+        let len = desc.Description.iter().position(|&c| c == 0).unwrap_or(desc.Description.len());
+        let key = format!("0x{:08X}_0x{:08X}", desc.AdapterLuid.HighPart as u32, desc.AdapterLuid.LowPart);
+        names.push((key, String::from_utf16_lossy(&desc.Description[..len])));
+    }
+    names
+}
+
+// NVML entry points (nvml.h), loaded at runtime so EasyFPS doesn't depend on the NVIDIA driver
+type NvmlInit = unsafe extern "C" fn() -> i32;
+type NvmlShutdown = unsafe extern "C" fn() -> i32;
+type NvmlDeviceGetHandleByIndex = unsafe extern "C" fn(u32, *mut *mut std::ffi::c_void) -> i32;
+type NvmlDeviceGetTemperature = unsafe extern "C" fn(*mut std::ffi::c_void, u32, *mut u32) -> i32;
+type RawProc = unsafe extern "system" fn() -> isize;
+
+const NVML_SUCCESS: i32 = 0;
+const NVML_TEMPERATURE_GPU: u32 = 0;
+
+/// Temperature of the first NVIDIA GPU through nvml.dll (shipped with the driver)
+struct Nvml {
+    module: windows::Win32::Foundation::HMODULE,
+    device: *mut std::ffi::c_void,
+    shutdown: NvmlShutdown,
+    get_temperature: NvmlDeviceGetTemperature,
+}
+
+impl Nvml {
+    /// None when the driver (and so nvml.dll) isn't installed or reports no device
+    unsafe fn load() -> Option<Self> {
+        use windows::core::s;
+        use windows::Win32::Foundation::FreeLibrary;
+        use windows::Win32::System::LibraryLoader::{GetProcAddress, LoadLibraryW};
+
+        // System32 on current drivers, NVSMI on older ones
+        let module = LoadLibraryW(w!("nvml.dll"))
+            .or_else(|_| LoadLibraryW(w!("C:\\Program Files\\NVIDIA Corporation\\NVSMI\\nvml.dll")))
+            .ok()?;
+
+        let symbols = (|| {
+            Some((
+                std::mem::transmute::<RawProc, NvmlInit>(GetProcAddress(module, s!("nvmlInit_v2"))?),
+                std::mem::transmute::<RawProc, NvmlShutdown>(GetProcAddress(module, s!("nvmlShutdown"))?),
+                std::mem::transmute::<RawProc, NvmlDeviceGetHandleByIndex>(GetProcAddress(
+                    module,
+                    s!("nvmlDeviceGetHandleByIndex_v2"),
+                )?),
+                std::mem::transmute::<RawProc, NvmlDeviceGetTemperature>(GetProcAddress(
+                    module,
+                    s!("nvmlDeviceGetTemperature"),
+                )?),
+            ))
+        })();
+        let Some((init, shutdown, get_handle, get_temperature)) = symbols else {
+            let _ = FreeLibrary(module);
+            return None;
+        };
+
+        if init() != NVML_SUCCESS {
+            let _ = FreeLibrary(module);
+            return None;
+        }
+        let mut device = std::ptr::null_mut();
+        if get_handle(0, &mut device) != NVML_SUCCESS {
+            shutdown();
+            let _ = FreeLibrary(module);
+            return None;
+        }
+        Some(Self { module, device, shutdown, get_temperature })
+    }
+
+    unsafe fn temperature(&self) -> Option<f32> {
+        let mut temp = 0u32;
+        ((self.get_temperature)(self.device, NVML_TEMPERATURE_GPU, &mut temp) == NVML_SUCCESS).then_some(temp as f32)
+    }
+}
+
+impl Drop for Nvml {
+    fn drop(&mut self) {
+        unsafe {
+            (self.shutdown)();
+            let _ = windows::Win32::Foundation::FreeLibrary(self.module);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn engine(luid: &str, index: u32, engtype: &str, val: f64) -> (String, f64) {
+        (format!("pid_1234_luid_{}_phys_0_eng_{}_engtype_{}", luid, index, engtype), val)
+    }
+
+    const ADAPTER: &str = "0x00000000_0x0000D1A2";
+    const OTHER_ADAPTER: &str = "0x00000000_0x0001F3B4";
+
+    #[test]
+    fn instance_name_parts() {
+        let (name, _) = engine(ADAPTER, 3, "Compute_0", 0.0);
+        assert_eq!(luid_key(&name), Some(ADAPTER));
+        assert_eq!(engine_type(&name), Some("Compute_0"));
+    }
+
+    #[test]
+    fn max_against_sum_clamped() {
+        let items = [
+            engine(ADAPTER, 0, "3D", 60.0),
+            engine(ADAPTER, 1, "Copy", 15.0),
+            engine(ADAPTER, 2, "Compute_0", 20.0),
+        ];
+        assert_eq!(aggregate_gpu_usage(&items, GpuAggregation::Max), 60.0);
+        assert_eq!(aggregate_gpu_usage(&items, GpuAggregation::SumClamped), 95.0);
+    }
+
+    #[test]
+    fn same_type_engines_are_not_added() {
+        // Two 3D engines time-slice the same hardware: only the busiest one counts
+        let items = [
+            engine(ADAPTER, 0, "3D", 40.0),
+            engine(ADAPTER, 1, "3D", 30.0),
+            engine(ADAPTER, 2, "Copy", 10.0),
+        ];
+        assert_eq!(aggregate_gpu_usage(&items, GpuAggregation::SumClamped), 50.0);
+    }
+
+    #[test]
+    fn sum_is_clamped_per_adapter() {
+        let items = [
+            engine(ADAPTER, 0, "3D", 90.0),
+            engine(ADAPTER, 1, "Compute_0", 50.0),
+            engine(OTHER_ADAPTER, 0, "3D", 30.0),
+            engine(OTHER_ADAPTER, 1, "Copy", 20.0),
+        ];
+        // The busiest adapter (140) is clamped, the other one's 50 isn't added to it
+        assert_eq!(aggregate_gpu_usage(&items, GpuAggregation::SumClamped), 100.0);
+
+        let items = [
+            engine(ADAPTER, 0, "3D", 30.0),
+            engine(OTHER_ADAPTER, 0, "3D", 40.0),
+            engine(OTHER_ADAPTER, 1, "Copy", 20.0),
+        ];
+        assert_eq!(aggregate_gpu_usage(&items, GpuAggregation::SumClamped), 60.0);
+    }
 }
@@ -0,0 +1,201 @@
+//! Transient toast notifications (tray balloons) for background events that don't deserve
+//! a modal message box. Toasts are shown by a worker thread, so `notify` never blocks and
+//! can be called from any thread.
+
+use parking_lot::Mutex;
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use windows::Win32::Foundation::HWND;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::UI::Shell::{
+    Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_TIP, NIIF_INFO, NIM_ADD, NIM_DELETE, NIM_MODIFY,
+    NOTIFYICONDATAW,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, LoadIconW, PeekMessageW,
+    RegisterClassW, HICON, HWND_MESSAGE, IDI_APPLICATION, MSG, PM_REMOVE, WINDOW_EX_STYLE,
+    WINDOW_STYLE, WNDCLASSW,
+};
+
+/// Minimum time between two toasts, whatever they say
+const MIN_INTERVAL: Duration = Duration::from_secs(3);
+/// The same title and body aren't shown again within this time
+const REPEAT_INTERVAL: Duration = Duration::from_secs(60);
+/// The notification icon is removed after this long without toasts
+const ICON_LINGER: Duration = Duration::from_secs(10);
+// Icon ID of our own notification area entry (the tray menu icon belongs to tray-icon)
+const NOTIFY_ICON_ID: u32 = 1;
+// Resource ID winres gives the embedded application icon
+const APP_ICON_RESOURCE: usize = 1;
+
+struct Toast {
+    title: String,
+    body: String,
+}
+
+#[derive(Default)]
+struct RateLimiter {
+    last_shown: Option<Instant>,
+    // (title + body, when it was shown)
+    recent: Vec<(String, Instant)>,
+}
+
+impl RateLimiter {
+    fn allow(&mut self, key: &str, now: Instant) -> bool {
+        if self.last_shown.is_some_and(|last| now.duration_since(last) < MIN_INTERVAL) {
+            return false;
+        }
+        self.recent.retain(|(_, at)| now.duration_since(*at) < REPEAT_INTERVAL);
+        if self.recent.iter().any(|(recent, _)| recent == key) {
+            return false;
+        }
+        self.last_shown = Some(now);
+        self.recent.push((key.to_string(), now));
+        true
+    }
+}
+
+static LIMITER: once_cell::sync::Lazy<Mutex<RateLimiter>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(RateLimiter::default()));
+// Worker thread, started on the first toast
+type Worker = Option<(Sender<Toast>, JoinHandle<()>)>;
+static WORKER: once_cell::sync::Lazy<Mutex<Worker>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
+/// Show a toast. Dropped when it comes too soon after the previous one or repeats a recent one.
+pub fn notify(title: &str, body: &str) {
+    if !LIMITER.lock().allow(&format!("{}\n{}", title, body), Instant::now()) {
+        return;
+    }
+
+    let mut worker = WORKER.lock();
+    let (sender, _) = worker.get_or_insert_with(|| {
+        let (sender, receiver) = mpsc::channel();
+        (sender, std::thread::spawn(move || run_notifier(receiver)))
+    });
+    let _ = sender.send(Toast { title: title.to_string(), body: body.to_string() });
+}
+
+/// Stop the worker and remove its notification icon
+pub fn shutdown() {
+    let worker = WORKER.lock().take();
+    if let Some((sender, handle)) = worker {
+        drop(sender);
+        let _ = handle.join();
+    }
+}
+
+fn run_notifier(receiver: Receiver<Toast>) {
+    let Some(hwnd) = (unsafe { create_message_window() }) else {
+        return;
+    };
+    let icon = unsafe { app_icon() };
+    let mut last_toast: Option<Instant> = None;
+
+    loop {
+        match receiver.recv_timeout(Duration::from_millis(500)) {
+            Ok(toast) => unsafe {
+                show_balloon(hwnd, icon, &toast, last_toast.is_some());
+                last_toast = Some(Instant::now());
+            },
+            Err(RecvTimeoutError::Timeout) => {
+                if last_toast.is_some_and(|at| at.elapsed() >= ICON_LINGER) {
+                    unsafe { remove_icon(hwnd) };
+                    last_toast = None;
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+
+        unsafe {
+            let mut msg = MSG::default();
+            while PeekMessageW(&mut msg, hwnd, 0, 0, PM_REMOVE).as_bool() {
+                DispatchMessageW(&msg);
+            }
+        }
+    }
+
+    unsafe {
+        if last_toast.is_some() {
+            remove_icon(hwnd);
+        }
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+/// Message-only window the notification icon belongs to
+unsafe fn create_message_window() -> Option<HWND> {
+    unsafe extern "system" fn wndproc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: windows::Win32::Foundation::WPARAM,
+        lparam: windows::Win32::Foundation::LPARAM,
+    ) -> windows::Win32::Foundation::LRESULT {
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    let class_name = windows::core::w!("EasyFPS_Notify");
+    let wc = WNDCLASSW {
+        lpfnWndProc: Some(wndproc),
+        lpszClassName: class_name,
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    let hwnd = CreateWindowExW(
+        WINDOW_EX_STYLE::default(),
+        class_name,
+        windows::core::w!(""),
+        WINDOW_STYLE::default(),
+        0, 0, 0, 0,
+        HWND_MESSAGE, None, None, None,
+    );
+    (hwnd.0 != 0).then_some(hwnd)
+}
+
+/// The executable's icon (resource 1, embedded by build.rs), or the generic application icon
+unsafe fn app_icon() -> HICON {
+    let from_exe = GetModuleHandleW(None)
+        .and_then(|module| LoadIconW(module, windows::core::PCWSTR(APP_ICON_RESOURCE as *const u16)));
+    from_exe
+        .or_else(|_| LoadIconW(None, IDI_APPLICATION))
+        .unwrap_or_default()
+}
+
+unsafe fn show_balloon(hwnd: HWND, icon: HICON, toast: &Toast, icon_added: bool) {
+    let mut data = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: NOTIFY_ICON_ID,
+        uFlags: NIF_ICON | NIF_TIP | NIF_INFO,
+        hIcon: icon,
+        dwInfoFlags: NIIF_INFO,
+        ..Default::default()
+    };
+    copy_wide(&mut data.szTip, "EasyFPS");
+    copy_wide(&mut data.szInfoTitle, &toast.title);
+    copy_wide(&mut data.szInfo, &toast.body);
+
+    let message = if icon_added { NIM_MODIFY } else { NIM_ADD };
+    // Fails while Explorer isn't running; the toast is just lost
+    let _ = Shell_NotifyIconW(message, &data);
+}
+
+unsafe fn remove_icon(hwnd: HWND) {
+    let data = NOTIFYICONDATAW {
+        cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+        hWnd: hwnd,
+        uID: NOTIFY_ICON_ID,
+        ..Default::default()
+    };
+    let _ = Shell_NotifyIconW(NIM_DELETE, &data);
+}
+
+/// Copy `text` into a fixed-size, null-terminated UTF-16 field, truncating if needed
+fn copy_wide(field: &mut [u16], text: &str) {
+    let len = field.len() - 1;
+    for (dst, src) in field.iter_mut().zip(text.encode_utf16().take(len).chain(std::iter::once(0))) {
+        *dst = src;
+    }
+}
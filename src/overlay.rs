@@ -1,98 +1,684 @@
-use crate::settings::{FpsColor, OverlayPosition, OverlaySize, Settings};
+use crate::fps_capture::{FpsData, FrameCap};
+use crate::fullscreen::DisplayRect;
+use crate::i18n::{tr, Text};
+use crate::monitor::{GpuAdapterUsage, NetworkRate, RamUsage, SystemMonitor};
+use crate::settings::{
+    BackgroundStyle, FpsColor, FpsColorMode, NumberLocale, OverlayLayout, OverlayPosition, OverlaySize, RamDisplay, Settings,
+    StatKind, Unit,
+};
+use crate::template::{Template, TemplateValues};
 use parking_lot::Mutex;
 use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, POINT, RECT, WPARAM};
 use windows::Win32::Graphics::Gdi::{
-    BeginPaint, CreateFontW, CreateSolidBrush, DeleteObject, EndPaint,
-    InvalidateRect, SelectObject, SetBkMode, SetTextColor, TextOutW, HBRUSH,
-    PAINTSTRUCT, TRANSPARENT, RoundRect, CreatePen, PS_SOLID,
+    BeginPaint, CreateFontW, GetDC, ReleaseDC, CreateSolidBrush, DeleteObject, EndPaint, FillRect, GetMonitorInfoW,
+    Ellipse, GetStockObject, NULL_PEN, MonitorFromPoint, MonitorFromRect, MONITORINFO, MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL,
+    MONITOR_DEFAULTTOPRIMARY,
+    InvalidateRect, SelectObject, SetBkMode, SetTextColor, TextOutW, HBRUSH, HFONT, HPEN,
+    PAINTSTRUCT, TRANSPARENT, RoundRect, CreatePen, PS_INSIDEFRAME, PS_SOLID,
 };
+use windows::Win32::UI::HiDpi::GetDpiForWindow;
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetSystemMetrics,
-    PeekMessageW, PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes,
-    SetWindowPos, ShowWindow, TranslateMessage, HWND_TOPMOST, LWA_ALPHA,
-    MSG, PM_REMOVE, SM_CXSCREEN, SWP_NOACTIVATE, SWP_SHOWWINDOW, SW_HIDE, SW_SHOWNOACTIVATE,
-    WM_DESTROY, WM_PAINT, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetClientRect, GetSystemMetrics, GetWindow, GetWindowLongPtrW, GetWindowRect,
+    IsWindowVisible, PeekMessageW, PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes, SetTimer, SetWindowLongPtrW,
+    SetWindowPos, ShowWindow, TranslateMessage, GWL_EXSTYLE, HTCAPTION, HWND_TOPMOST, LWA_ALPHA, LWA_COLORKEY,
+    GW_HWNDPREV, MSG, PM_REMOVE, SM_CXSCREEN, SM_CXVIRTUALSCREEN, SM_CYSCREEN, SM_CYVIRTUALSCREEN,
+    SM_XVIRTUALSCREEN, SM_YVIRTUALSCREEN, SWP_FRAMECHANGED, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE,
+    SWP_NOZORDER, SWP_SHOWWINDOW, SW_HIDE, SW_SHOWNOACTIVATE,
+    WM_CONTEXTMENU, WM_DESTROY, WM_ENTERSIZEMOVE, WM_EXITSIZEMOVE, WM_NCHITTEST, WM_PAINT, WM_TIMER, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
     WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
 };
 
-const OVERLAY_MARGIN: i32 = 10;
+const FADE_DURATION: std::time::Duration = std::time::Duration::from_millis(150);
 const BACKGROUND_COLOR: u32 = 0x1A1A1A;
+// auto_contrast over a dark scene: light box, dark labels, and dark text instead of white
+const LIGHT_BACKGROUND_COLOR: u32 = 0xE8E8E8;
+const LIGHT_LABEL_COLOR: u32 = 0x555555;
+const LIGHT_TEXT_COLOR: u32 = 0x1A1A1A;
+// Scene luminance (0-1) under which the box turns light, and over which it turns dark again
+const DARK_SCENE_BELOW: f64 = 0.3;
+const BRIGHT_SCENE_ABOVE: f64 = 0.4;
+// Screen points sampled this far outside the overlay, so its own pixels aren't read (px)
+const BACKDROP_SAMPLE_GAP: i32 = 4;
 const BORDER_RADIUS: i32 = 6;
+// TextOnly fills the window with this color and makes it transparent through
+// LWA_COLORKEY. Per-pixel alpha (UpdateLayeredWindow) would need the whole overlay
+// painted into a 32-bit DIB; the color key keeps the plain WM_PAINT path. It is
+// near-black so antialiased text edges, blended against it, read as part of the shadow.
+const COLOR_KEY: u32 = 0x010101;
+// Shadow offset for TextOnly text (px at 100% scaling)
+const SHADOW_OFFSET: i32 = 1;
+// Outline width with show_border (px at 100% scaling)
+const OUTLINE_WIDTH: i32 = 2;
+// Narrowest overlay (px at 100% scaling), so a lone short value still gets a proper box
+const MIN_WIDTH: i32 = 40;
+// Low-FPS alert: the border blinks red for FLASH_DURATION, toggling every FLASH_BLINK_MS
+const FLASH_COLOR: u32 = 0x3C3CFF; // Red (COLORREF is BGR)
+const FLASH_DURATION: std::time::Duration = std::time::Duration::from_secs(2);
+const FLASH_BLINK_MS: u128 = 250;
+// Space above and below the text of the TopBar layout (px at 100% scaling)
+const TOP_BAR_PAD: i32 = 3;
+// "REC" dot in the top-right corner while a benchmark runs (px at 100% scaling)
+const REC_COLOR: u32 = 0x3C3CFF; // Red (COLORREF is BGR)
+const REC_DOT: i32 = 6;
+// Share of the normal opacity while the last values linger after the game lost focus
+const DIM_OPACITY: f32 = 0.6;
+// force_topmost: how often the overlay thread checks that nothing covers the overlay
+const TOPMOST_TIMER_ID: usize = 1;
+const TOPMOST_INTERVAL_MS: u32 = 500;
+// Frametime graph: one 1px column per frame, frametimes at or above the ceiling are spikes
+const GRAPH_WIDTH: i32 = 120;
+const GRAPH_HEIGHT: i32 = 40;
+const GRAPH_CEILING_MS: f64 = 50.0;
+const GRAPH_SPIKE_COLOR: u32 = 0x3C3CFF; // Red (COLORREF is BGR)
+// Histogram: HISTOGRAM_BUCKET_MS wide bars up to GRAPH_CEILING_MS, slower frames pile up
+// in the last (spike colored) bar. Same size as the graph.
+const HISTOGRAM_BUCKET_MS: f64 = 2.0;
+const HISTOGRAM_BARS: usize = (GRAPH_CEILING_MS / HISTOGRAM_BUCKET_MS) as usize;
+// Refresh warning: on after the FPS stays under REFRESH_WARNING_BELOW x Hz for
+// REFRESH_WARNING_DELAY, off once it's back over REFRESH_WARNING_CLEAR x Hz
+const REFRESH_WARNING_BELOW: f64 = 0.85;
+const REFRESH_WARNING_CLEAR: f64 = 0.95;
+const REFRESH_WARNING_DELAY: std::time::Duration = std::time::Duration::from_secs(3);
+const REFRESH_WARNING_COLOR: u32 = 0x00A5FF; // Orange (COLORREF is BGR)
+// Between the stats of the Horizontal layout
+const HORIZONTAL_SEPARATOR: &str = "  |  ";
+// Upper bound for min_display_fps, so real (if low) framerates are never masked
+const MAX_MIN_DISPLAY_FPS: u32 = 10;
+// Longer CPU/GPU/process names are cut, so they don't widen the overlay past the stats
+const NAME_MAX_CHARS: usize = 24;
 
 /// Overlay display data (thread-safe)
 struct OverlayData {
     current_fps: f64,
+    instant_fps: f64,
     one_percent_low: f64,
+    point_one_percent_low: f64,
+    frametime_ms: f64,
+    jitter_ms: f64,
+    stutter_count: u32,
+    min_fps: f64,
+    max_fps: f64,
+    avg_fps_session: f64,
     cpu_usage: f32,
     gpu_usage: f32,
+    ram_usage: RamUsage,
+    gpu_temp: Option<f32>,
+    battery_percent: Option<u8>,
+    on_ac: bool,
+    network: NetworkRate,
+    disk_usage: f32,
+    cpu_name: Option<String>,
+    gpu_name: Option<String>,
+    core_usage: Vec<f32>,
+    gpu_adapters: Vec<GpuAdapterUsage>,
     position: OverlayPosition,
     fps_color: FpsColor,
+    stats_color: FpsColor,
+    // Outline color, None without show_border
+    border_color: Option<FpsColor>,
+    background_style: BackgroundStyle,
+    fps_color_mode: FpsColorMode,
     size: OverlaySize,
+    layout: OverlayLayout,
+    font: String,
+    font_weight: u32,
+    font_italic: bool,
     show_1_percent_low: bool,
+    show_point_one_low: bool,
+    show_frametime_ms: bool,
+    show_jitter: bool,
+    show_stutter_count: bool,
+    show_min_max_avg: bool,
+    show_fps_spread: bool,
+    window_min_fps: f64,
+    window_max_fps: f64,
+    show_clock: bool,
+    clock_24h: bool,
+    show_resolution: bool,
     show_cpu_usage: bool,
+    show_per_core_cpu: bool,
     show_gpu_usage: bool,
+    show_all_gpus: bool,
+    show_ram_usage: bool,
+    show_gpu_temp: bool,
+    show_battery: bool,
+    show_network: bool,
+    show_disk_usage: bool,
+    show_hardware_names: bool,
+    auto_contrast: bool,
+    /// A benchmark run is recording, shown as a red dot
+    recording: bool,
+    show_process_name: bool,
+    ram_display: RamDisplay,
+    show_frametime_graph: bool,
+    /// Most recent frametimes (ms), oldest first, for the graph
+    frametimes: Vec<f64>,
+    show_histogram: bool,
+    /// Frames per HISTOGRAM_BUCKET_MS bucket, HISTOGRAM_BARS long
+    histogram: Vec<u32>,
     overlay_opacity: u8,
+    fps_on_bottom: bool,
+    stat_order: Vec<StatKind>,
+    total_playtime: Option<u64>,
+    cap: Option<FrameCap>,
+    show_cap_stability: bool,
+    is_capped: bool,
+    show_cap_indicator: bool,
+    show_instant_fps: bool,
+    /// FPS has been well under the refresh rate for a while (show_refresh_warning)
+    refresh_warning: bool,
+    /// When the FPS last dropped under the warning level
+    below_refresh_since: Option<std::time::Instant>,
+    min_display_fps: u32,
+    usage_warn_pct: u32,
+    usage_crit_pct: u32,
+    display_fps: Option<f64>,
+    show_present_vs_display: bool,
+    gpu_busy: Option<(f64, f64)>,
+    show_gpu_busy: bool,
+    game_name: Option<String>,
+    resolution: Option<(i32, i32)>,
+    refresh_hz: Option<u32>,
+    /// Rendered overlay_template lines, None = default layout
+    template_lines: Option<Vec<String>>,
+    stalled: bool,
+    /// PresentMon couldn't attach, the FPS reads "Admin?" or "N/A"
+    capture_failed: bool,
+    number_locale: NumberLocale,
+    compact_numbers: bool,
+    fps_decimals: u8,
+    usage_decimals: u8,
+    /// Monitor the game is on, the overlay is placed on it (None = primary monitor)
+    display: Option<DisplayRect>,
+    /// DPI of the monitor the overlay window is on (96 = 100% scaling)
+    dpi: u32,
+    /// auto_scale factor for the monitor's resolution (1.0 = off or 1080p)
+    resolution_scale: f32,
+}
+
+impl OverlayData {
+    /// A length designed at 96 DPI (and 1080p), in pixels at the overlay's DPI and resolution
+    fn px(&self, px: i32) -> i32 {
+        ((px * self.dpi as i32 / 96) as f32 * self.resolution_scale).round() as i32
+    }
+
+    /// The size preset's (width, height, large font, small font), scaled to the overlay's DPI
+    fn dimensions(&self) -> (i32, i32, i32, i32) {
+        let (width, height, font_large, font_small) = self.size.dimensions();
+        (self.px(width), self.px(height), self.px(font_large), self.px(font_small))
+    }
 }
 
 static OVERLAY_HWND: AtomicIsize = AtomicIsize::new(0);
 static OVERLAY_VISIBLE: AtomicBool = AtomicBool::new(false);
+// Cleared by the toggle hotkey: the overlay stays hidden even while a game is running
+static OVERLAY_ENABLED: AtomicBool = AtomicBool::new(true);
+// Unlocked from the tray: the overlay takes mouse input and can be dragged
+static POSITION_LOCKED: AtomicBool = AtomicBool::new(true);
+// The animations setting, for hide() which gets no settings
+static ANIMATIONS: AtomicBool = AtomicBool::new(true);
+static FADE: Mutex<Fade> = Mutex::new(Fade { alpha: 0, from: 0, target: 0, started: None });
+// The overlay_clickthrough setting; the overlay is click-through when this is set and it's locked
+static CLICKTHROUGH: AtomicBool = AtomicBool::new(true);
+// The force_topmost setting, read by the overlay thread's timer
+static FORCE_TOPMOST: AtomicBool = AtomicBool::new(false);
+// The exclude_from_capture setting, applied as the window's display affinity
+static EXCLUDE_FROM_CAPTURE: AtomicBool = AtomicBool::new(false);
+// auto_contrast found a dark scene around the overlay: the box is drawn light
+static LIGHT_BOX: AtomicBool = AtomicBool::new(false);
+// TextOnly style: the window is keyed out on COLOR_KEY
+static TEXT_ONLY: AtomicBool = AtomicBool::new(false);
+// Elevation can't change while running, checked once for the capture-failed text
+static ELEVATED: once_cell::sync::Lazy<bool> = once_cell::sync::Lazy::new(crate::elevation::is_elevated);
+// Set while the user is dragging, so update_window doesn't pull the overlay back
+static DRAGGING: AtomicBool = AtomicBool::new(false);
+// Where the last drag ended, until the main loop saves it
+static DROPPED_AT: Mutex<Option<(i32, i32)>> = Mutex::new(None);
+// Entry picked from the overlay's right-click menu, until the main loop acts on it
+static MENU_COMMAND: Mutex<Option<&'static str>> = Mutex::new(None);
+// Until when the border flashes for a low-FPS alert
+static FLASH_UNTIL: Mutex<Option<std::time::Instant>> = Mutex::new(None);
+
+/// Right-click menu entry that moves the overlay back to the default corner
+pub const MENU_RESET_POSITION: &str = "reset_position";
 static OVERLAY_DATA: once_cell::sync::Lazy<Mutex<OverlayData>> =
     once_cell::sync::Lazy::new(|| Mutex::new(OverlayData {
         current_fps: 0.0,
+        instant_fps: 0.0,
         one_percent_low: 0.0,
+        point_one_percent_low: 0.0,
+        frametime_ms: 0.0,
+        jitter_ms: 0.0,
+        stutter_count: 0,
+        min_fps: 0.0,
+        max_fps: 0.0,
+        avg_fps_session: 0.0,
         cpu_usage: 0.0,
         gpu_usage: 0.0,
+        ram_usage: RamUsage { percent: 0.0, used_mb: 0, total_mb: 0 },
+        gpu_temp: None,
+        battery_percent: None,
+        on_ac: false,
+        network: NetworkRate { down: 0.0, up: 0.0 },
+        disk_usage: 0.0,
+        cpu_name: None,
+        gpu_name: None,
+        core_usage: Vec::new(),
+        gpu_adapters: Vec::new(),
         position: OverlayPosition::TopRight,
         fps_color: FpsColor::White,
+        stats_color: FpsColor::White,
+        border_color: None,
+        background_style: BackgroundStyle::SolidBox,
+        fps_color_mode: FpsColorMode::Static,
         size: OverlaySize::Medium,
+        layout: OverlayLayout::Vertical,
+        font: crate::settings::DEFAULT_FONT.to_string(),
+        font_weight: 700,
+        font_italic: false,
         show_1_percent_low: true,
+        show_point_one_low: false,
+        show_frametime_ms: false,
+        show_jitter: false,
+        show_stutter_count: false,
+        show_min_max_avg: false,
+        show_fps_spread: false,
+        window_min_fps: 0.0,
+        window_max_fps: 0.0,
+        show_clock: false,
+        clock_24h: true,
+        show_resolution: false,
         show_cpu_usage: false,
+        show_per_core_cpu: false,
         show_gpu_usage: false,
+        show_all_gpus: false,
+        show_ram_usage: false,
+        show_gpu_temp: false,
+        show_battery: false,
+        show_network: false,
+        show_disk_usage: false,
+        show_hardware_names: false,
+        auto_contrast: false,
+        recording: false,
+        show_process_name: false,
+        ram_display: RamDisplay::Percent,
+        show_frametime_graph: false,
+        frametimes: Vec::new(),
+        show_histogram: false,
+        histogram: Vec::new(),
         overlay_opacity: 90,
+        fps_on_bottom: false,
+        stat_order: StatKind::ALL.to_vec(),
+        total_playtime: None,
+        cap: None,
+        show_cap_stability: false,
+        is_capped: false,
+        show_cap_indicator: false,
+        show_instant_fps: false,
+        refresh_warning: false,
+        below_refresh_since: None,
+        min_display_fps: 0,
+        usage_warn_pct: 85,
+        usage_crit_pct: 95,
+        display_fps: None,
+        show_present_vs_display: false,
+        gpu_busy: None,
+        show_gpu_busy: false,
+        game_name: None,
+        resolution: None,
+        refresh_hz: None,
+        template_lines: None,
+        stalled: false,
+        capture_failed: false,
+        number_locale: NumberLocale::Invariant,
+        compact_numbers: false,
+        fps_decimals: 0,
+        usage_decimals: 0,
+        display: None,
+        dpi: 96,
+        resolution_scale: 1.0,
     }));
 
+/// GDI objects reused across paints instead of being created ~60 times a second
+#[derive(Default)]
+struct GdiCache {
+    // Font for the pixel height, family, weight and italic flag it was created for, rebuilt
+    // when size, DPI or the font settings change
+    font: Option<(i32, String, u32, bool, HFONT)>,
+    // Box fill and edge in the color they were created for (dark, or light with auto_contrast)
+    background: Option<(u32, HBRUSH, HPEN)>,
+    // Outline in the border color and width it was created for
+    border_pen: Option<(u32, i32, HPEN)>,
+    // Graph bars in the value color (rebuilt when it changes) and spikes
+    bar_brush: Option<(u32, HBRUSH)>,
+    spike_brush: HBRUSH,
+    // Fill for the TextOnly style
+    color_key_brush: HBRUSH,
+    // Fill of the benchmark "REC" dot
+    rec_brush: HBRUSH,
+}
+
+impl GdiCache {
+    unsafe fn font(&mut self, height: i32, family: &str, weight: u32, italic: bool) -> HFONT {
+        match &self.font {
+            Some((cached_height, cached_family, cached_weight, cached_italic, font))
+                if *cached_height == height && cached_family == family && *cached_weight == weight && *cached_italic == italic =>
+            {
+                *font
+            }
+            _ => {
+                if let Some((_, _, _, _, old)) = self.font.take() {
+                    let _ = DeleteObject(old);
+                }
+                let face: Vec<u16> = family.encode_utf16().chain(std::iter::once(0)).collect();
+                let font = CreateFontW(
+                    height, 0, 0, 0, weight as i32, italic as u32, 0, 0, 0, 0, 0, 0, 0,
+                    PCWSTR(face.as_ptr()),
+                );
+                self.font = Some((height, family.to_string(), weight, italic, font));
+                font
+            }
+        }
+    }
+
+    unsafe fn background(&mut self, color: u32) -> (HBRUSH, HPEN) {
+        match self.background {
+            Some((cached_color, brush, pen)) if cached_color == color => (brush, pen),
+            _ => {
+                if let Some((_, old_brush, old_pen)) = self.background.take() {
+                    let _ = DeleteObject(old_brush);
+                    let _ = DeleteObject(old_pen);
+                }
+                let brush = CreateSolidBrush(COLORREF(color));
+                let pen = CreatePen(PS_SOLID, 1, COLORREF(color));
+                self.background = Some((color, brush, pen));
+                (brush, pen)
+            }
+        }
+    }
+
+    /// Pen for the outline. PS_INSIDEFRAME keeps the whole stroke inside the RoundRect's
+    /// bounds, so the window edge doesn't clip half of it.
+    unsafe fn border_pen(&mut self, color: COLORREF, width: i32) -> HPEN {
+        match self.border_pen {
+            Some((cached_color, cached_width, pen)) if cached_color == color.0 && cached_width == width => pen,
+            _ => {
+                if let Some((_, _, old)) = self.border_pen.take() {
+                    let _ = DeleteObject(old);
+                }
+                let pen = CreatePen(PS_INSIDEFRAME, width, color);
+                self.border_pen = Some((color.0, width, pen));
+                pen
+            }
+        }
+    }
+
+    unsafe fn color_key_brush(&mut self) -> HBRUSH {
+        if self.color_key_brush.is_invalid() {
+            self.color_key_brush = CreateSolidBrush(COLORREF(COLOR_KEY));
+        }
+        self.color_key_brush
+    }
+
+    unsafe fn rec_brush(&mut self) -> HBRUSH {
+        if self.rec_brush.is_invalid() {
+            self.rec_brush = CreateSolidBrush(COLORREF(REC_COLOR));
+        }
+        self.rec_brush
+    }
+
+    unsafe fn graph_brushes(&mut self, bar_color: COLORREF) -> (HBRUSH, HBRUSH) {
+        let bar = match self.bar_brush {
+            Some((color, brush)) if color == bar_color.0 => brush,
+            _ => {
+                if let Some((_, old)) = self.bar_brush.take() {
+                    let _ = DeleteObject(old);
+                }
+                let brush = CreateSolidBrush(bar_color);
+                self.bar_brush = Some((bar_color.0, brush));
+                brush
+            }
+        };
+        if self.spike_brush.is_invalid() {
+            self.spike_brush = CreateSolidBrush(COLORREF(GRAPH_SPIKE_COLOR));
+        }
+        (bar, self.spike_brush)
+    }
+
+    unsafe fn release(&mut self) {
+        let cache = std::mem::take(self);
+        if let Some((_, _, _, _, font)) = cache.font {
+            let _ = DeleteObject(font);
+        }
+        if let Some((_, brush)) = cache.bar_brush {
+            let _ = DeleteObject(brush);
+        }
+        if let Some((_, _, pen)) = cache.border_pen {
+            let _ = DeleteObject(pen);
+        }
+        if let Some((_, brush, pen)) = cache.background {
+            let _ = DeleteObject(brush);
+            let _ = DeleteObject(pen);
+        }
+        for brush in [cache.spike_brush, cache.color_key_brush, cache.rec_brush] {
+            if !brush.is_invalid() {
+                let _ = DeleteObject(brush);
+            }
+        }
+    }
+}
+
+static GDI_CACHE: once_cell::sync::Lazy<Mutex<GdiCache>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(GdiCache::default()));
+
+// Last parsed overlay_template (source, parse result), so it isn't re-parsed on every update
+type TemplateCache = Option<(String, Option<Template>)>;
+static TEMPLATE_CACHE: once_cell::sync::Lazy<Mutex<TemplateCache>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(None));
+
 pub fn init() -> Result<(), String> {
     std::thread::spawn(move || {
         if let Err(e) = run_overlay_window() {
-            eprintln!("Overlay error: {}", e);
+            crate::log::error(&format!("Overlay error: {}", e));
         }
     });
     std::thread::sleep(std::time::Duration::from_millis(100));
     Ok(())
 }
 
-pub fn show(fps: f64, one_percent_low: f64, cpu_usage: f32, gpu_usage: f32, settings: &Settings) {
+/// Update and show the overlay on `display` (the game's monitor, None = primary monitor)
+pub fn show(fps: &FpsData, monitor: &SystemMonitor, settings: &Settings, display: Option<DisplayRect>) {
+    show_with(fps, monitor, None, settings, display);
+}
+
+/// Show the overlay on the primary monitor with made-up, moving values, to try settings
+/// out without a game. `elapsed` is the time since the preview started.
+pub fn show_preview(elapsed: std::time::Duration, monitor: &SystemMonitor, settings: &Settings) {
+    let t = elapsed.as_secs_f64();
+    // FPS sweeps 60-240, CPU and GPU swing out of step with it
+    let fps = 150.0 + 90.0 * (t * 0.8).sin();
+    let data = FpsData {
+        fps,
+        fps_instant: fps + 6.0 * (t * 7.0).sin(),
+        one_percent_low: fps * 0.8,
+        point_one_percent_low: fps * 0.7,
+        frametime_ms: 1000.0 / fps,
+        jitter_ms: 0.4,
+        window_min_fps: fps * 0.85,
+        window_max_fps: fps * 1.1,
+        min_fps: 60.0,
+        max_fps: 240.0,
+        avg_fps_session: 150.0,
+        ..Default::default()
+    };
+    let cpu = 45.0 + 25.0 * (t * 1.3).sin() as f32;
+    let gpu = 70.0 + 25.0 * (t * 0.9 + 1.0).sin() as f32;
+    show_with(&data, monitor, Some((cpu, gpu)), settings, None);
+}
+
+/// `show`, with the CPU/GPU load taken from `usage` instead of the monitor when given
+fn show_with(
+    fps: &FpsData,
+    monitor: &SystemMonitor,
+    usage: Option<(f32, f32)>,
+    settings: &Settings,
+    display: Option<DisplayRect>,
+) {
+    if !OVERLAY_ENABLED.load(Ordering::SeqCst) {
+        hide();
+        return;
+    }
     {
         let mut data = OVERLAY_DATA.lock();
-        data.current_fps = fps;
-        data.one_percent_low = one_percent_low;
-        data.cpu_usage = cpu_usage;
-        data.gpu_usage = gpu_usage;
+        data.display = display;
+        data.current_fps = fps.fps;
+        data.instant_fps = fps.fps_instant;
+        data.one_percent_low = fps.one_percent_low;
+        data.point_one_percent_low = fps.point_one_percent_low;
+        data.frametime_ms = fps.frametime_ms;
+        data.jitter_ms = fps.jitter_ms;
+        data.stutter_count = fps.stutter_count;
+        data.min_fps = fps.min_fps;
+        data.window_min_fps = fps.window_min_fps;
+        data.window_max_fps = fps.window_max_fps;
+        data.max_fps = fps.max_fps;
+        data.avg_fps_session = fps.avg_fps_session;
+        data.cap = fps.cap;
+        data.is_capped = fps.is_capped;
+        data.display_fps = fps.display_fps;
+        data.gpu_busy = fps.gpu_busy_ms.zip(fps.gpu_busy_pct);
+        data.stalled = fps.stalled;
+        data.capture_failed = fps.capture_failed;
+        (data.cpu_usage, data.gpu_usage) = usage.unwrap_or((monitor.get_cpu_usage(), monitor.get_gpu_usage()));
+        data.ram_usage = monitor.get_ram_usage();
+        data.gpu_temp = monitor.get_gpu_temp();
+        data.battery_percent = monitor.get_battery_percent();
+        data.on_ac = monitor.is_on_ac();
+        data.network = monitor.get_network();
+        data.disk_usage = monitor.get_disk_usage();
+        if data.cpu_name.as_deref() != monitor.get_cpu_name() {
+            data.cpu_name = monitor.get_cpu_name().map(str::to_string);
+        }
+        if data.gpu_name.as_deref() != monitor.get_gpu_name() {
+            data.gpu_name = monitor.get_gpu_name().map(str::to_string);
+        }
+        data.core_usage.clear();
+        data.core_usage.extend_from_slice(monitor.get_per_core_usage());
+        data.gpu_adapters.clear();
+        data.gpu_adapters.extend_from_slice(monitor.get_gpu_adapters());
         data.position = settings.position;
         data.fps_color = settings.fps_color;
+        data.stats_color = settings.stats_color.unwrap_or(settings.fps_color);
+        data.border_color = settings.show_border.then_some(settings.border_color);
+        data.background_style = settings.background_style;
+        data.fps_color_mode = settings.fps_color_mode;
         data.size = settings.size;
+        data.layout = settings.layout;
+        if data.font != settings.overlay_font() {
+            data.font = settings.overlay_font().to_string();
+        }
+        data.font_weight = settings.font_weight;
+        data.font_italic = settings.font_italic;
         data.show_1_percent_low = settings.show_1_percent_low;
+        data.show_point_one_low = settings.show_point_one_low;
+        data.show_frametime_ms = settings.show_frametime_ms;
+        data.show_jitter = settings.show_jitter;
+        data.show_stutter_count = settings.show_stutter_count;
+        data.show_min_max_avg = settings.show_min_max_avg;
+        data.show_fps_spread = settings.show_fps_spread;
+        data.show_clock = settings.show_clock;
+        data.clock_24h = settings.clock_24h;
+        data.show_resolution = settings.show_resolution;
         data.show_cpu_usage = settings.show_cpu_usage;
+        data.show_per_core_cpu = settings.show_per_core_cpu;
         data.show_gpu_usage = settings.show_gpu_usage;
+        data.show_all_gpus = settings.show_all_gpus;
+        data.show_ram_usage = settings.show_ram_usage;
+        data.show_gpu_temp = settings.show_gpu_temp;
+        data.show_battery = settings.show_battery;
+        data.show_network = settings.show_network;
+        data.show_disk_usage = settings.show_disk_usage;
+        data.show_hardware_names = settings.show_hardware_names;
+        data.auto_contrast = settings.auto_contrast;
+        data.recording = crate::fps_capture::is_benchmarking();
+        data.show_process_name = settings.show_process_name;
+        data.ram_display = settings.ram_display;
+        data.show_frametime_graph = settings.show_frametime_graph;
+        data.frametimes = if settings.show_frametime_graph {
+            crate::fps_capture::recent_frametimes(GRAPH_WIDTH as usize)
+        } else {
+            Vec::new()
+        };
+        data.show_histogram = settings.show_histogram;
+        data.histogram = if settings.show_histogram {
+            histogram_bars(&crate::fps_capture::frametime_histogram(HISTOGRAM_BUCKET_MS))
+        } else {
+            Vec::new()
+        };
         data.overlay_opacity = settings.overlay_opacity;
+        data.fps_on_bottom = settings.fps_on_bottom;
+        data.stat_order.clone_from(&settings.stat_order);
+        data.show_cap_stability = settings.show_cap_stability;
+        data.show_cap_indicator = settings.show_cap_indicator;
+        data.show_instant_fps = settings.show_instant_fps;
+        data.refresh_warning = update_refresh_warning(&mut data) && settings.show_refresh_warning;
+        data.min_display_fps = settings.min_display_fps.min(MAX_MIN_DISPLAY_FPS);
+        data.usage_warn_pct = settings.usage_warn_pct;
+        data.usage_crit_pct = settings.usage_crit_pct;
+        data.show_present_vs_display = settings.show_present_vs_display;
+        data.show_gpu_busy = settings.show_gpu_busy;
+        data.number_locale = settings.number_locale;
+        data.compact_numbers = settings.compact_numbers;
+        data.fps_decimals = settings.fps_decimals.min(1);
+        data.usage_decimals = settings.usage_decimals.min(1);
+        data.template_lines = render_template(&settings.overlay_template, &data);
     }
     
     let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
     if hwnd_val != 0 {
         let hwnd = HWND(hwnd_val as isize);
         
-        // Apply Opacity
+        set_clickthrough(settings.overlay_clickthrough);
+        if EXCLUDE_FROM_CAPTURE.swap(settings.exclude_from_capture, Ordering::SeqCst) != settings.exclude_from_capture {
+            apply_capture_exclusion();
+        }
+        let text_only = settings.background_style == BackgroundStyle::TextOnly;
+        let style_changed = TEXT_ONLY.swap(text_only, Ordering::SeqCst) != text_only;
+
+        // Apply Opacity, faded in when the overlay appears (or comes back during a fade-out)
         let alpha = (settings.overlay_opacity as f32 / 100.0 * 255.0) as u8;
-        unsafe {
-            let _ = SetLayeredWindowAttributes(hwnd, None, alpha, LWA_ALPHA);
+        ANIMATIONS.store(settings.animations, Ordering::SeqCst);
+        FORCE_TOPMOST.store(settings.force_topmost, Ordering::SeqCst);
+        let appearing = !OVERLAY_VISIBLE.swap(true, Ordering::SeqCst);
+        let mut fade = FADE.lock();
+        if settings.animations && (appearing || fade.started.is_some()) {
+            if fade.target != alpha {
+                fade.start(alpha);
+            }
+            if appearing {
+                // Shown at the fade's current alpha, not the last opacity
+                unsafe {
+                    set_alpha(hwnd, fade.alpha);
+                }
+            } else if style_changed {
+                unsafe {
+                    set_alpha(hwnd, fade.alpha);
+                }
+            }
+        } else {
+            *fade = Fade { alpha, from: alpha, target: alpha, started: None };
+            unsafe {
+                set_alpha(hwnd, alpha);
+            }
         }
-        if !OVERLAY_VISIBLE.load(Ordering::SeqCst) {
-            OVERLAY_VISIBLE.store(true, Ordering::SeqCst);
+        if appearing {
             unsafe {
                 let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
             }
         }
+        drop(fade);
         
         // Update position and size
         unsafe {
@@ -102,110 +688,970 @@ pub fn show(fps: f64, one_percent_low: f64, cpu_usage: f32, gpu_usage: f32, sett
     }
 }
 
+/// Flash the overlay border for a low-FPS alert
+pub fn flash_border() {
+    *FLASH_UNTIL.lock() = Some(std::time::Instant::now() + FLASH_DURATION);
+}
+
+/// Whether the border is in the lit half of a flash blink
+fn flash_phase() -> bool {
+    let mut until = FLASH_UNTIL.lock();
+    match *until {
+        Some(end) => {
+            let now = std::time::Instant::now();
+            if now >= end {
+                *until = None;
+                return false;
+            }
+            ((end - now).as_millis() / FLASH_BLINK_MS).is_multiple_of(2)
+        }
+        None => false,
+    }
+}
+
+/// Flip the toggle hotkey state. Disabling hides the overlay right away,
+/// `show` keeps it hidden until it's enabled again.
+pub fn toggle_enabled() {
+    let was_enabled = OVERLAY_ENABLED.fetch_xor(true, Ordering::SeqCst);
+    if was_enabled {
+        hide();
+    }
+}
+
+/// Whether the overlay is shown (it can be hidden from the hotkey or the tray)
+pub fn is_enabled() -> bool {
+    OVERLAY_ENABLED.load(Ordering::SeqCst)
+}
+
+pub fn is_position_locked() -> bool {
+    POSITION_LOCKED.load(Ordering::SeqCst)
+}
+
+/// Lock the overlay in place or unlock it so it can be dragged (never click-through)
+pub fn set_position_locked(locked: bool) {
+    POSITION_LOCKED.store(locked, Ordering::SeqCst);
+    apply_clickthrough();
+}
+
+/// Let clicks through to the game, or have the locked overlay take them
+pub fn set_clickthrough(enabled: bool) {
+    if CLICKTHROUGH.swap(enabled, Ordering::SeqCst) != enabled {
+        apply_clickthrough();
+    }
+}
+
+/// auto_contrast: sample the screen just outside the overlay and pick the box color for
+/// the scene behind it, a light box over a dark scene. Called on the 1 s stats cadence.
+pub fn sample_backdrop() {
+    use windows::Win32::Graphics::Gdi::{GetPixel, CLR_INVALID};
+
+    let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
+    if hwnd_val == 0 || !OVERLAY_VISIBLE.load(Ordering::SeqCst) {
+        return;
+    }
+    let mut rect = RECT::default();
+    unsafe {
+        if GetWindowRect(HWND(hwnd_val), &mut rect).is_err() {
+            return;
+        }
+    }
+    // Three points along each edge, BACKDROP_SAMPLE_GAP outside it
+    let (left, top) = (rect.left - BACKDROP_SAMPLE_GAP, rect.top - BACKDROP_SAMPLE_GAP);
+    let (right, bottom) = (rect.right + BACKDROP_SAMPLE_GAP, rect.bottom + BACKDROP_SAMPLE_GAP);
+    let xs = [rect.left, (rect.left + rect.right) / 2, rect.right];
+    let ys = [rect.top, (rect.top + rect.bottom) / 2, rect.bottom];
+    let points = xs
+        .iter()
+        .flat_map(|&x| [(x, top), (x, bottom)])
+        .chain(ys.iter().flat_map(|&y| [(left, y), (right, y)]));
+
+    let mut total = 0.0;
+    let mut samples = 0;
+    unsafe {
+        let hdc = GetDC(None);
+        for (x, y) in points {
+            let color = GetPixel(hdc, x, y);
+            // Off-screen points (overlay in a corner) can't be read
+            if color.0 == CLR_INVALID {
+                continue;
+            }
+            let (r, g, b) = (color.0 & 0xFF, (color.0 >> 8) & 0xFF, (color.0 >> 16) & 0xFF);
+            total += (0.2126 * r as f64 + 0.7152 * g as f64 + 0.0722 * b as f64) / 255.0;
+            samples += 1;
+        }
+        ReleaseDC(None, hdc);
+    }
+    if samples == 0 {
+        return;
+    }
+    let luminance = total / samples as f64;
+    // Two thresholds, so a scene in between doesn't flip the box every second
+    let light = if LIGHT_BOX.load(Ordering::SeqCst) {
+        luminance < BRIGHT_SCENE_ABOVE
+    } else {
+        luminance < DARK_SCENE_BELOW
+    };
+    LIGHT_BOX.store(light, Ordering::SeqCst);
+}
+
+/// Set the display affinity from exclude_from_capture. WDA_EXCLUDEFROMCAPTURE needs
+/// Windows 10 2004; on older versions the call fails and the overlay stays in captures.
+fn apply_capture_exclusion() {
+    use windows::Win32::UI::WindowsAndMessaging::{SetWindowDisplayAffinity, WDA_EXCLUDEFROMCAPTURE, WDA_NONE};
+
+    let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
+    if hwnd_val == 0 {
+        return;
+    }
+    let exclude = EXCLUDE_FROM_CAPTURE.load(Ordering::SeqCst);
+    let affinity = if exclude { WDA_EXCLUDEFROMCAPTURE } else { WDA_NONE };
+    if let Err(e) = unsafe { SetWindowDisplayAffinity(HWND(hwnd_val), affinity) } {
+        crate::log::warn(&format!("Could not change the overlay's capture exclusion: {}", e));
+    }
+}
+
+/// Toggle WS_EX_TRANSPARENT to match the lock and click-through state
+fn apply_clickthrough() {
+    let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
+    if hwnd_val == 0 {
+        return;
+    }
+    let hwnd = HWND(hwnd_val);
+    let transparent = POSITION_LOCKED.load(Ordering::SeqCst) && CLICKTHROUGH.load(Ordering::SeqCst);
+    unsafe {
+        let ex_style = GetWindowLongPtrW(hwnd, GWL_EXSTYLE) as u32;
+        let ex_style = if transparent {
+            ex_style | WS_EX_TRANSPARENT.0
+        } else {
+            ex_style & !WS_EX_TRANSPARENT.0
+        };
+        SetWindowLongPtrW(hwnd, GWL_EXSTYLE, ex_style as isize);
+        // Extended style changes only take effect after SWP_FRAMECHANGED
+        let _ = SetWindowPos(
+            hwnd,
+            None,
+            0,
+            0,
+            0,
+            0,
+            SWP_NOMOVE | SWP_NOSIZE | SWP_NOZORDER | SWP_NOACTIVATE | SWP_FRAMECHANGED,
+        );
+    }
+}
+
+/// Top-left corner where the user last dropped the overlay, once per drag
+pub fn take_dropped_position() -> Option<(i32, i32)> {
+    DROPPED_AT.lock().take()
+}
+
+/// Entry picked from the overlay's right-click menu: a tray MENU_* id or MENU_RESET_POSITION
+pub fn take_menu_command() -> Option<&'static str> {
+    MENU_COMMAND.lock().take()
+}
+
+/// Set the accumulated playtime (seconds) shown for the current game, None hides the line
+pub fn set_playtime(total_secs: Option<u64>) {
+    OVERLAY_DATA.lock().total_playtime = total_secs;
+}
+
+/// Set the game name, resolution and monitor refresh rate used by the text template,
+/// the Stream layout and the resolution line
+pub fn set_app_info(game_name: Option<String>, resolution: Option<(i32, i32)>, refresh_hz: Option<u32>) {
+    let mut data = OVERLAY_DATA.lock();
+    data.game_name = game_name;
+    data.resolution = resolution;
+    data.refresh_hz = refresh_hz;
+}
+
+/// FPS value as displayed: "PAUSED" while no frames arrive, "Admin?" (or "N/A" when
+/// already elevated) when PresentMon couldn't attach, near-zero readings during
+/// transitions are shown as "--"
+fn fps_text(data: &OverlayData) -> String {
+    if data.capture_failed {
+        if *ELEVATED { "N/A" } else { "Admin?" }.to_string()
+    } else if data.stalled {
+        "PAUSED".to_string()
+    } else if data.current_fps < data.min_display_fps as f64 {
+        "--".to_string()
+    } else if data.show_instant_fps {
+        // Averaged value first, the fast-reacting one in brackets
+        format!(
+            "{} ({})",
+            fps_num_text(data, data.current_fps),
+            fps_num_text(data, data.instant_fps)
+        )
+    } else {
+        fps_num_text(data, data.current_fps)
+    }
+}
+
+/// Debounced "FPS well under the refresh rate" state, with a gap between the on and off
+/// levels so a reading hovering around one of them doesn't flicker
+fn update_refresh_warning(data: &mut OverlayData) -> bool {
+    let Some(hz) = data.refresh_hz.filter(|_| !data.stalled && data.current_fps > 0.0) else {
+        data.below_refresh_since = None;
+        return false;
+    };
+    let hz = hz as f64;
+    if data.current_fps >= hz * REFRESH_WARNING_CLEAR {
+        data.below_refresh_since = None;
+        return false;
+    }
+    if data.current_fps < hz * REFRESH_WARNING_BELOW {
+        let since = *data.below_refresh_since.get_or_insert_with(std::time::Instant::now);
+        return data.refresh_warning || since.elapsed() >= REFRESH_WARNING_DELAY;
+    }
+    // Between the two levels: keep the current state
+    data.refresh_warning
+}
+
+/// The FPS line's value: the reading, tagged with show_cap_indicator when a cap holds it.
+/// A cap at the monitor's refresh rate is most likely VSync.
+fn fps_line_text(data: &OverlayData) -> String {
+    let text = fps_text(data);
+    match data.cap {
+        Some(cap) if data.show_cap_indicator && data.is_capped && !data.stalled => {
+            let tag = if data.refresh_hz == Some(cap.fps) { "VSYNC" } else { "CAP" };
+            format!("{} {}", text, tag)
+        }
+        _ => text,
+    }
+}
+
+/// RAM value as configured: "45%" or "7350/16384 MB"
+fn ram_text(data: &OverlayData) -> String {
+    match data.ram_display {
+        RamDisplay::Percent => format!("{}%", num_text(data, data.ram_usage.percent as f64)),
+        RamDisplay::Megabytes if data.compact_numbers => {
+            let bytes = |mb: u64| mb as f64 * 1024.0 * 1024.0;
+            format!(
+                "{}/{}",
+                data.number_locale.format_compact(bytes(data.ram_usage.used_mb), Unit::Bytes),
+                data.number_locale.format_compact(bytes(data.ram_usage.total_mb), Unit::Bytes)
+            )
+        }
+        RamDisplay::Megabytes => format!(
+            "{}/{} MB",
+            num_text(data, data.ram_usage.used_mb as f64),
+            num_text(data, data.ram_usage.total_mb as f64)
+        ),
+    }
+}
+
+/// Per-core loads as grid rows. Columns grow with the core count (2 for 4 cores,
+/// 6 for 32) so the grid stays roughly square; values are padded with figure spaces
+/// (digit width) so the columns line up in a proportional font.
+fn core_grid_rows(cores: &[f32]) -> Vec<String> {
+    const FIGURE_SPACE: char = '\u{2007}';
+    let columns = ((cores.len() as f64).sqrt().ceil() as usize).clamp(2, 8);
+    cores
+        .chunks(columns)
+        .map(|row| {
+            row.iter()
+                .map(|load| format!("{:>3}", load.round() as u32).replace(' ', &FIGURE_SPACE.to_string()))
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect()
+}
+
+/// Short overlay label for a GPU adapter: the DXGI name without vendor/brand words
+/// ("NVIDIA GeForce RTX 3080" -> "RTX 3080"), "GPU<index>" when unknown or still too long
+fn gpu_label(adapter: &GpuAdapterUsage, index: usize) -> String {
+    const NOISE: [&str; 6] = ["NVIDIA", "GeForce", "AMD", "Radeon", "Intel", "Graphics"];
+    const MAX_LABEL_CHARS: usize = 12;
+    let short = adapter.name.as_deref().map(|name| {
+        name.replace("(R)", "")
+            .replace("(TM)", "")
+            .split_whitespace()
+            .filter(|word| !NOISE.contains(word))
+            .collect::<Vec<_>>()
+            .join(" ")
+    });
+    match short {
+        Some(short) if !short.is_empty() && short.chars().count() <= MAX_LABEL_CHARS => short,
+        _ => format!("GPU{}", index),
+    }
+}
+
+/// Threshold color for the FPS number: red below `low`, yellow in between, green at/above `high`
+fn color_for_fps(fps: f64, low: u32, high: u32) -> (u8, u8, u8) {
+    if fps < low as f64 {
+        (255, 60, 60)
+    } else if fps < high as f64 {
+        (255, 220, 0)
+    } else {
+        (57, 255, 20)
+    }
+}
+
+/// Amber from `warn`%, red from `crit`%, None (normal color) below
+fn usage_color(pct: f32, warn: u32, crit: u32) -> Option<(u8, u8, u8)> {
+    if pct >= crit as f32 {
+        Some((255, 60, 60))
+    } else if pct >= warn as f32 {
+        Some((255, 176, 0))
+    } else {
+        None
+    }
+}
+
+/// Whole-number stat value in the user's number format
+fn num_text(data: &OverlayData, value: f64) -> String {
+    decimal_text(data, value, 0)
+}
+
+/// FPS value with the fps_decimals setting
+fn fps_num_text(data: &OverlayData, value: f64) -> String {
+    decimal_text(data, value, data.fps_decimals)
+}
+
+/// Stat value with `decimals` digits; with compact_numbers, from 1000 up it's "1.2K" instead
+fn decimal_text(data: &OverlayData, value: f64, decimals: u8) -> String {
+    if data.compact_numbers && value.abs().round() >= 1000.0 {
+        data.number_locale.format_compact(value, Unit::Count)
+    } else {
+        data.number_locale.format(value, decimals as usize)
+    }
+}
+
+/// Bytes per second as "256K" or "1.2M"
+fn rate_text(data: &OverlayData, bytes_per_sec: f64) -> String {
+    if data.compact_numbers {
+        return data.number_locale.format_compact(bytes_per_sec, Unit::Bytes);
+    }
+    let kb = bytes_per_sec / 1024.0;
+    if kb < 1024.0 {
+        format!("{}K", data.number_locale.format(kb, 0))
+    } else {
+        format!("{}M", data.number_locale.format(kb / 1024.0, 1))
+    }
+}
+
+/// Render the user template, None when it's empty or invalid (default layout is used)
+fn render_template(template: &str, data: &OverlayData) -> Option<Vec<String>> {
+    if template.is_empty() {
+        return None;
+    }
+
+    let mut cache = TEMPLATE_CACHE.lock();
+    if cache.as_ref().map(|(source, _)| source.as_str()) != Some(template) {
+        let parsed = Template::parse(template)
+            .map_err(|e| crate::log::warn(&format!("Invalid overlay template: {}", e)))
+            .ok();
+        *cache = Some((template.to_string(), parsed));
+    }
+    let parsed = cache.as_ref().and_then(|(_, parsed)| parsed.as_ref())?;
+
+    let values = TemplateValues {
+        fps: fps_text(data),
+        low: fps_num_text(data, data.one_percent_low),
+        cpu: num_text(data, data.cpu_usage as f64),
+        gpu: num_text(data, data.gpu_usage as f64),
+        game: data.game_name.clone().unwrap_or_default(),
+        time: local_time_hhmm(),
+        res: data.resolution.map(|(w, h)| format!("{}x{}", w, h)).unwrap_or_default(),
+    };
+    Some(parsed.render(&values))
+}
+
+/// `name` cut to NAME_MAX_CHARS, with "…" when something was dropped
+fn truncate_name(name: &str) -> String {
+    if name.chars().count() <= NAME_MAX_CHARS {
+        return name.to_string();
+    }
+    let mut cut: String = name.chars().take(NAME_MAX_CHARS - 1).collect();
+    cut.truncate(cut.trim_end().len());
+    cut.push('…');
+    cut
+}
+
+fn local_time_hhmm() -> String {
+    let now = unsafe { windows::Win32::System::SystemInformation::GetLocalTime() };
+    format!("{:02}:{:02}", now.wHour, now.wMinute)
+}
+
+/// Local time for the clock line, "21:05" or "9:05 PM"
+fn clock_text(clock_24h: bool) -> String {
+    if clock_24h {
+        return local_time_hhmm();
+    }
+    let now = unsafe { windows::Win32::System::SystemInformation::GetLocalTime() };
+    let suffix = if now.wHour < 12 { "AM" } else { "PM" };
+    let hour = match now.wHour % 12 {
+        0 => 12,
+        hour => hour,
+    };
+    format!("{}:{:02} {}", hour, now.wMinute, suffix)
+}
+
+/// Keep the last values up but fainter (the game is briefly out of the foreground,
+/// see `hide_delay_ms`). The next `show` restores the full opacity.
+pub fn dim(settings: &Settings) {
+    let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
+    if hwnd_val == 0 || !OVERLAY_VISIBLE.load(Ordering::SeqCst) {
+        return;
+    }
+    let alpha = (settings.overlay_opacity as f32 / 100.0 * DIM_OPACITY * 255.0) as u8;
+    let mut fade = FADE.lock();
+    if fade.target != alpha || fade.started.is_some() {
+        *fade = Fade { alpha, from: alpha, target: alpha, started: None };
+        unsafe {
+            set_alpha(HWND(hwnd_val), alpha);
+        }
+    }
+}
+
 pub fn hide() {
     if OVERLAY_VISIBLE.load(Ordering::SeqCst) {
         OVERLAY_VISIBLE.store(false, Ordering::SeqCst);
         let hwnd_val = OVERLAY_HWND.load(Ordering::SeqCst);
         if hwnd_val != 0 {
             let hwnd = HWND(hwnd_val as isize);
-            unsafe {
-                let _ = ShowWindow(hwnd, SW_HIDE);
+            let mut fade = FADE.lock();
+            if ANIMATIONS.load(Ordering::SeqCst) {
+                // The overlay thread hides the window once the fade reaches 0
+                fade.start(0);
+            } else {
+                fade.started = None;
+                unsafe {
+                    let _ = ShowWindow(hwnd, SW_HIDE);
+                }
             }
         }
     }
 }
 
-fn calculate_dimensions(data: &OverlayData) -> (i32, i32, i32, i32) {
-    let (_, height, font_large, font_small) = data.size.dimensions();
-    
-    // FPS Width
-    let fps_num_width = if data.current_fps >= 100.0 {
-        (font_large as f32 * 0.6 * 3.0) as i32
-    } else if data.current_fps >= 10.0 {
-        (font_large as f32 * 0.6 * 2.0) as i32
+/// Alpha animation of the overlay, stepped by the overlay thread
+struct Fade {
+    /// Alpha the window has now
+    alpha: u8,
+    from: u8,
+    /// Opacity while showing, 0 while hiding
+    target: u8,
+    /// None when no fade is running
+    started: Option<std::time::Instant>,
+}
+
+impl Fade {
+    /// Head for `target` from the current alpha, replacing any fade in progress
+    fn start(&mut self, target: u8) {
+        self.from = self.alpha;
+        self.target = target;
+        self.started = Some(std::time::Instant::now());
+    }
+}
+
+/// Set the window's opacity, keying out COLOR_KEY in the TextOnly style
+unsafe fn set_alpha(hwnd: HWND, alpha: u8) {
+    let _ = if TEXT_ONLY.load(Ordering::SeqCst) {
+        SetLayeredWindowAttributes(hwnd, COLORREF(COLOR_KEY), alpha, LWA_ALPHA | LWA_COLORKEY)
     } else {
-        (font_large as f32 * 0.6) as i32
+        SetLayeredWindowAttributes(hwnd, None, alpha, LWA_ALPHA)
     };
-    let fps_label_width = (font_small as f32 * 0.5 * 3.0) as i32;
-    let fps_total_width = 6 + fps_num_width + 4 + fps_label_width + 6;
+}
 
-    let mut max_width = fps_total_width;
-    let mut total_height = height;
+/// Advance the running fade, hiding the window when a fade-out completes
+unsafe fn step_fade(hwnd: HWND) {
+    let mut fade = FADE.lock();
+    let Some(started) = fade.started else { return };
+    let t = (started.elapsed().as_secs_f32() / FADE_DURATION.as_secs_f32()).min(1.0);
+    fade.alpha = (fade.from as f32 + (fade.target as f32 - fade.from as f32) * t).round() as u8;
+    set_alpha(hwnd, fade.alpha);
+    if t >= 1.0 {
+        fade.started = None;
+        if fade.target == 0 {
+            let _ = ShowWindow(hwnd, SW_HIDE);
+        }
+    }
+}
 
+/// Which color a line's value is drawn in
+#[derive(Clone, Copy, PartialEq)]
+enum LineKind {
+    /// The FPS number (threshold coloring applies)
+    Fps,
+    /// Any other stat, in the configured color
+    Stat,
+    /// CPU/GPU load (%), amber/red near full load
+    Usage(f32),
+    /// Stream layout header (game name), not aligned with the values
+    Header,
+    /// Unlabeled text sized to fit (template lines, per-core grid)
+    Text,
+}
 
-    // Check additional lines width
-    // Use approximation: char width ~ font_large * 0.6
-    let estimate_width = |text_len: usize| -> i32 {
-        6 + (font_large as f32 * 0.6 * text_len as f32) as i32 + 6
+/// One overlay line: gray label followed by the colored value
+struct OverlayLine {
+    label: String,
+    value: String,
+    kind: LineKind,
+}
+
+impl OverlayLine {
+    fn new(label: impl Into<String>, value: String, kind: LineKind) -> Self {
+        Self { label: label.into(), value, kind }
+    }
+}
+
+/// The lines to draw, top to bottom, for the current layout.
+/// Both sizing and painting work from this list, so they can't disagree.
+fn build_lines(data: &OverlayData) -> Vec<OverlayLine> {
+    let stat = |label: &'static str, value| OverlayLine::new(label, value, LineKind::Stat);
+
+    // Custom template: its lines replace the layout
+    if let Some(lines) = &data.template_lines {
+        return lines.iter().map(|line| OverlayLine::new("", line.clone(), LineKind::Text)).collect();
+    }
+
+    let fps = || OverlayLine::new("FPS", fps_line_text(data), LineKind::Fps);
+    let one_percent_low = || stat("1%", fps_num_text(data, data.one_percent_low));
+    let usage = |label: String, pct: f32| {
+        OverlayLine::new(label, format!("{}%", decimal_text(data, pct as f64, data.usage_decimals)), LineKind::Usage(pct))
     };
+    let cpu = || usage("CPU".to_string(), data.cpu_usage);
+    let gpu = || usage("GPU".to_string(), data.gpu_usage);
+
+    if data.layout == OverlayLayout::Stream {
+        // Fixed block for stream captures, whatever the individual show_* options say
+        let game = data
+            .game_name
+            .as_deref()
+            .map(|name| name.strip_suffix(".exe").unwrap_or(name).to_string())
+            .unwrap_or_else(|| "Unknown game".to_string());
+        return vec![
+            OverlayLine::new("", game, LineKind::Header),
+            fps(),
+            one_percent_low(),
+            cpu(),
+            gpu(),
+        ];
+    }
+
+    let mut lines = Vec::new();
+
+    // Game executable on top, "game.exe" shown as "game"
+    if let (true, Some(name)) = (data.show_process_name, &data.game_name) {
+        let name = name.strip_suffix(".exe").unwrap_or(name);
+        lines.push(OverlayLine::new("", truncate_name(name), LineKind::Header));
+    }
+
+    // Stats in the stat_order order, each only when its show_* option is on.
+    // FPS goes last instead with fps_on_bottom.
+    for &kind in &data.stat_order {
+        match kind {
+            StatKind::Fps if !data.fps_on_bottom => lines.push(fps()),
+            StatKind::OnePercentLow if data.show_1_percent_low => lines.push(one_percent_low()),
+            StatKind::PointOneLow if data.show_point_one_low => {
+                lines.push(stat("0.1%", fps_num_text(data, data.point_one_percent_low)));
+            }
+            StatKind::Frametime if data.show_frametime_ms => {
+                lines.push(stat("ms", data.number_locale.format(data.frametime_ms, 1)));
+            }
+            StatKind::Jitter if data.show_jitter => {
+                lines.push(stat("JIT", data.number_locale.format(data.jitter_ms, 1)));
+            }
+            StatKind::StutterCount if data.show_stutter_count => {
+                lines.push(stat("STU", data.stutter_count.to_string()));
+            }
+            StatKind::MinMaxAvg if data.show_min_max_avg => {
+                lines.push(stat("MIN", fps_num_text(data, data.min_fps)));
+                lines.push(stat("MAX", fps_num_text(data, data.max_fps)));
+                lines.push(stat("AVG", fps_num_text(data, data.avg_fps_session)));
+            }
+            // The same spread on a single line, over the averaging window instead of the session
+            StatKind::FpsSpread if data.show_fps_spread => {
+                lines.push(stat(
+                    "",
+                    format!(
+                        "L{} A{} H{}",
+                        fps_num_text(data, data.window_min_fps),
+                        fps_num_text(data, data.current_fps),
+                        fps_num_text(data, data.window_max_fps)
+                    ),
+                ));
+            }
+            StatKind::Cpu if data.show_cpu_usage => lines.push(cpu()),
+            StatKind::PerCoreCpu if data.show_per_core_cpu => {
+                lines.extend(core_grid_rows(&data.core_usage).into_iter().map(|row| OverlayLine::new("", row, LineKind::Text)));
+            }
+            // One line per adapter only when there's more than one to tell apart
+            StatKind::Gpu if data.show_gpu_usage && data.show_all_gpus && data.gpu_adapters.len() > 1 => {
+                lines.extend(data.gpu_adapters.iter().enumerate().map(|(i, adapter)| usage(gpu_label(adapter, i), adapter.usage)));
+            }
+            StatKind::Gpu if data.show_gpu_usage => lines.push(gpu()),
+            StatKind::GpuTemp if data.show_gpu_temp => {
+                let temp = match data.gpu_temp {
+                    Some(temp) => format!("{}°C", num_text(data, temp as f64)),
+                    None => "N/A".to_string(),
+                };
+                lines.push(stat("GPU°", temp));
+            }
+            StatKind::Ram if data.show_ram_usage => lines.push(stat("RAM", ram_text(data))),
+            StatKind::Network if data.show_network => {
+                lines.push(stat("NET", format!("↓{} ↑{}", rate_text(data, data.network.down), rate_text(data, data.network.up))));
+            }
+            StatKind::Disk if data.show_disk_usage => {
+                lines.push(stat("DISK", format!("{}%", num_text(data, data.disk_usage as f64))));
+            }
+            StatKind::HardwareNames if data.show_hardware_names => {
+                for name in [&data.cpu_name, &data.gpu_name].into_iter().flatten() {
+                    lines.push(OverlayLine::new("", truncate_name(name), LineKind::Text));
+                }
+            }
+            // No line at all on desktops
+            StatKind::Battery if data.show_battery => {
+                if let Some(percent) = data.battery_percent {
+                    let val = if data.on_ac { "AC".to_string() } else { format!("{}%", percent) };
+                    lines.push(stat("BAT", val));
+                }
+            }
+            // Total playtime for this game
+            StatKind::Playtime => {
+                if let Some(secs) = data.total_playtime {
+                    lines.push(stat("TOTAL", crate::playtime::format_playtime(secs)));
+                }
+            }
+            StatKind::Resolution if data.show_resolution => {
+                if let Some((width, height)) = data.resolution {
+                    let mode = match data.refresh_hz {
+                        Some(hz) => format!("{}x{} @{}", width, height, hz),
+                        None => format!("{}x{}", width, height),
+                    };
+                    lines.push(stat("RES", mode));
+                }
+            }
+            // Painted every frame, so the minute is always current
+            StatKind::Clock if data.show_clock => lines.push(stat("", clock_text(data.clock_24h))),
+            // Frame cap stability: ✓ = cap held, ~ = frequently missed (hidden when no cap)
+            StatKind::CapStability if data.show_cap_stability => {
+                if let Some(cap) = data.cap {
+                    let mark = if cap.stable { "✓" } else { "~" };
+                    lines.push(stat("CAP", format!("{} {}", cap.fps, mark)));
+                }
+            }
+            // Presented vs displayed FPS (display part omitted when unavailable)
+            StatKind::PresentVsDisplay if data.show_present_vs_display => {
+                let val = match data.display_fps {
+                    Some(display) => format!("{} / D {}", fps_num_text(data, data.current_fps), fps_num_text(data, display)),
+                    None => fps_num_text(data, data.current_fps),
+                };
+                lines.push(stat("P", val));
+            }
+            // GPU busy time per frame and its share of the frametime (no line without the column)
+            StatKind::GpuBusy if data.show_gpu_busy => {
+                if let Some((busy_ms, busy_pct)) = data.gpu_busy {
+                    let val = format!("{} ms {}%", data.number_locale.format(busy_ms, 1), num_text(data, busy_pct));
+                    lines.push(stat("BUSY", val));
+                }
+            }
+            _ => {}
+        }
+    }
+    if data.fps_on_bottom {
+        lines.push(fps());
+    }
+    lines
+}
+
+/// Overlay (width, height) for the current lines, plus the frametime graph below them.
+/// Sized to the measured text, never narrower than MIN_WIDTH.
+fn calculate_dimensions(data: &OverlayData) -> (i32, i32) {
+    let (_default_width, height, font_large, _font_small) = data.dimensions();
+    let pad = data.px(6);
     
     // Line height is now larger (font_large)
-    let line_height = font_large + 4;
+    let line_height = font_large + data.px(4);
 
-    if data.show_1_percent_low {
-        // "1%: 100" -> 7 chars approx
-        let w = estimate_width(8);
-        max_width = max_width.max(w);
-        total_height += line_height;
+    // One row as wide as the monitor, update_window sets the width
+    if data.layout == OverlayLayout::TopBar {
+        return (data.px(MIN_WIDTH), font_large + data.px(TOP_BAR_PAD) * 2);
     }
-    if data.show_cpu_usage {
-        // "CPU: 100%" -> 9 chars
-        let w = estimate_width(10);
-        max_width = max_width.max(w);
-        total_height += line_height;
+
+    // Widths are measured in the overlay font, so custom fonts and long values fit exactly
+    let lines = build_lines(data);
+    let content_width = unsafe {
+        if data.layout == OverlayLayout::Horizontal {
+            row_width(data, &lines)
+        } else {
+            column_width(data, &lines)
+        }
+    };
+    let width = (pad + content_width + pad).max(data.px(MIN_WIDTH));
+
+    // Base height is for the first line, the others add font_large + padding
+    let total_height = if data.layout == OverlayLayout::Horizontal {
+        height
+    } else {
+        height + line_height * (lines.len() as i32 - 1).max(0)
+    };
+
+    // The graph and the histogram each take a GRAPH_WIDTH x GRAPH_HEIGHT strip at the bottom
+    let strips = data.show_frametime_graph as i32 + data.show_histogram as i32;
+    if strips > 0 {
+        (
+            width.max(pad + data.px(GRAPH_WIDTH) + pad),
+            total_height + (data.px(GRAPH_HEIGHT) + pad) * strips,
+        )
+    } else {
+        (width, total_height)
     }
-    if data.show_gpu_usage {
-        // "GPU: 100%" -> 9 chars
-        let w = estimate_width(10);
-        max_width = max_width.max(w);
-        total_height += line_height;
+}
+
+/// Label as drawn, followed by the gap before its value
+fn label_text(label: &str) -> Vec<u16> {
+    if label.is_empty() {
+        Vec::new()
+    } else {
+        format!("{}  ", label).encode_utf16().collect()
     }
+}
+
+/// Call `f` with a function that measures text in the overlay font
+unsafe fn measure<R>(data: &OverlayData, f: impl FnOnce(&dyn Fn(&str) -> i32) -> R) -> R {
+    let (_, _, font_large, _) = data.dimensions();
+    let hdc = GetDC(None);
+    let old_font = SelectObject(hdc, GDI_CACHE.lock().font(font_large, &data.font, data.font_weight, data.font_italic));
+    let text_width = |text: &str| {
+        let wide: Vec<u16> = text.encode_utf16().collect();
+        let mut size = windows::Win32::Foundation::SIZE::default();
+        let _ = windows::Win32::Graphics::Gdi::GetTextExtentPoint32W(hdc, &wide, &mut size);
+        size.cx
+    };
+    let result = f(&text_width);
+    SelectObject(hdc, old_font);
+    ReleaseDC(None, hdc);
+    result
+}
 
-    (max_width, total_height, fps_num_width, fps_label_width)
+/// Widest line of the one-per-line layouts, placed the way WM_PAINT draws them:
+/// values after their label, or all in one column in the Stream layout
+unsafe fn column_width(data: &OverlayData, lines: &[OverlayLine]) -> i32 {
+    measure(data, |text_width| {
+        let label_width = |line: &OverlayLine| text_width(&String::from_utf16_lossy(&label_text(&line.label)));
+        let value_column = if data.layout == OverlayLayout::Stream {
+            lines
+                .iter()
+                .filter(|line| line.kind != LineKind::Header)
+                .map(label_width)
+                .max()
+                .unwrap_or(0)
+        } else {
+            0
+        };
+        lines
+            .iter()
+            .map(|line| match line.kind {
+                LineKind::Header | LineKind::Text => text_width(&line.value),
+                _ => label_width(line).max(value_column) + text_width(&line.value),
+            })
+            .max()
+            .unwrap_or(0)
+    })
+}
+
+/// Width of the Horizontal layout's row, separators included
+unsafe fn row_width(data: &OverlayData, lines: &[OverlayLine]) -> i32 {
+    measure(data, |text_width| {
+        let separators = text_width(HORIZONTAL_SEPARATOR) * (lines.len() as i32 - 1).max(0);
+        let segments: i32 = lines
+            .iter()
+            .map(|line| {
+                let label = String::from_utf16_lossy(&label_text(&line.label));
+                text_width(&label) + text_width(&line.value)
+            })
+            .sum();
+        separators + segments
+    })
+}
+
+/// Fold histogram buckets into HISTOGRAM_BARS bars, the last one also counting every slower frame
+fn histogram_bars(buckets: &[(f64, u32)]) -> Vec<u32> {
+    let mut bars = vec![0; HISTOGRAM_BARS];
+    for (i, &(_, count)) in buckets.iter().enumerate() {
+        bars[i.min(HISTOGRAM_BARS - 1)] += count;
+    }
+    bars
 }
 
 fn update_window(hwnd: HWND, settings: &Settings) {
-    let data = OVERLAY_DATA.lock();
-    let (default_width, height, font_large, _font_small) = settings.size.dimensions();
-    
-    // Calculate width based on content
-    let (base_w, _, _, _) = calculate_dimensions(&*data);
-    let width = base_w.min(default_width);
-    
-    // Calculate height based on enabled lines
-    // Base height is for FPS line
-    let mut total_height = height; 
+    let mut data = OVERLAY_DATA.lock();
     
-    // Additional lines use font_large + padding
-    let line_height = font_large + 4;
+    // Sizes are designed at 96 DPI and scaled to the monitor the overlay is on
+    data.dpi = match unsafe { GetDpiForWindow(hwnd) } {
+        0 => 96,
+        dpi => dpi,
+    };
+    data.resolution_scale = if settings.auto_scale {
+        resolution_scale(data.display)
+    } else {
+        1.0
+    };
     
-    if data.show_1_percent_low {
-        total_height += line_height;
-    }
-    if data.show_cpu_usage {
-        total_height += line_height;
-    }
-    if data.show_gpu_usage {
-        total_height += line_height;
-    }
+    // Size based on the enabled lines
+    let (width, total_height) = calculate_dimensions(&data);
+    let display = data.display;
+    let margin = data.px(settings.overlay_margin.clamp(0, crate::settings::OVERLAY_MARGIN_MAX));
     
     drop(data);
     
-    let screen_width = unsafe { GetSystemMetrics(SM_CXSCREEN) };
+    let area = placement_area(display, settings.avoid_taskbar);
+    let top_bar = settings.layout == OverlayLayout::TopBar;
+    let width = if top_bar { area.right - area.left } else { width };
     
+    // Bottom corners use the current height, so the overlay stays anchored as lines come and go
+    let left_x = area.left + margin;
+    let right_x = area.right - width - margin;
+    let top_y = area.top + margin;
+    let bottom_y = area.bottom - total_height - margin;
     let (x, y) = match settings.position {
-        OverlayPosition::TopRight => (screen_width - width - OVERLAY_MARGIN, OVERLAY_MARGIN),
-        OverlayPosition::TopLeft => (OVERLAY_MARGIN, OVERLAY_MARGIN),
+        // Pinned along the top of the monitor, wherever `position` says
+        _ if top_bar => (area.left, area.top),
+        OverlayPosition::TopRight => (right_x, top_y),
+        OverlayPosition::TopLeft => (left_x, top_y),
+        OverlayPosition::BottomRight => (right_x, bottom_y),
+        OverlayPosition::BottomLeft => (left_x, bottom_y),
+        OverlayPosition::Custom { x, y } => match offscreen_corner(x, y, width, total_height, margin) {
+            Some(corner) => {
+                // Saved by the main loop like a drop, so the correction only happens once
+                let mut dropped = DROPPED_AT.lock();
+                if *dropped != Some(corner) {
+                    crate::log::warn(&format!(
+                        "Overlay position ({}, {}) is off every monitor, moved to ({}, {})",
+                        x, y, corner.0, corner.1
+                    ));
+                    *dropped = Some(corner);
+                }
+                corner
+            }
+            None => clamp_to_monitor(x, y, width, total_height),
+        },
+        // Clamped, so the box stays fully inside the area whatever the percentages say
+        OverlayPosition::Percent { x_pct, y_pct } => {
+            let free_x = (area.right - area.left - width).max(0) as f32;
+            let free_y = (area.bottom - area.top - total_height).max(0) as f32;
+            (
+                area.left + (free_x * x_pct.clamp(0.0, 100.0) / 100.0).round() as i32,
+                area.top + (free_y * y_pct.clamp(0.0, 100.0) / 100.0).round() as i32,
+            )
+        }
     };
     
+    // The user is moving it right now, the drop is saved as the new position
+    if DRAGGING.load(Ordering::SeqCst) {
+        return;
+    }
+    
     unsafe {
-        // Force Z-Order: Bring to top first
+        // Force Z-Order: Bring to top first. Re-asserted on every update, which also keeps
+        // the overlay above the (topmost) taskbar when an auto-hidden one slides in over it.
         let _ = windows::Win32::UI::WindowsAndMessaging::BringWindowToTop(hwnd);
         // SWP_SHOWWINDOW ensures it stays visible even if something tried to hide it
         let _ = SetWindowPos(hwnd, HWND_TOPMOST, x, y, width, total_height, SWP_NOACTIVATE | SWP_SHOWWINDOW);
     }
 }
 
+/// Nearest corner of the nearest monitor's work area when a `width` x `height` box at
+/// (x, y) doesn't touch any connected monitor, e.g. it was saved on a monitor that's
+/// since been unplugged. None while any part of it is visible.
+fn offscreen_corner(x: i32, y: i32, width: i32, height: i32, margin: i32) -> Option<(i32, i32)> {
+    let rect = RECT { left: x, top: y, right: x + width, bottom: y + height };
+    unsafe {
+        // The virtual screen is the bounding box of all monitors: outside it is off-screen
+        // for sure, inside it the box can still fall in a gap between monitors
+        let virtual_left = GetSystemMetrics(SM_XVIRTUALSCREEN);
+        let virtual_top = GetSystemMetrics(SM_YVIRTUALSCREEN);
+        let in_virtual_screen = rect.right > virtual_left
+            && rect.left < virtual_left + GetSystemMetrics(SM_CXVIRTUALSCREEN)
+            && rect.bottom > virtual_top
+            && rect.top < virtual_top + GetSystemMetrics(SM_CYVIRTUALSCREEN);
+        if in_virtual_screen && MonitorFromRect(&rect, MONITOR_DEFAULTTONULL).0 != 0 {
+            return None;
+        }
+
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if !GetMonitorInfoW(MonitorFromRect(&rect, MONITOR_DEFAULTTONEAREST), &mut info).as_bool() {
+            return None;
+        }
+        let area = info.rcWork;
+        let (left, right) = (area.left + margin, area.right - width - margin);
+        let (top, bottom) = (area.top + margin, area.bottom - height - margin);
+        [(left, top), (right, top), (left, bottom), (right, bottom)]
+            .into_iter()
+            .min_by_key(|&(cx, cy)| (cx - x) as i64 * (cx - x) as i64 + (cy - y) as i64 * (cy - y) as i64)
+    }
+}
+
+/// Keep a dragged overlay fully on the monitor its corner is nearest to,
+/// e.g. after that monitor was disconnected or the resolution dropped
+fn clamp_to_monitor(x: i32, y: i32, width: i32, height: i32) -> (i32, i32) {
+    let mut info = MONITORINFO {
+        cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+        ..Default::default()
+    };
+    let monitor = unsafe { MonitorFromPoint(POINT { x, y }, MONITOR_DEFAULTTONEAREST) };
+    if !unsafe { GetMonitorInfoW(monitor, &mut info) }.as_bool() {
+        return (x, y);
+    }
+    let area = info.rcMonitor;
+    (
+        x.min(area.right - width).max(area.left),
+        y.min(area.bottom - height).max(area.top),
+    )
+}
+
+/// auto_scale factor for `display` (primary monitor if None): its height relative to 1080p
+fn resolution_scale(display: Option<DisplayRect>) -> f32 {
+    use crate::settings::{AUTO_SCALE_BASE_HEIGHT, AUTO_SCALE_MAX, AUTO_SCALE_MIN};
+
+    let monitor = placement_area(display, false);
+    let height = monitor.bottom - monitor.top;
+    if height <= 0 {
+        return 1.0;
+    }
+    (height as f32 / AUTO_SCALE_BASE_HEIGHT as f32).clamp(AUTO_SCALE_MIN, AUTO_SCALE_MAX)
+}
+
+/// Screen rectangle the overlay is placed in: `display` (primary monitor if None), or its
+/// work area (monitor minus taskbar and docked toolbars) with `avoid_taskbar`.
+/// The work area is per monitor, so it's right whichever display the taskbar is on;
+/// an auto-hidden taskbar reserves no work area, see the topmost note in update_window.
+fn placement_area(display: Option<DisplayRect>, avoid_taskbar: bool) -> RECT {
+    let display_rect = display.map(|d| RECT {
+        left: d.left,
+        top: d.top,
+        right: d.left + d.width,
+        bottom: d.top + d.height,
+    });
+    if let (Some(rect), false) = (display_rect, avoid_taskbar) {
+        return rect;
+    }
+
+    unsafe {
+        let (origin, fallback) = match display {
+            Some(d) => (POINT { x: d.left, y: d.top }, MONITOR_DEFAULTTONEAREST),
+            None => (POINT { x: 0, y: 0 }, MONITOR_DEFAULTTOPRIMARY),
+        };
+        let monitor = MonitorFromPoint(origin, fallback);
+        let mut info = MONITORINFO {
+            cbSize: std::mem::size_of::<MONITORINFO>() as u32,
+            ..Default::default()
+        };
+        if GetMonitorInfoW(monitor, &mut info).as_bool() {
+            return if avoid_taskbar { info.rcWork } else { info.rcMonitor };
+        }
+        display_rect.unwrap_or(RECT {
+            left: 0,
+            top: 0,
+            right: GetSystemMetrics(SM_CXSCREEN),
+            bottom: GetSystemMetrics(SM_CYSCREEN),
+        })
+    }
+}
+
 unsafe extern "system" fn overlay_wndproc(
     hwnd: HWND,
     msg: u32,
@@ -218,93 +1664,246 @@ unsafe extern "system" fn overlay_wndproc(
             let hdc = BeginPaint(hwnd, &mut ps);
             
             let data = OVERLAY_DATA.lock();
-            let (default_width, _height, font_large, _font_small) = data.size.dimensions();
+            let (_default_width, _height, font_large, _font_small) = data.dimensions();
+            let pad = data.px(6);
             
-            let (actual_width, total_height, _fps_num_width, _) = calculate_dimensions(&*data);
+            let top_bar = data.layout == OverlayLayout::TopBar;
+            let (width, total_height) = if top_bar {
+                // As wide as update_window made it
+                let mut client = RECT::default();
+                let _ = GetClientRect(hwnd, &mut client);
+                (client.right, client.bottom)
+            } else {
+                calculate_dimensions(&data)
+            };
             
-            // Use calculated width or default, whichever is smaller (to avoid too wide)
-            let width = actual_width.min(default_width);
+            let mut gdi = GDI_CACHE.lock();
             
-            // Background
-            let brush = CreateSolidBrush(windows::Win32::Foundation::COLORREF(BACKGROUND_COLOR));
-            let pen = CreatePen(PS_SOLID, 1, windows::Win32::Foundation::COLORREF(BACKGROUND_COLOR));
-            let old_brush = SelectObject(hdc, brush);
-            let old_pen = SelectObject(hdc, pen);
-            let _ = RoundRect(hdc, 0, 0, width, total_height, BORDER_RADIUS, BORDER_RADIUS);
-            SelectObject(hdc, old_brush);
-            SelectObject(hdc, old_pen);
-            let _ = DeleteObject(brush);
-            let _ = DeleteObject(pen);
+            let text_only = data.background_style == BackgroundStyle::TextOnly;
+            let light_box = !text_only && data.auto_contrast && LIGHT_BOX.load(Ordering::SeqCst);
+            if text_only {
+                // Everything in the key color turns transparent, see COLOR_KEY
+                let rect = RECT { left: 0, top: 0, right: width, bottom: total_height };
+                FillRect(hdc, &rect, gdi.color_key_brush());
+            } else {
+                // Background, outlined with show_border
+                let color = if light_box { LIGHT_BACKGROUND_COLOR } else { BACKGROUND_COLOR };
+                let (brush, mut pen) = gdi.background(color);
+                if flash_phase() {
+                    pen = gdi.border_pen(COLORREF(FLASH_COLOR), data.px(OUTLINE_WIDTH * 2).max(2));
+                } else if let Some(border) = data.border_color {
+                    let (r, g, b) = border.to_rgb();
+                    let color = COLORREF((b as u32) << 16 | (g as u32) << 8 | r as u32);
+                    pen = gdi.border_pen(color, data.px(OUTLINE_WIDTH).max(1));
+                }
+                let old_brush = SelectObject(hdc, brush);
+                let old_pen = SelectObject(hdc, pen);
+                // The top bar is a plain strip
+                let radius = if top_bar { 0 } else { data.px(BORDER_RADIUS) };
+                let _ = RoundRect(hdc, 0, 0, width, total_height, radius, radius);
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+            }
             
             let _ = SetBkMode(hdc, TRANSPARENT);
             
             // Shared Drawing State
-            let mut current_y = 2; // Start with a small top padding
-            let line_height = font_large + 4; 
-            let label_color_ref = windows::Win32::Foundation::COLORREF(0xAAAAAA); // Light gray for labels
-            let (r, g, b) = data.fps_color.to_rgb();
-            let value_color_ref = windows::Win32::Foundation::COLORREF(
-                 (b as u32) << 16 | (g as u32) << 8 | (r as u32)
-            );
+            let mut current_y = data.px(2); // Start with a small top padding
+            let line_height = font_large + data.px(4); 
+            let label_color_ref = if light_box {
+                COLORREF(LIGHT_LABEL_COLOR)
+            } else {
+                windows::Win32::Foundation::COLORREF(0xAAAAAA) // Light gray for labels
+            };
+            // White text would vanish on the light box, it's drawn dark there
+            let to_colorref = |(r, g, b): (u8, u8, u8)| {
+                if light_box && (r, g, b) == (255, 255, 255) {
+                    return COLORREF(LIGHT_TEXT_COLOR);
+                }
+                windows::Win32::Foundation::COLORREF((b as u32) << 16 | (g as u32) << 8 | (r as u32))
+            };
+            // The FPS line has its own color, every other value uses the stats color
+            let value_color_ref = to_colorref(data.stats_color.to_rgb());
+            // Threshold mode only recolors a real FPS reading (not "--" or "PAUSED")
+            let fps_color_ref = match data.fps_color_mode {
+                FpsColorMode::Threshold { low, high } if !data.stalled && data.current_fps >= data.min_display_fps as f64 => {
+                    to_colorref(color_for_fps(data.current_fps, low, high))
+                }
+                _ if data.refresh_warning => COLORREF(REFRESH_WARNING_COLOR),
+                _ => to_colorref(data.fps_color.to_rgb()),
+            };
 
-            // Helper to draw a line: "Label  Value"
+            // Every line: "Label  Value"
             // Label is gray, Value is colored (white/green/whatever set in settings)
             // Both use the same Large Font
-            let draw_stat_line = |label: &str, value: String, y: i32| {
-                let font = CreateFontW(
-                    font_large, 0, 0, 0, 700, 0, 0, 0, 0, 0, 0, 0, 0,
-                    windows::core::w!("Segoe UI"),
-                );
-                let old_font_loop = SelectObject(hdc, font);
-                
-                // Draw Label (Gray)
-                SetTextColor(hdc, label_color_ref);
-                let label_wide: Vec<u16> = format!("{}  ", label).encode_utf16().collect();
-                let _ = TextOutW(hdc, 6, y, &label_wide);
-                
-                // Calc label width to position value
+            let old_font = SelectObject(hdc, gdi.font(font_large, &data.font, data.font_weight, data.font_italic));
+
+            let text_width = |text: &[u16]| -> i32 {
                 let mut size = windows::Win32::Foundation::SIZE::default();
-                let _ = windows::Win32::Graphics::Gdi::GetTextExtentPoint32W(hdc, &label_wide, &mut size);
-                
-                // Draw Value (Colored)
-                SetTextColor(hdc, value_color_ref);
-                let value_wide: Vec<u16> = value.encode_utf16().collect();
-                let _ = TextOutW(hdc, 6 + size.cx, y, &value_wide);
-                
-                SelectObject(hdc, old_font_loop);
-                let _ = DeleteObject(font);
+                let _ = windows::Win32::Graphics::Gdi::GetTextExtentPoint32W(hdc, text, &mut size);
+                size.cx
+            };
+            // TextOnly draws every string twice: a black copy offset down-right, then the text
+            let shadow = data.px(SHADOW_OFFSET).max(1);
+            let draw_text = |x: i32, y: i32, text: &[u16], color: COLORREF| {
+                if text_only {
+                    SetTextColor(hdc, COLORREF(0));
+                    let _ = TextOutW(hdc, x + shadow, y + shadow, text);
+                }
+                SetTextColor(hdc, color);
+                let _ = TextOutW(hdc, x, y, text);
+            };
+
+            let lines = build_lines(&data);
+            // The Stream layout lines all values up in one column
+            let value_column = if data.layout == OverlayLayout::Stream {
+                lines
+                    .iter()
+                    .filter(|line| line.kind != LineKind::Header)
+                    .map(|line| text_width(&label_text(&line.label)))
+                    .max()
+                    .unwrap_or(0)
+            } else {
+                0
+            };
+
+            let value_color = |kind: LineKind| match kind {
+                LineKind::Fps => fps_color_ref,
+                LineKind::Usage(pct) => {
+                    usage_color(pct, data.usage_warn_pct, data.usage_crit_pct).map_or(value_color_ref, to_colorref)
+                }
+                LineKind::Stat | LineKind::Header | LineKind::Text => value_color_ref,
             };
 
-            // FPS
-            let fps_val = format!("{:.0}", data.current_fps);
-            draw_stat_line("FPS", fps_val, current_y);
-            current_y += line_height;
+            if top_bar {
+                // Each stat centered in an equal share of the bar
+                let slot = (width - pad * 2) / lines.len().max(1) as i32;
+                let y = (total_height - font_large) / 2;
+                for (i, line) in lines.iter().enumerate() {
+                    let label_wide = label_text(&line.label);
+                    let value_wide: Vec<u16> = line.value.encode_utf16().collect();
+                    let label_width = text_width(&label_wide);
+                    let item_width = label_width + text_width(&value_wide);
+                    let x = pad + slot * i as i32 + (slot - item_width).max(0) / 2;
+                    draw_text(x, y, &label_wide, label_color_ref);
+                    draw_text(x + label_width, y, &value_wide, value_color(line.kind));
+                }
+            } else if data.layout == OverlayLayout::Horizontal {
+                // One row, left to right, gray separators between the stats
+                let separator: Vec<u16> = HORIZONTAL_SEPARATOR.encode_utf16().collect();
+                let mut x = pad;
+                for (i, line) in lines.iter().enumerate() {
+                    if i > 0 {
+                        draw_text(x, current_y, &separator, label_color_ref);
+                        x += text_width(&separator);
+                    }
+                    let label_wide = label_text(&line.label);
+                    draw_text(x, current_y, &label_wide, label_color_ref);
+                    x += text_width(&label_wide);
+                    let value_wide: Vec<u16> = line.value.encode_utf16().collect();
+                    draw_text(x, current_y, &value_wide, value_color(line.kind));
+                    x += text_width(&value_wide);
+                }
+            } else {
+                for line in &lines {
+                    // Draw Label (Gray)
+                    let label_wide = label_text(&line.label);
+                    draw_text(pad, current_y, &label_wide, label_color_ref);
+
+                    // Draw Value (Colored), after the label
+                    let value_x = match line.kind {
+                        LineKind::Header | LineKind::Text => 0,
+                        _ => text_width(&label_wide).max(value_column),
+                    };
+                    let value_wide: Vec<u16> = line.value.encode_utf16().collect();
+                    draw_text(pad + value_x, current_y, &value_wide, value_color(line.kind));
 
-            // 1% low
-            if data.show_1_percent_low {
-                let val = format!("{:.0}", data.one_percent_low);
-                draw_stat_line("1%", val, current_y);
-                current_y += line_height;
+                    current_y += line_height;
+                }
             }
 
-            // CPU
-            if data.show_cpu_usage {
-                let val = format!("{:.0}%", data.cpu_usage);
-                draw_stat_line("CPU", val, current_y);
-                current_y += line_height;
+            SelectObject(hdc, old_font);
+
+            // Frametime graph along the bottom, newest frame on the right.
+            // GRAPH_WIDTH slots, each a column of 1px at 96 DPI (wider when scaled).
+            // The histogram, when shown, sits below it
+            let histogram_strip = if data.show_histogram { data.px(GRAPH_HEIGHT) + pad } else { 0 };
+            if data.show_frametime_graph && !top_bar {
+                let bottom = total_height - pad - histogram_strip;
+                let (graph_width, graph_height) = (data.px(GRAPH_WIDTH), data.px(GRAPH_HEIGHT));
+                let (bar_brush, spike_brush) = gdi.graph_brushes(value_color_ref);
+                let first_slot = GRAPH_WIDTH - data.frametimes.len() as i32;
+                let slot_x = |slot: i32| pad + slot * graph_width / GRAPH_WIDTH;
+                for (i, &ms) in data.frametimes.iter().enumerate() {
+                    let spike = ms >= GRAPH_CEILING_MS;
+                    let bar = ((ms.min(GRAPH_CEILING_MS) / GRAPH_CEILING_MS * graph_height as f64) as i32).max(1);
+                    let slot = first_slot + i as i32;
+                    let rect = RECT { left: slot_x(slot), top: bottom - bar, right: slot_x(slot + 1), bottom };
+                    FillRect(hdc, &rect, if spike { spike_brush } else { bar_brush });
+                }
             }
 
-            // GPU
-            if data.show_gpu_usage {
-                let val = format!("{:.0}%", data.gpu_usage);
-                draw_stat_line("GPU", val, current_y);
+            // Frametime histogram, fastest frames on the left. Bars are scaled to the
+            // fullest bucket, so two humps (bimodal stutter) stand out from one wide one.
+            if data.show_histogram && !top_bar {
+                let bottom = total_height - pad;
+                let (graph_width, graph_height) = (data.px(GRAPH_WIDTH), data.px(GRAPH_HEIGHT));
+                let (bar_brush, spike_brush) = gdi.graph_brushes(value_color_ref);
+                let fullest = data.histogram.iter().copied().max().unwrap_or(0).max(1);
+                let bar_x = |bar: usize| pad + bar as i32 * graph_width / HISTOGRAM_BARS as i32;
+                for (i, &count) in data.histogram.iter().enumerate().filter(|(_, &count)| count > 0) {
+                    let bar = ((count as f64 / fullest as f64 * graph_height as f64) as i32).max(1);
+                    // One px gap between bars
+                    let rect = RECT { left: bar_x(i), top: bottom - bar, right: bar_x(i + 1) - 1, bottom };
+                    FillRect(hdc, &rect, if i == HISTOGRAM_BARS - 1 { spike_brush } else { bar_brush });
+                }
             }
             
+            // Benchmark recording: red dot in the top-right padding
+            if data.recording {
+                let dot = data.px(REC_DOT);
+                let (right, top) = (width - data.px(3), data.px(3));
+                let old_brush = SelectObject(hdc, gdi.rec_brush());
+                let old_pen = SelectObject(hdc, GetStockObject(NULL_PEN));
+                let _ = Ellipse(hdc, right - dot, top, right, top + dot);
+                SelectObject(hdc, old_brush);
+                SelectObject(hdc, old_pen);
+            }
+            
+            drop(gdi);
+            
             drop(data);
             
             let _ = EndPaint(hwnd, &ps);
             LRESULT(0)
         }
+        // Unlocked: the whole overlay acts as a title bar, Windows does the dragging
+        WM_NCHITTEST if !POSITION_LOCKED.load(Ordering::SeqCst) => LRESULT(HTCAPTION as isize),
+        WM_ENTERSIZEMOVE => {
+            DRAGGING.store(true, Ordering::SeqCst);
+            LRESULT(0)
+        }
+        WM_EXITSIZEMOVE => {
+            let mut rect = RECT::default();
+            if GetWindowRect(hwnd, &mut rect).is_ok() {
+                *DROPPED_AT.lock() = Some((rect.left, rect.top));
+            }
+            DRAGGING.store(false, Ordering::SeqCst);
+            LRESULT(0)
+        }
+        WM_TIMER if wparam.0 == TOPMOST_TIMER_ID => {
+            if FORCE_TOPMOST.load(Ordering::SeqCst) && IsWindowVisible(hwnd).as_bool() {
+                reassert_topmost(hwnd);
+            }
+            LRESULT(0)
+        }
+        // Quick menu, only when the overlay takes clicks by setting (not while just unlocked to drag)
+        WM_CONTEXTMENU if !CLICKTHROUGH.load(Ordering::SeqCst) => {
+            if let Some(command) = show_context_menu(hwnd) {
+                *MENU_COMMAND.lock() = Some(command);
+            }
+            LRESULT(0)
+        }
         WM_DESTROY => {
             PostQuitMessage(0);
             LRESULT(0)
@@ -313,6 +1912,47 @@ unsafe extern "system" fn overlay_wndproc(
     }
 }
 
+/// Put the overlay back at the top of the z-order if a visible window got above it.
+/// A no-op while it's already on top, so the timer doesn't cause flicker.
+/// True exclusive fullscreen can still cover it (the game owns the screen); borderless can't.
+unsafe fn reassert_topmost(hwnd: HWND) {
+    let mut above = GetWindow(hwnd, GW_HWNDPREV);
+    while above.0 != 0 {
+        if IsWindowVisible(above).as_bool() {
+            let _ = SetWindowPos(hwnd, HWND_TOPMOST, 0, 0, 0, 0, SWP_NOACTIVATE | SWP_NOMOVE | SWP_NOSIZE);
+            return;
+        }
+        above = GetWindow(above, GW_HWNDPREV);
+    }
+}
+
+/// Pop up the overlay's menu at the cursor and return the picked entry's command
+unsafe fn show_context_menu(hwnd: HWND) -> Option<&'static str> {
+    use windows::Win32::UI::WindowsAndMessaging::{
+        AppendMenuW, CreatePopupMenu, DestroyMenu, GetCursorPos, SetForegroundWindow, TrackPopupMenu, MF_STRING,
+        TPM_NONOTIFY, TPM_RETURNCMD, TPM_RIGHTBUTTON,
+    };
+
+    let entries = [
+        (crate::tray::MENU_SETTINGS, Text::MenuSettings),
+        (crate::tray::MENU_TOGGLE_OVERLAY, Text::MenuHideOverlay),
+        (MENU_RESET_POSITION, Text::MenuResetPosition),
+    ];
+    let menu = CreatePopupMenu().ok()?;
+    // Item ids start at 1, TrackPopupMenu returns 0 when nothing was picked
+    for (i, (_, text)) in entries.iter().enumerate() {
+        let label: Vec<u16> = tr(*text).encode_utf16().chain(std::iter::once(0)).collect();
+        let _ = AppendMenuW(menu, MF_STRING, i + 1, PCWSTR(label.as_ptr()));
+    }
+    let mut point = POINT::default();
+    let _ = GetCursorPos(&mut point);
+    // Otherwise the menu doesn't close when clicking elsewhere
+    let _ = SetForegroundWindow(hwnd);
+    let picked = TrackPopupMenu(menu, TPM_RETURNCMD | TPM_RIGHTBUTTON | TPM_NONOTIFY, point.x, point.y, 0, hwnd, None);
+    let _ = DestroyMenu(menu);
+    entries.get((picked.0 as usize).checked_sub(1)?).map(|(command, _)| *command)
+}
+
 fn run_overlay_window() -> Result<(), String> {
     unsafe {
         let class_name = windows::core::w!("EasyFPS_Overlay");
@@ -340,6 +1980,11 @@ fn run_overlay_window() -> Result<(), String> {
         }
         
         OVERLAY_HWND.store(hwnd.0 as isize, Ordering::SeqCst);
+        // Created click-through, which the lock or the setting may have turned off already
+        apply_clickthrough();
+        apply_capture_exclusion();
+        // Checks force_topmost on every tick, so the setting applies without a restart
+        SetTimer(hwnd, TOPMOST_TIMER_ID, TOPMOST_INTERVAL_MS, None);
         
         SetLayeredWindowAttributes(hwnd, None, 230, LWA_ALPHA)
             .map_err(|e| format!("SetLayeredWindowAttributes failed: {}", e))?;
@@ -353,6 +1998,7 @@ fn run_overlay_window() -> Result<(), String> {
                 let _ = TranslateMessage(&msg);
                 DispatchMessageW(&msg);
             }
+            step_fade(hwnd);
             std::thread::sleep(std::time::Duration::from_millis(16));
         }
     }
@@ -368,4 +2014,37 @@ pub fn shutdown() {
         }
         OVERLAY_HWND.store(0, Ordering::SeqCst);
     }
+    unsafe {
+        GDI_CACHE.lock().release();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: (u8, u8, u8) = (255, 60, 60);
+
+    #[test]
+    fn usage_color_bands() {
+        const AMBER: (u8, u8, u8) = (255, 176, 0);
+        assert_eq!(usage_color(79.9, 80, 95), None);
+        assert_eq!(usage_color(80.0, 80, 95), Some(AMBER));
+        assert_eq!(usage_color(94.9, 80, 95), Some(AMBER));
+        assert_eq!(usage_color(95.0, 80, 95), Some(RED));
+        assert_eq!(usage_color(100.0, 80, 95), Some(RED));
+        // No amber band when both thresholds match
+        assert_eq!(usage_color(90.0, 90, 90), Some(RED));
+    }
+
+    #[test]
+    fn color_for_fps_boundaries() {
+        const YELLOW: (u8, u8, u8) = (255, 220, 0);
+        const GREEN: (u8, u8, u8) = (57, 255, 20);
+        assert_eq!(color_for_fps(29.9, 30, 60), RED);
+        // Exactly at `low` is no longer red, exactly at `high` is green
+        assert_eq!(color_for_fps(30.0, 30, 60), YELLOW);
+        assert_eq!(color_for_fps(59.9, 30, 60), YELLOW);
+        assert_eq!(color_for_fps(60.0, 30, 60), GREEN);
+    }
 }
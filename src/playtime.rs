@@ -0,0 +1,91 @@
+use crate::settings::Settings;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// How often accumulated playtime is written to disk
+const SAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Accumulates playtime per game (keyed by process name) across launches
+pub struct PlaytimeTracker {
+    /// Total seconds per process name
+    totals: HashMap<String, u64>,
+    /// Game currently being timed and the instant up to which it was counted
+    current: Option<(String, Instant)>,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl PlaytimeTracker {
+    fn file_path() -> PathBuf {
+        Settings::config_dir().join("playtime.json")
+    }
+
+    /// Load the playtime table from disk, or start empty
+    pub fn load() -> Self {
+        let totals = fs::read_to_string(Self::file_path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        Self {
+            totals,
+            current: None,
+            last_save: Instant::now(),
+            dirty: false,
+        }
+    }
+
+    /// Write the playtime table to disk
+    pub fn save(&mut self) {
+        let path = Self::file_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string_pretty(&self.totals) {
+            let _ = fs::write(&path, content);
+        }
+        self.dirty = false;
+        self.last_save = Instant::now();
+    }
+
+    /// Advance the timer. `game` is the process currently running fullscreen (None = no game).
+    /// Uses monotonic time, so clock or timezone changes don't affect the totals.
+    pub fn tick(&mut self, game: Option<&str>) {
+        let now = Instant::now();
+
+        match (&mut self.current, game) {
+            (Some((name, since)), Some(game)) if name.as_str() == game => {
+                // Only whole seconds are added; the remainder carries over to the next tick
+                let secs = now.duration_since(*since).as_secs();
+                if secs > 0 {
+                    *self.totals.entry(game.to_string()).or_insert(0) += secs;
+                    *since += Duration::from_secs(secs);
+                    self.dirty = true;
+                }
+            }
+            _ => {
+                self.current = game.map(|g| (g.to_string(), now));
+            }
+        }
+
+        if self.dirty && self.last_save.elapsed() >= SAVE_INTERVAL {
+            self.save();
+        }
+    }
+
+    /// Total recorded seconds for a game
+    pub fn total_secs(&self, game: &str) -> u64 {
+        self.totals.get(game).copied().unwrap_or(0)
+    }
+}
+
+/// Format a playtime as "12h" or "45m" for the overlay
+pub fn format_playtime(secs: u64) -> String {
+    if secs >= 3600 {
+        format!("{}h", secs / 3600)
+    } else {
+        format!("{}m", secs / 60)
+    }
+}
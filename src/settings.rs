@@ -1,12 +1,23 @@
+use crate::i18n::Lang;
+use crate::log::LogLevel;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
 
 /// Overlay position on screen
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum OverlayPosition {
     TopRight,
     TopLeft,
+    BottomRight,
+    BottomLeft,
+    /// Dragged by the user: top-left corner in virtual screen coordinates
+    Custom { x: i32, y: i32 },
+    /// Share of the free space left of and above the overlay (0-100 each), so 0/0 is the
+    /// top-left corner and 100/100 the bottom-right one. Scales with each monitor's size.
+    Percent { x_pct: f32, y_pct: f32 },
 }
 
 impl Default for OverlayPosition {
@@ -15,11 +26,52 @@ impl Default for OverlayPosition {
     }
 }
 
+impl OverlayPosition {
+    /// Next corner clockwise, used by the position hotkey. A dragged or percentage position
+    /// goes back to the top right.
+    pub fn next(self) -> Self {
+        match self {
+            Self::TopRight => Self::BottomRight,
+            Self::BottomRight => Self::BottomLeft,
+            Self::BottomLeft => Self::TopLeft,
+            Self::TopLeft | Self::Custom { .. } | Self::Percent { .. } => Self::TopRight,
+        }
+    }
+
+    /// A corner as a `Percent` position POSITION_INSET_PCT in from its edges;
+    /// other positions are returned unchanged
+    pub fn proportional(self) -> Self {
+        let (near, far) = (POSITION_INSET_PCT, 100.0 - POSITION_INSET_PCT);
+        match self {
+            Self::TopRight => Self::Percent { x_pct: far, y_pct: near },
+            Self::TopLeft => Self::Percent { x_pct: near, y_pct: near },
+            Self::BottomRight => Self::Percent { x_pct: far, y_pct: far },
+            Self::BottomLeft => Self::Percent { x_pct: near, y_pct: far },
+            other => other,
+        }
+    }
+
+    /// The corner a position is closest to (None for a dragged position)
+    pub fn nearest_corner(self) -> Option<Self> {
+        match self {
+            Self::Custom { .. } => None,
+            Self::Percent { x_pct, y_pct } => Some(match (x_pct >= 50.0, y_pct >= 50.0) {
+                (true, false) => Self::TopRight,
+                (false, false) => Self::TopLeft,
+                (true, true) => Self::BottomRight,
+                (false, true) => Self::BottomLeft,
+            }),
+            corner => Some(corner),
+        }
+    }
+}
+
 /// FPS text color
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum FpsColor {
     White,
     Green, // Bright green #39FF14
+    Custom { r: u8, g: u8, b: u8 },
 }
 
 impl Default for FpsColor {
@@ -34,16 +86,30 @@ impl FpsColor {
         match self {
             FpsColor::White => (255, 255, 255),
             FpsColor::Green => (57, 255, 20), // #39FF14
+            FpsColor::Custom { r, g, b } => (*r, *g, *b),
         }
     }
 }
 
+/// How the FPS number is colored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FpsColorMode {
+    /// Always `fps_color`
+    #[default]
+    Static,
+    /// Red below `low`, yellow in between, green at or above `high`
+    Threshold { low: u32, high: u32 },
+}
+
 /// Overlay size
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum OverlaySize {
     Small,
     Medium,
     Large,
+    /// Set by hand in settings.json, e.g. bigger than Large for a TV across the room
+    /// (sizes at 96 DPI, kept within the CUSTOM_SIZE_* bounds)
+    Custom { width: i32, height: i32, font_large: i32, font_small: i32 },
 }
 
 impl Default for OverlaySize {
@@ -55,25 +121,514 @@ impl Default for OverlaySize {
 impl OverlaySize {
     /// Get dimensions (width, height, font_large, font_small)
     pub fn dimensions(&self) -> (i32, i32, i32, i32) {
-        match self {
+        match *self {
             OverlaySize::Small => (75, 42, 20, 10),
             OverlaySize::Medium => (95, 52, 26, 12),
             OverlaySize::Large => (120, 65, 32, 14),
+            OverlaySize::Custom { width, height, font_large, font_small } => (width, height, font_large, font_small),
+        }
+    }
+}
+
+/// Which fullscreen window to monitor when several are fullscreen (multi-monitor)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FullscreenPolicy {
+    /// Only the foreground window (original behavior)
+    #[default]
+    Foreground,
+    /// The largest fullscreen window
+    Largest,
+    /// The fullscreen window on the primary monitor
+    PrimaryMonitor,
+}
+
+/// Which GPU the "GPU" line reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GpuSelection {
+    /// The busiest engine across every adapter
+    #[default]
+    MaxAll,
+    /// One adapter. LUIDs are reassigned at boot, so the name is used to find it again.
+    Adapter { luid: String, name: String },
+}
+
+/// How the per-engine GPU readings are combined into one number
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GpuAggregation {
+    /// The busiest single engine (original behavior)
+    #[default]
+    Max,
+    /// The busiest engine of each type (3D, Copy, Compute...) added up, at most 100%
+    SumClamped,
+}
+
+/// How the 1% and 0.1% lows are computed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum LowMethod {
+    /// FPS of the frame at the 99th (99.9th) percentile of frame count
+    #[default]
+    FrameCount,
+    /// Average FPS over the slowest frames making up 1% (0.1%) of the capture time (CapFrameX)
+    TimeWeighted,
+}
+
+/// What the displayed FPS number means
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum FpsMethod {
+    /// 1000 / average frametime over the averaging window
+    #[default]
+    FrameTimeAverage,
+    /// Frames presented in the last second of wall-clock time, like most in-game counters
+    FramesPerSecondCount,
+}
+
+/// How the displayed FPS is smoothed (the 1% lows always use the raw frametimes)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum Smoothing {
+    /// Plain average over the averaging window
+    #[default]
+    None,
+    /// Exponential moving average, alpha 0.05 (slow and steady) to 0.5 (reacts quickly)
+    Ema { alpha: f32 },
+}
+
+/// When the overlay is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OverlayMode {
+    /// Only over fullscreen (and borderless fullscreen) games
+    #[default]
+    FullscreenOnly,
+    /// Over whatever window is in the foreground, windowed games included
+    Always,
+}
+
+/// A process picked by hand to monitor, see `Settings::target_override`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TargetSpec {
+    Pid(u32),
+    /// Executable name, e.g. "game.exe" (case-insensitive)
+    Name(String),
+}
+
+/// How numbers are written in the overlay and the summary panel
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum NumberLocale {
+    /// 1234.5 (dot decimal, no grouping)
+    #[default]
+    Invariant,
+    /// 1,234.5
+    English,
+    /// 1.234,5
+    European,
+    /// 1'234.5
+    Swiss,
+}
+
+impl NumberLocale {
+    /// Decimal separator and optional thousands separator
+    fn separators(self) -> (char, Option<char>) {
+        match self {
+            NumberLocale::Invariant => ('.', None),
+            NumberLocale::English => ('.', Some(',')),
+            NumberLocale::European => (',', Some('.')),
+            NumberLocale::Swiss => ('.', Some('\'')),
+        }
+    }
+
+    /// Format `value` with `decimals` digits after the separator
+    pub fn format(self, value: f64, decimals: usize) -> String {
+        let (decimal_sep, thousands_sep) = self.separators();
+        let plain = format!("{:.*}", decimals, value.abs());
+        let (int_part, frac_part) = match plain.split_once('.') {
+            Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+            None => (plain.as_str(), None),
+        };
+
+        let mut out = String::with_capacity(plain.len() + int_part.len() / 3 + 1);
+        if value < 0.0 && plain.chars().any(|c| c != '0' && c != '.') {
+            out.push('-');
+        }
+        for (i, digit) in int_part.chars().enumerate() {
+            if let Some(sep) = thousands_sep {
+                if i > 0 && (int_part.len() - i) % 3 == 0 {
+                    out.push(sep);
+                }
+            }
+            out.push(digit);
+        }
+        if let Some(frac_part) = frac_part {
+            out.push(decimal_sep);
+            out.push_str(frac_part);
+        }
+        out
+    }
+
+    /// Format `value` tersely for `unit`: "1.2K" for 1200 frames, "7.2G" for 7.2 GiB.
+    /// Values below one step (1000, or 1024 bytes) are shown in full, percentages always are.
+    pub fn format_compact(self, value: f64, unit: Unit) -> String {
+        let (base, suffixes): (f64, &[&str]) = match unit {
+            Unit::Percent => return format!("{}%", self.format(value, 0)),
+            Unit::Bytes => (1024.0, &["B", "K", "M", "G", "T"]),
+            Unit::Count => (1000.0, &["", "K", "M", "G"]),
+        };
+        let mut scaled = value;
+        let mut step = 0;
+        // Rounded, so 999.7 becomes "1K" and not "1000"
+        while scaled.abs().round() >= base && step < suffixes.len() - 1 {
+            scaled /= base;
+            step += 1;
+        }
+        if step == 0 {
+            return format!("{}{}", self.format(value, 0), suffixes[0]);
+        }
+        // One decimal below 10 ("7.2G"), none above it ("512M") or when it's zero ("1K")
+        let decimals = usize::from(scaled.abs() < 10.0 && (scaled * 10.0).round() % 10.0 != 0.0);
+        format!("{}{}", self.format(scaled, decimals), suffixes[step])
+    }
+}
+
+/// What a value given to `NumberLocale::format_compact` measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Bytes, in 1024 steps: "512B", "1K", "7.2G"
+    Bytes,
+    /// A percentage, never shortened: "45%"
+    Percent,
+    /// A plain count such as an FPS, in 1000 steps: "999", "1K", "1.2K"
+    Count,
+}
+
+/// Arrangement of the overlay lines
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum OverlayLayout {
+    /// One stat per line, as selected with the show_* options
+    #[default]
+    Vertical,
+    /// Fixed block for stream thumbnails: game name header, FPS, 1% low, CPU, GPU
+    Stream,
+    /// The Vertical lines side by side in one row: "FPS  142  |  CPU  43%  |  GPU  88%"
+    Horizontal,
+    /// Thin bar across the top of the game's monitor, the stats spread evenly over it
+    /// (`position` is ignored, no graph or histogram)
+    TopBar,
+}
+
+/// How the RAM line is shown in the overlay
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum RamDisplay {
+    /// "RAM 45%"
+    #[default]
+    Percent,
+    /// "RAM 7350/16384 MB" (used/total)
+    Megabytes,
+}
+
+/// What sits behind the overlay text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum BackgroundStyle {
+    /// Dark rounded box
+    #[default]
+    SolidBox,
+    /// No box, the text gets a 1px shadow so it stays readable over the game
+    TextOnly,
+}
+
+/// What a single left click on the tray icon does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum TrayClickAction {
+    /// Nothing besides the menu Windows shows on left click
+    #[default]
+    None,
+    ToggleOverlay,
+    OpenSettings,
+}
+
+/// Color scheme of the settings and benchmark windows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum GuiTheme {
+    #[default]
+    Dark,
+    Light,
+}
+
+/// Where frame data comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+pub enum CaptureBackendKind {
+    /// PresentMon (ETW): per-process frametimes and every frame stat
+    #[default]
+    PresentMon,
+    /// DWM composition counter: only an FPS, for systems where ETW is blocked
+    DwmPolling,
+}
+
+/// A stat (or group of lines) on the overlay, placed by `stat_order`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StatKind {
+    Fps,
+    OnePercentLow,
+    PointOneLow,
+    Frametime,
+    Jitter,
+    StutterCount,
+    MinMaxAvg,
+    FpsSpread,
+    Cpu,
+    PerCoreCpu,
+    Gpu,
+    GpuTemp,
+    Ram,
+    Network,
+    Disk,
+    HardwareNames,
+    Battery,
+    Playtime,
+    Resolution,
+    Clock,
+    CapStability,
+    PresentVsDisplay,
+    GpuBusy,
+}
+
+impl StatKind {
+    /// Every stat, in the default order
+    pub const ALL: [StatKind; 23] = [
+        StatKind::Fps,
+        StatKind::OnePercentLow,
+        StatKind::PointOneLow,
+        StatKind::Frametime,
+        StatKind::Jitter,
+        StatKind::StutterCount,
+        StatKind::MinMaxAvg,
+        StatKind::FpsSpread,
+        StatKind::Cpu,
+        StatKind::PerCoreCpu,
+        StatKind::Gpu,
+        StatKind::GpuTemp,
+        StatKind::Ram,
+        StatKind::Network,
+        StatKind::Disk,
+        StatKind::HardwareNames,
+        StatKind::Battery,
+        StatKind::Playtime,
+        StatKind::Resolution,
+        StatKind::Clock,
+        StatKind::CapStability,
+        StatKind::PresentVsDisplay,
+        StatKind::GpuBusy,
+    ];
+}
+
+/// `order` with repeats dropped and any missing stat appended in the default order,
+/// so every stat has exactly one place
+fn normalize_stat_order(order: &[StatKind]) -> Vec<StatKind> {
+    let mut normalized: Vec<StatKind> = Vec::with_capacity(StatKind::ALL.len());
+    for &kind in order.iter().chain(StatKind::ALL.iter()) {
+        if !normalized.contains(&kind) {
+            normalized.push(kind);
+        }
+    }
+    normalized
+}
+
+/// Column of the per-second stats CSV log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CsvColumn {
+    Timestamp,
+    Fps,
+    OnePercentLow,
+    Cpu,
+    Gpu,
+    Game,
+    Ram,
+}
+
+impl CsvColumn {
+    /// Header text for this column
+    pub fn header(&self) -> &'static str {
+        match self {
+            CsvColumn::Timestamp => "timestamp",
+            CsvColumn::Fps => "fps",
+            CsvColumn::OnePercentLow => "one_percent_low",
+            CsvColumn::Cpu => "cpu_percent",
+            CsvColumn::Gpu => "gpu_percent",
+            CsvColumn::Game => "game",
+            CsvColumn::Ram => "ram_percent",
         }
     }
 }
 
+/// A global hotkey: MOD_* modifier flags plus a virtual-key code
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Hotkey {
+    pub modifiers: u32,
+    pub vk: u32,
+}
+
+// Win32 MOD_* flags, kept here so settings don't depend on the windows crate
+pub const MOD_ALT: u32 = 0x1;
+pub const MOD_CONTROL: u32 = 0x2;
+pub const MOD_SHIFT: u32 = 0x4;
+pub const MOD_WIN: u32 = 0x8;
+
+impl std::fmt::Display for Hotkey {
+    /// Readable key combination, e.g. "Ctrl+Alt+S"
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (flag, name) in [(MOD_CONTROL, "Ctrl"), (MOD_ALT, "Alt"), (MOD_SHIFT, "Shift"), (MOD_WIN, "Win")] {
+            if self.modifiers & flag != 0 {
+                write!(f, "{}+", name)?;
+            }
+        }
+        match self.vk {
+            // Digits and letters share their ASCII codes
+            0x30..=0x39 | 0x41..=0x5A => write!(f, "{}", self.vk as u8 as char),
+            0x70..=0x87 => write!(f, "F{}", self.vk - 0x6F),
+            vk => write!(f, "0x{:02X}", vk),
+        }
+    }
+}
+
+/// Opacity presets stepped through by the opacity hotkeys
+pub const OPACITY_PRESETS: [u8; 4] = [40, 60, 80, 100];
+
+/// How often settings.json's modification time is checked
+const RELOAD_POLL: Duration = Duration::from_millis(500);
+/// How long settings.json must stay unchanged before it is reloaded,
+/// so an editor writing the file in several steps triggers a single reload
+const RELOAD_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// Notices edits made to settings.json outside the app (by hand or by a script)
+pub struct SettingsWatcher {
+    seen: Option<SystemTime>,
+    changed_at: Option<Instant>,
+    checked_at: Instant,
+}
+
+impl Default for SettingsWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SettingsWatcher {
+    pub fn new() -> Self {
+        Self {
+            seen: Settings::modified(),
+            changed_at: None,
+            checked_at: Instant::now(),
+        }
+    }
+
+    /// The settings on disk once the file has changed and settled, `Err` if they don't parse
+    pub fn poll(&mut self) -> Option<Result<Settings, String>> {
+        if self.checked_at.elapsed() < RELOAD_POLL {
+            return None;
+        }
+        self.checked_at = Instant::now();
+        let modified = Settings::modified();
+        if modified != self.seen {
+            self.seen = modified;
+            self.changed_at = Some(Instant::now());
+            return None;
+        }
+        match self.changed_at {
+            Some(at) if at.elapsed() >= RELOAD_DEBOUNCE => {
+                self.changed_at = None;
+                Some(Settings::try_load())
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Process names compared case-insensitively, with or without ".exe"
+fn same_process(a: &str, b: &str) -> bool {
+    let strip = |name: &str| {
+        let name = name.trim();
+        match name.len().checked_sub(4) {
+            Some(end) if name.is_char_boundary(end) && name[end..].eq_ignore_ascii_case(".exe") => name[..end].to_string(),
+            _ => name.to_string(),
+        }
+    };
+    strip(a).eq_ignore_ascii_case(&strip(b))
+}
+
+/// Next opacity preset above (`up`) or below `current`, clamped to 40-100
+pub fn step_opacity(current: u8, up: bool) -> u8 {
+    let next = if up {
+        OPACITY_PRESETS.iter().copied().find(|&p| p > current).unwrap_or(100)
+    } else {
+        OPACITY_PRESETS.iter().rev().copied().find(|&p| p < current).unwrap_or(40)
+    };
+    next.clamp(40, 100)
+}
+
 /// Application settings
+// Missing fields fall back to their defaults so older settings files keep loading
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct Settings {
-    /// Overlay position (top-right or top-left)
+    /// Schema version the file was written with (missing = 0, files from before versioning)
+    #[serde(default)]
+    pub version: u32,
+
+    /// Overlay position (screen corner)
     pub position: OverlayPosition,
     
     /// FPS text color
     pub fps_color: FpsColor,
+
+    /// Color of the other stat values (None = same as `fps_color`, as before this option)
+    pub stats_color: Option<FpsColor>,
+
+    /// Outline the overlay box, so it stands out over bright scenes
+    pub show_border: bool,
+
+    /// Color of that outline
+    pub border_color: FpsColor,
+
+    /// Box behind the text, or shadowed text only
+    pub background_style: BackgroundStyle,
+
+    /// Turn the box light over dark scenes, sampling the screen around the overlay once a second
+    pub auto_contrast: bool,
+
+    /// Longest gap between the two clicks of a double click on the tray icon (opens settings)
+    pub tray_double_click_ms: u64,
+
+    /// What a single left click on the tray icon does. With an action the left click no longer
+    /// opens the menu (right click still does), and the action waits out tray_double_click_ms.
+    pub tray_single_click_action: TrayClickAction,
     
+    /// Static FPS color or threshold-based coloring (FPS line only)
+    pub fps_color_mode: FpsColorMode,
+
+    /// CPU/GPU usage (%) from which the value turns amber
+    pub usage_warn_pct: u32,
+
+    /// CPU/GPU usage (%) from which the value turns red
+    pub usage_crit_pct: u32,
+
     /// Overlay size
     pub size: OverlaySize,
+
+    /// Scale the size, fonts and margin with the game monitor's resolution (height
+    /// relative to 1080p, AUTO_SCALE_MIN-AUTO_SCALE_MAX), on top of the DPI scaling
+    pub auto_scale: bool,
+
+    /// Overlay font (any installed font, the settings window offers FONT_CHOICES)
+    pub font_family: String,
+
+    /// Use MONOSPACE_FONT instead of font_family, so changing numbers keep their width
+    pub monospace_digits: bool,
+
+    /// Overlay font weight, 100 (thin) to FONT_WEIGHT_MAX (black); 400 is regular, 700 bold
+    pub font_weight: u32,
+
+    /// Draw the overlay text in italics
+    pub font_italic: bool,
+
+    /// Line arrangement (ignored while overlay_template is set)
+    pub layout: OverlayLayout,
     
     /// Start with Windows
     pub start_with_windows: bool,
@@ -81,57 +636,697 @@ pub struct Settings {
     /// Show 1% low FPS
     pub show_1_percent_low: bool,
 
+    /// Show 0.1% low FPS
+    pub show_point_one_low: bool,
+
+    /// Show the average frametime ("ms 16.6")
+    pub show_frametime_ms: bool,
+
+    /// Show frame pacing jitter ("JIT 2.1", ms)
+    pub show_jitter: bool,
+
+    /// Show how many frames in the averaging window were stutters ("STU 3")
+    pub show_stutter_count: bool,
+
+    /// Show the session min, max and average FPS (reset from the tray)
+    pub show_min_max_avg: bool,
+
+    /// Show the low, average and high FPS of the averaging window on one line ("L98 A142 H210")
+    pub show_fps_spread: bool,
+
+    /// Show the wall clock
+    pub show_clock: bool,
+
+    /// Show the game's resolution and the monitor refresh rate ("2560x1440 @144")
+    pub show_resolution: bool,
+
+    /// 24-hour clock ("21:05") instead of 12-hour ("9:05 PM")
+    pub clock_24h: bool,
+
     /// Show CPU Usage
     pub show_cpu_usage: bool,
 
+    /// Show a grid with the load of each logical core below the CPU line
+    pub show_per_core_cpu: bool,
+
     /// Show GPU Usage
     pub show_gpu_usage: bool,
 
+    /// With multiple GPUs, show one line per adapter instead of the busiest one
+    pub show_all_gpus: bool,
+
+    /// GPU reported on the "GPU" line when there are several
+    pub gpu_selection: GpuSelection,
+
+    /// Busiest engine, or engine types added up (reads higher when work is split across engines)
+    pub gpu_aggregation: GpuAggregation,
+
+    /// Show system RAM usage
+    pub show_ram_usage: bool,
+
+    /// Show the GPU temperature (NVIDIA only, "N/A" elsewhere)
+    pub show_gpu_temp: bool,
+
+    /// Show the battery level ("AC" when plugged in), hidden on machines without a battery
+    pub show_battery: bool,
+
+    /// Show the network download/upload rate
+    pub show_network: bool,
+
+    /// Show how busy the disks are (% active time), to spot asset-streaming stutters
+    pub show_disk_usage: bool,
+
+    /// Show the CPU and GPU model names, to confirm which hardware is in use
+    pub show_hardware_names: bool,
+
+    /// Show the game's executable name (without ".exe") as a top line, e.g. for recordings
+    pub show_process_name: bool,
+
+    /// Show RAM as a percentage or in MB
+    pub ram_display: RamDisplay,
+
+    /// Draw a scrolling frametime graph below the stats
+    pub show_frametime_graph: bool,
+
+    /// Draw a small frametime histogram below the stats (how frametimes are distributed)
+    pub show_histogram: bool,
+
+    /// Keep the overlay inside the monitor's work area so the taskbar can't cover it
+    pub avoid_taskbar: bool,
+
+    /// Distance of the corner positions from the screen edge (px at 100% scaling, 0-200),
+    /// raised on TVs whose overscan hides the edges
+    pub overlay_margin: i32,
+
+    /// Mouse clicks go through the overlay to the game (off: the overlay takes them)
+    pub overlay_clickthrough: bool,
+
+    /// Keep the overlay out of screen captures (OBS, Game Bar) while it stays visible on screen.
+    /// Needs Windows 10 2004 or later, older versions keep capturing it
+    pub exclude_from_capture: bool,
+
+    /// Fade the overlay in and out instead of showing/hiding it at once
+    pub animations: bool,
+
+    /// How often the overlay is redrawn (Hz, OVERLAY_UPDATE_HZ_MIN..=OVERLAY_UPDATE_HZ_MAX),
+    /// lower values save CPU on slow systems
+    pub overlay_update_hz: u32,
+
     /// Overlay Opacity (40-100)
     pub overlay_opacity: u8,
+
+    /// Draw the FPS line below the secondary stats instead of on top
+    pub fps_on_bottom: bool,
+
+    /// Top-to-bottom order of the overlay stats; each is still shown only when enabled.
+    /// Every StatKind appears exactly once (repeats dropped, missing ones appended on load)
+    pub stat_order: Vec<StatKind>,
+
+    /// Accumulate playtime per game (stored in playtime.json)
+    pub track_playtime: bool,
+
+    /// Show the accumulated playtime of the current game (requires track_playtime)
+    pub show_playtime: bool,
+
+    /// Keep the overlay hidden unless an alert condition is met
+    pub alert_only_mode: bool,
+
+    /// Alert-only mode: show the overlay when FPS drops below this value (0 = disabled).
+    /// Not to be confused with `alert_below_fps`, the border flash on a sustained drop.
+    pub alert_only_fps_below: u32,
+
+    /// Alert-only mode: show the overlay when CPU or GPU usage reaches this percentage (0 = disabled)
+    pub alert_only_usage_above: u8,
+
+    /// Alert-only mode: show the overlay when the averaging window holds this many stutters
+    /// (frames over twice the median frametime); it clears once the window has none left (0 = disabled)
+    pub alert_only_stutters: u32,
+
+    /// How long the overlay stays visible after the alert clears (ms)
+    pub alert_linger_ms: u32,
+
+    /// Flash the overlay border when the FPS stays below this value for a few seconds (None = off).
+    /// Works with or without alert-only mode, whose threshold is `alert_only_fps_below`.
+    pub alert_below_fps: Option<u32>,
+
+    /// Minimum time between two low-FPS alerts (seconds)
+    pub alert_repeat_secs: u32,
+
+    /// Also play the system warning sound on a low-FPS alert
+    pub alert_beep: bool,
+
+    /// Hide the overlay while the FPS is above this value, e.g. in menus (0 = disabled)
+    pub hide_above_fps: u32,
+
+    /// Show the overlay only while the FPS is below this value (0 = disabled)
+    pub show_below_fps: u32,
+
+    /// Write a CSV row per second while a game is running
+    pub log_stats_csv: bool,
+
+    /// Columns included in the stats CSV, in order
+    pub csv_columns: Vec<CsvColumn>,
+
+    /// Hotkey that shows/hides the recent frames summary panel
+    pub summary_hotkey: Hotkey,
+
+    /// Hotkey that opens the settings window (the only way in with --no-tray)
+    pub settings_hotkey: Hotkey,
+
+    /// Hotkey that closes EasyFPS
+    pub exit_hotkey: Hotkey,
+
+    /// Hotkey that hides/shows the overlay (e.g. for screenshots)
+    pub toggle_hotkey: Hotkey,
+
+    /// Hotkey that moves the overlay to the next screen corner
+    pub position_hotkey: Hotkey,
+
+    /// Hotkey that starts a benchmark run, or stops it and shows the results
+    pub benchmark_hotkey: Hotkey,
+
+    /// Show the detected frame cap and whether it's hit consistently (experimental)
+    pub show_cap_stability: bool,
+
+    /// Tag the FPS value when a frame cap or VSync holds it ("60 VSYNC", "141 CAP")
+    pub show_cap_indicator: bool,
+
+    /// Follow the FPS value with the instant FPS of the last few frames, "142 (138)"
+    pub show_instant_fps: bool,
+
+    /// Tint the FPS value while it stays well under the monitor's refresh rate (tearing-prone)
+    pub show_refresh_warning: bool,
+
+    /// FPS readings below this are shown as "--" (0 = show everything, max 10)
+    pub min_display_fps: u32,
+
+    /// Which window to monitor when multiple fullscreen windows exist
+    pub multi_fullscreen_policy: FullscreenPolicy,
+
+    /// Also treat borderless (WS_POPUP) windows covering ~90% of the monitor as fullscreen
+    pub detect_borderless: bool,
+
+    /// Show the overlay only over fullscreen games, or over any foreground window
+    pub overlay_mode: OverlayMode,
+
+    /// Keep the last values up (dimmed) this long after the game leaves the foreground,
+    /// e.g. a quick alt-tab to a chat app (ms, 0 = hide at once)
+    pub hide_delay_ms: u32,
+
+    /// Monitor this process instead of detecting the fullscreen game. The overlay shows
+    /// over it whether or not it's fullscreen; None = automatic detection.
+    pub target_override: Option<TargetSpec>,
+
+    /// Processes that never get the overlay (e.g. "vlc.exe"), case-insensitive, ".exe" optional
+    pub process_blacklist: Vec<String>,
+
+    /// When not empty, only these processes get the overlay
+    pub process_whitelist: Vec<String>,
+
+    /// Frame-count percentile or time-weighted definition of the 1%/0.1% lows
+    pub low_method: LowMethod,
+
+    /// Frametime average over avg_window_ms, or a count of the frames in the last second
+    pub fps_method: FpsMethod,
+
+    /// Smoothing of the displayed FPS (EMA alpha per 1/60 s, whatever the overlay refresh rate)
+    pub smoothing: Smoothing,
+
+    /// Smoothing of the CPU/GPU/RAM/disk load readings, separate from the FPS one.
+    /// The default takes the edge off second-to-second swings without visible lag.
+    pub stats_smoothing: Smoothing,
+
+    /// Version 1 smoothing options, only read to migrate them to `smoothing`
+    #[serde(skip_serializing)]
+    fps_smoothing: bool,
+    #[serde(skip_serializing)]
+    ema_alpha: Option<f32>,
+
+    /// Version 2 names of the alert-only thresholds, only read to migrate them
+    #[serde(skip_serializing)]
+    alert_fps_below: Option<u32>,
+    #[serde(skip_serializing)]
+    alert_usage_above: Option<u8>,
+    #[serde(skip_serializing)]
+    alert_stutters: Option<u32>,
+
+    /// Show presented vs displayed FPS ("P 144 / D 120") to reveal dropped frames
+    pub show_present_vs_display: bool,
+
+    /// Show how long the GPU works on each frame ("GPU-bound" near 100%), when PresentMon reports it
+    pub show_gpu_busy: bool,
+
+    /// Time span the displayed FPS and 1% low are averaged over (ms, 0 = whole sample buffer).
+    /// The lows always use at least the last 200 frames.
+    pub avg_window_ms: u32,
+
+    /// Serve the live stats as JSON on the \\.\pipe\EasyFPS named pipe
+    pub enable_ipc: bool,
+
+    /// Frametimes kept for the live stats (clamped to SAMPLE_BUFFER_MIN..=SAMPLE_BUFFER_MAX)
+    pub sample_buffer_size: usize,
+
+    /// Custom overlay text, e.g. "{fps} fps | {low} low | {cpu}% cpu" (empty = default layout).
+    /// Tokens: {fps} {low} {cpu} {gpu} {game} {time} {res}, "\n" starts a new line.
+    pub overlay_template: String,
+
+    /// Decimal/thousands separators for displayed numbers (the CSV log always uses Invariant)
+    pub number_locale: NumberLocale,
+
+    /// Shorten large values: "1.2K" FPS, "7.2G/16G" RAM, "1.2M" network (FPS under 1000 is unchanged)
+    pub compact_numbers: bool,
+
+    /// Digits after the decimal separator of FPS values (0 or 1), e.g. for A/B testing tweaks
+    pub fps_decimals: u8,
+
+    /// Digits after the decimal separator of the CPU/GPU load (0 or 1)
+    pub usage_decimals: u8,
+
+    /// Restart PresentMon once if no frames arrive this long after a game is detected (ms, 0 = disabled)
+    pub capture_watchdog_ms: u32,
+
+    /// Copy PresentMon's raw CSV output to presentmon_raw.csv (for support/debugging)
+    pub save_raw_presentmon: bool,
+
+    /// Show "PAUSED" when the game presents no frames for this long (ms, 0 = disabled)
+    pub detect_stall_ms: u32,
+
+    /// Stop PresentMon after this long without a game, restarting it when one appears (s, 0 = never)
+    pub idle_capture_timeout_secs: u32,
+
+    /// PresentMon.exe to use instead of the bundled one (None = automatic search)
+    pub presentmon_path: Option<PathBuf>,
+
+    /// Keep pushing the overlay back on top, for exclusive-fullscreen games that cover it.
+    /// Some exclusive-fullscreen (DX12) games still hide it; borderless fullscreen always works.
+    pub force_topmost: bool,
+
+    /// Most verbose messages written to debug.log (Info for bug reports)
+    pub log_level: LogLevel,
+
+    /// Frame data source, DwmPolling when PresentMon/ETW can't run (FPS only)
+    pub capture_backend: CaptureBackendKind,
+
+    /// UI language (defaults to the Windows display language)
+    pub language: Lang,
+
+    /// Colors of the settings and benchmark windows
+    pub gui_theme: GuiTheme,
 }
 
 impl Default for Settings {
     fn default() -> Self {
         Self {
+            version: SETTINGS_VERSION,
             position: OverlayPosition::TopRight,
             fps_color: FpsColor::White,
+            stats_color: None,
+            show_border: false,
+            border_color: FpsColor::Custom { r: 128, g: 128, b: 128 },
+            background_style: BackgroundStyle::SolidBox,
+            auto_contrast: false,
+            tray_double_click_ms: 500,
+            tray_single_click_action: TrayClickAction::None,
+            fps_color_mode: FpsColorMode::Static,
+            usage_warn_pct: 85,
+            usage_crit_pct: 95,
             size: OverlaySize::Medium,
+            auto_scale: false,
+            font_family: DEFAULT_FONT.to_string(),
+            monospace_digits: false,
+            font_weight: 700,
+            font_italic: false,
+            layout: OverlayLayout::Vertical,
             start_with_windows: false,
             show_1_percent_low: true,
+            show_point_one_low: false,
+            show_frametime_ms: false,
+            show_jitter: false,
+            show_stutter_count: false,
+            show_min_max_avg: false,
+            show_fps_spread: false,
+            show_clock: false,
+            show_resolution: false,
+            clock_24h: true,
             show_cpu_usage: false,
+            show_per_core_cpu: false,
             show_gpu_usage: false,
+            show_all_gpus: false,
+            gpu_selection: GpuSelection::MaxAll,
+            gpu_aggregation: GpuAggregation::Max,
+            show_ram_usage: false,
+            show_gpu_temp: false,
+            show_battery: false,
+            show_network: false,
+            show_disk_usage: false,
+            show_hardware_names: false,
+            show_process_name: false,
+            ram_display: RamDisplay::Percent,
+            show_frametime_graph: false,
+            show_histogram: false,
+            avoid_taskbar: true,
+            overlay_margin: 10,
+            overlay_clickthrough: true,
+            exclude_from_capture: false,
+            animations: true,
+            overlay_update_hz: 60,
             overlay_opacity: 90,
+            fps_on_bottom: false,
+            stat_order: StatKind::ALL.to_vec(),
+            track_playtime: false,
+            show_playtime: true,
+            alert_only_mode: false,
+            alert_only_fps_below: 60,
+            alert_only_usage_above: 95,
+            alert_only_stutters: 3,
+            alert_linger_ms: 3000,
+            alert_below_fps: None,
+            alert_repeat_secs: 60,
+            alert_beep: false,
+            hide_above_fps: 0,
+            show_below_fps: 0,
+            log_stats_csv: false,
+            csv_columns: vec![CsvColumn::Timestamp, CsvColumn::Fps, CsvColumn::OnePercentLow],
+            summary_hotkey: Hotkey { modifiers: MOD_CONTROL | MOD_ALT, vk: 0x53 }, // Ctrl+Alt+S
+            settings_hotkey: Hotkey { modifiers: MOD_CONTROL | MOD_ALT, vk: 0x4F }, // Ctrl+Alt+O
+            exit_hotkey: Hotkey { modifiers: MOD_CONTROL | MOD_ALT, vk: 0x51 }, // Ctrl+Alt+Q
+            toggle_hotkey: Hotkey { modifiers: MOD_ALT, vk: 0x46 }, // Alt+F
+            position_hotkey: Hotkey { modifiers: MOD_CONTROL | MOD_ALT, vk: 0x50 }, // Ctrl+Alt+P
+            benchmark_hotkey: Hotkey { modifiers: 0, vk: 0x79 }, // F10
+            show_cap_stability: false,
+            show_cap_indicator: false,
+            show_instant_fps: false,
+            show_refresh_warning: false,
+            min_display_fps: 0,
+            multi_fullscreen_policy: FullscreenPolicy::Foreground,
+            detect_borderless: false,
+            overlay_mode: OverlayMode::FullscreenOnly,
+            hide_delay_ms: 0,
+            target_override: None,
+            process_blacklist: Vec::new(),
+            process_whitelist: Vec::new(),
+            low_method: LowMethod::FrameCount,
+            fps_method: FpsMethod::FrameTimeAverage,
+            smoothing: Smoothing::None,
+            stats_smoothing: Smoothing::Ema { alpha: 0.5 },
+            fps_smoothing: false,
+            ema_alpha: None,
+            alert_fps_below: None,
+            alert_usage_above: None,
+            alert_stutters: None,
+            show_present_vs_display: false,
+            show_gpu_busy: false,
+            avg_window_ms: 1000,
+            sample_buffer_size: 2000,
+            enable_ipc: false,
+            overlay_template: String::new(),
+            detect_stall_ms: 0,
+            idle_capture_timeout_secs: 30,
+            save_raw_presentmon: false,
+            capture_watchdog_ms: 5000,
+            number_locale: NumberLocale::Invariant,
+            compact_numbers: false,
+            fps_decimals: 0,
+            usage_decimals: 0,
+            presentmon_path: None,
+            force_topmost: false,
+            capture_backend: CaptureBackendKind::PresentMon,
+            log_level: LogLevel::Warn,
+            language: Lang::detect(),
+            gui_theme: GuiTheme::Dark,
         }
     }
 }
 
+/// Current settings schema version, bump it when a field changes meaning
+/// and add the conversion to `Settings::migrate`
+pub const SETTINGS_VERSION: u32 = 3;
+
+pub const DEFAULT_FONT: &str = "Segoe UI";
+/// Fixed-pitch font used with `monospace_digits`
+pub const MONOSPACE_FONT: &str = "Consolas";
+/// Fonts offered in the settings window, all shipped with Windows
+pub const FONT_CHOICES: [&str; 5] = [DEFAULT_FONT, "Arial", "Tahoma", "Verdana", MONOSPACE_FONT];
+/// Font weights offered in the settings window: light, regular, semibold, bold
+pub const FONT_WEIGHTS: [u32; 4] = [300, 400, 600, 700];
+/// Heaviest `font_weight` (FW_HEAVY)
+pub const FONT_WEIGHT_MAX: u32 = 900;
+
+/// How far in from the edges `OverlayPosition::proportional` places the overlay (%)
+pub const POSITION_INSET_PCT: f32 = 2.0;
+
+/// Largest `overlay_margin`
+pub const OVERLAY_MARGIN_MAX: i32 = 200;
+
+/// Monitor height `auto_scale` sizes are designed for
+pub const AUTO_SCALE_BASE_HEIGHT: i32 = 1080;
+/// Allowed range of the `auto_scale` factor
+pub const AUTO_SCALE_MIN: f32 = 0.75;
+pub const AUTO_SCALE_MAX: f32 = 2.0;
+
+/// Allowed range of `overlay_update_hz`
+pub const OVERLAY_UPDATE_HZ_MIN: u32 = 5;
+pub const OVERLAY_UPDATE_HZ_MAX: u32 = 60;
+
+/// Allowed range of `tray_double_click_ms`
+pub const TRAY_DOUBLE_CLICK_MIN_MS: u64 = 100;
+pub const TRAY_DOUBLE_CLICK_MAX_MS: u64 = 2000;
+
+/// Allowed range of `sample_buffer_size`
+pub const SAMPLE_BUFFER_MIN: usize = 500;
+pub const SAMPLE_BUFFER_MAX: usize = 20_000;
+
+/// Bounds of a Custom overlay size: (min, max) width, height and font sizes
+pub const CUSTOM_SIZE_WIDTH: (i32, i32) = (40, 1000);
+pub const CUSTOM_SIZE_HEIGHT: (i32, i32) = (20, 600);
+pub const CUSTOM_SIZE_FONT: (i32, i32) = (6, 200);
+
 impl Settings {
-    /// Get the config file path
-    fn config_path() -> PathBuf {
+    /// Get the EasyFPS config directory
+    pub fn config_dir() -> PathBuf {
         dirs::config_dir()
             .unwrap_or_else(|| PathBuf::from("."))
             .join("EasyFPS")
-            .join("settings.json")
+    }
+
+    /// Get the config file path
+    fn config_path() -> PathBuf {
+        Self::config_dir().join("settings.json")
     }
     
-    /// Load settings from disk, or return defaults
+    /// Load settings from disk, or return defaults.
+    /// A file that can't be parsed is copied to settings.json.bak first, so the user's
+    /// config isn't lost when the defaults are saved over it.
     pub fn load() -> Self {
-        let path = Self::config_path();
-        
-        if path.exists() {
-            match fs::read_to_string(&path) {
-                Ok(content) => {
-                    match serde_json::from_str(&content) {
-                        Ok(settings) => return settings,
-                        Err(_) => {}
-                    }
+        match Self::try_load() {
+            Ok(settings) => settings,
+            Err(e) => {
+                let path = Self::config_path();
+                if path.exists() {
+                    crate::log::warn(&format!("{}, using defaults", e));
+                    let _ = fs::copy(&path, path.with_extension("json.bak"));
                 }
-                Err(_) => {}
+                Self::default()
             }
         }
-        
-        Self::default()
+    }
+
+    /// Load settings from disk, failing if the file is missing or invalid
+    pub fn try_load() -> Result<Self, String> {
+        let content = fs::read_to_string(Self::config_path())
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+        Self::parse(&content, true)
+    }
+
+    /// Read settings from any JSON file in the settings.json format. Unlike loading
+    /// settings.json, a file with invalid fields or no known setting at all is rejected.
+    pub fn import_from(path: &Path) -> Result<Self, String> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read settings file: {}", e))?;
+        let known = serde_json::to_value(Self::default()).map_err(|e| e.to_string())?;
+        let has_known_key = match (serde_json::from_str(&content), known) {
+            (Ok(serde_json::Value::Object(fields)), serde_json::Value::Object(known)) => {
+                fields.keys().any(|key| known.contains_key(key))
+            }
+            _ => false,
+        };
+        if !has_known_key {
+            return Err("Not an EasyFPS settings file".to_string());
+        }
+        Self::parse(&content, false)
+    }
+
+    /// Parse, migrate and validate a settings file. With `lenient`, fields that fail to
+    /// parse fall back to their default instead of failing the whole file.
+    fn parse(content: &str, lenient: bool) -> Result<Self, String> {
+        let mut settings: Settings = match serde_json::from_str(content) {
+            Ok(settings) => settings,
+            // A field of the wrong type (or an unknown variant) shouldn't cost the whole file
+            Err(e) if lenient => {
+                Self::parse_lenient(content).ok_or_else(|| format!("Failed to parse settings file: {}", e))?
+            }
+            Err(e) => return Err(format!("Failed to parse settings file: {}", e)),
+        };
+        settings.migrate();
+        settings.validate_and_clamp();
+        Ok(settings)
+    }
+
+    /// Parse a settings file one field at a time: fields that don't deserialize keep their
+    /// default, the rest is kept. None if the file isn't a JSON object at all.
+    fn parse_lenient(content: &str) -> Option<Self> {
+        let serde_json::Value::Object(fields) = serde_json::from_str(content).ok()? else {
+            return None;
+        };
+        let serde_json::Value::Object(mut merged) = serde_json::to_value(Self::default()).ok()? else {
+            return None;
+        };
+        for (key, value) in fields {
+            let previous = merged.insert(key.clone(), value);
+            if serde_json::from_value::<Self>(serde_json::Value::Object(merged.clone())).is_err() {
+                crate::log::warn(&format!("Setting \"{}\" is invalid, using its default", key));
+                match previous {
+                    Some(previous) => merged.insert(key, previous),
+                    None => merged.remove(&key),
+                };
+            }
+        }
+        serde_json::from_value(serde_json::Value::Object(merged)).ok()
+    }
+
+    /// Bring values a hand-edited (or older) file may have out of range back into range,
+    /// and fix combinations that can't work, instead of failing on them later
+    pub fn validate_and_clamp(&mut self) {
+        let defaults = Self::default();
+
+        self.sample_buffer_size = self.sample_buffer_size.clamp(SAMPLE_BUFFER_MIN, SAMPLE_BUFFER_MAX);
+        self.overlay_margin = self.overlay_margin.clamp(0, OVERLAY_MARGIN_MAX);
+        self.overlay_update_hz = self.overlay_update_hz.clamp(OVERLAY_UPDATE_HZ_MIN, OVERLAY_UPDATE_HZ_MAX);
+        self.tray_double_click_ms = self
+            .tray_double_click_ms
+            .clamp(TRAY_DOUBLE_CLICK_MIN_MS, TRAY_DOUBLE_CLICK_MAX_MS);
+        self.overlay_opacity = self.overlay_opacity.clamp(OPACITY_PRESETS[0], 100);
+        if self.font_family.trim().is_empty() {
+            self.font_family = DEFAULT_FONT.to_string();
+        }
+        self.font_weight = self.font_weight.clamp(100, FONT_WEIGHT_MAX);
+        if let OverlaySize::Custom { width, height, font_large, font_small } = self.size {
+            self.size = OverlaySize::Custom {
+                width: width.clamp(CUSTOM_SIZE_WIDTH.0, CUSTOM_SIZE_WIDTH.1),
+                height: height.clamp(CUSTOM_SIZE_HEIGHT.0, CUSTOM_SIZE_HEIGHT.1),
+                font_large: font_large.clamp(CUSTOM_SIZE_FONT.0, CUSTOM_SIZE_FONT.1),
+                font_small: font_small.clamp(CUSTOM_SIZE_FONT.0, CUSTOM_SIZE_FONT.1),
+            };
+        }
+        self.stat_order = normalize_stat_order(&self.stat_order);
+        self.fps_decimals = self.fps_decimals.min(1);
+        self.usage_decimals = self.usage_decimals.min(1);
+
+        // Percentages, with the amber threshold at or below the red one
+        self.usage_crit_pct = self.usage_crit_pct.min(100);
+        self.usage_warn_pct = self.usage_warn_pct.min(self.usage_crit_pct);
+        self.alert_only_usage_above = self.alert_only_usage_above.min(100);
+        if let FpsColorMode::Threshold { low, high } = self.fps_color_mode {
+            self.fps_color_mode = FpsColorMode::Threshold { low: low.min(high), high: low.max(high) };
+        }
+        if self.alert_below_fps == Some(0) {
+            self.alert_below_fps = None;
+        }
+        self.alert_repeat_secs = self.alert_repeat_secs.max(1);
+
+        if let OverlayPosition::Percent { x_pct, y_pct } = self.position {
+            let pct = |v: f32| if v.is_finite() { v.clamp(0.0, 100.0) } else { POSITION_INSET_PCT };
+            self.position = OverlayPosition::Percent { x_pct: pct(x_pct), y_pct: pct(y_pct) };
+        }
+        for (smoothing, max_alpha) in [(&mut self.smoothing, 0.5), (&mut self.stats_smoothing, 1.0)] {
+            if let Smoothing::Ema { alpha } = *smoothing {
+                *smoothing = if alpha.is_finite() {
+                    Smoothing::Ema { alpha: alpha.clamp(0.05, max_alpha) }
+                } else {
+                    Smoothing::None
+                };
+            }
+        }
+        if matches!(&self.target_override, Some(TargetSpec::Name(name)) if name.trim().is_empty()) {
+            self.target_override = None;
+        }
+
+        // RegisterHotKey needs a virtual-key code (1-254) and known MOD_* flags
+        let hotkeys = [
+            (&mut self.summary_hotkey, defaults.summary_hotkey),
+            (&mut self.settings_hotkey, defaults.settings_hotkey),
+            (&mut self.exit_hotkey, defaults.exit_hotkey),
+            (&mut self.toggle_hotkey, defaults.toggle_hotkey),
+            (&mut self.position_hotkey, defaults.position_hotkey),
+            (&mut self.benchmark_hotkey, defaults.benchmark_hotkey),
+        ];
+        for (hotkey, default) in hotkeys {
+            if !(0x01..=0xFE).contains(&hotkey.vk) {
+                crate::log::warn(&format!("Hotkey key code 0x{:X} is invalid, using {}", hotkey.vk, default));
+                *hotkey = default;
+            }
+            hotkey.modifiers &= MOD_ALT | MOD_CONTROL | MOD_SHIFT | MOD_WIN;
+        }
+    }
+
+    /// Font the overlay is drawn with
+    pub fn overlay_font(&self) -> &str {
+        if self.monospace_digits {
+            MONOSPACE_FONT
+        } else {
+            &self.font_family
+        }
+    }
+
+    /// Whether a process may get the overlay under the black/whitelist. A process whose
+    /// name can't be read only passes without a whitelist.
+    pub fn allows_process(&self, name: Option<&str>) -> bool {
+        let listed = |list: &[String]| name.is_some_and(|name| list.iter().any(|entry| same_process(entry, name)));
+        !listed(&self.process_blacklist) && (self.process_whitelist.is_empty() || listed(&self.process_whitelist))
+    }
+
+    /// Bring settings written by an older build up to SETTINGS_VERSION.
+    /// Fields added since are already filled with their defaults by serde.
+    fn migrate(&mut self) {
+        if self.version > SETTINGS_VERSION {
+            // Written by a newer build: use what we understand, keep its version so
+            // saving doesn't claim the file is older than it is
+            crate::log::warn(&format!(
+                "Settings file version {} is newer than supported ({}), unknown options are ignored",
+                self.version, SETTINGS_VERSION
+            ));
+            return;
+        }
+        // 0 -> 1: versioning introduced, nothing to convert
+        // 1 -> 2: fps_smoothing + ema_alpha became `smoothing`
+        if self.version < 2 && self.fps_smoothing {
+            self.smoothing = Smoothing::Ema { alpha: self.ema_alpha.unwrap_or(0.2) };
+        }
+        // 2 -> 3: the alert-only thresholds got an alert_only_ prefix
+        if self.version < 3 {
+            if let Some(fps) = self.alert_fps_below.take() {
+                self.alert_only_fps_below = fps;
+            }
+            if let Some(pct) = self.alert_usage_above.take() {
+                self.alert_only_usage_above = pct;
+            }
+            if let Some(stutters) = self.alert_stutters.take() {
+                self.alert_only_stutters = stutters;
+            }
+        }
+        self.version = SETTINGS_VERSION;
+    }
+
+    /// Write the settings to a file of the user's choice (backups, sharing)
+    pub fn export_to(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+        fs::write(path, content).map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    /// Last modification time of the settings file
+    fn modified() -> Option<SystemTime> {
+        fs::metadata(Self::config_path()).and_then(|m| m.modified()).ok()
     }
     
     /// Save settings to disk
@@ -190,3 +1385,161 @@ impl Settings {
         Ok(())
     }
 }
+
+/// Per-game settings (profiles.json), keyed by lowercase executable name ("game.exe").
+/// A game with a profile uses it instead of the global settings for what's measured and
+/// shown (overlay, monitor readings, capture, alerts, CSV log).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Profiles {
+    profiles: HashMap<String, Settings>,
+}
+
+impl Profiles {
+    fn path() -> PathBuf {
+        Settings::config_dir().join("profiles.json")
+    }
+
+    /// Load profiles from disk, or none if the file is missing or invalid
+    pub fn load() -> Self {
+        let mut loaded: Self = fs::read_to_string(Self::path())
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        // Profiles are saved settings too, renamed fields included
+        for settings in loaded.profiles.values_mut() {
+            settings.migrate();
+        }
+        loaded
+    }
+
+    /// Save profiles to disk
+    pub fn save(&self) -> Result<(), String> {
+        let path = Self::path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create config directory: {}", e))?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| format!("Failed to serialize profiles: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("Failed to write profiles file: {}", e))
+    }
+
+    /// The profile for an executable name, if any
+    pub fn get(&self, exe_name: &str) -> Option<&Settings> {
+        self.profiles.get(&exe_name.to_lowercase())
+    }
+
+    /// Add or replace the profile for an executable name
+    pub fn insert(&mut self, exe_name: &str, settings: Settings) {
+        self.profiles.insert(exe_name.to_lowercase(), settings);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_and_missing_values_are_clamped() {
+        let settings = Settings::parse(
+            r#"{
+                "overlay_opacity": 5,
+                "overlay_margin": -20,
+                "usage_warn_pct": 95,
+                "usage_crit_pct": 250,
+                "smoothing": { "Ema": { "alpha": 3.0 } },
+                "overlay_update_hz": "fast"
+            }"#,
+            true,
+        )
+        .unwrap();
+        let defaults = Settings::default();
+
+        assert_eq!(settings.overlay_opacity, OPACITY_PRESETS[0]);
+        assert_eq!(settings.overlay_margin, 0);
+        assert_eq!(settings.usage_crit_pct, 100);
+        assert_eq!(settings.usage_warn_pct, 95);
+        assert_eq!(settings.smoothing, Smoothing::Ema { alpha: 0.5 });
+        // Wrong type and missing: both the default
+        assert_eq!(settings.overlay_update_hz, defaults.overlay_update_hz);
+        assert_eq!(settings.size, defaults.size);
+        assert_eq!(settings.version, SETTINGS_VERSION);
+    }
+
+    #[test]
+    fn strict_parse_rejects_invalid_fields() {
+        assert!(Settings::parse(r#"{ "overlay_update_hz": "fast" }"#, false).is_err());
+    }
+
+    #[test]
+    fn format_compact_step_boundaries() {
+        let locale = NumberLocale::Invariant;
+        let compact = |value, unit| locale.format_compact(value, unit);
+
+        assert_eq!(compact(999.0, Unit::Count), "999");
+        assert_eq!(compact(1000.0, Unit::Count), "1K");
+        assert_eq!(compact(1023.0, Unit::Count), "1K");
+        assert_eq!(compact(1024.0, Unit::Count), "1K");
+
+        assert_eq!(compact(999.0, Unit::Bytes), "999B");
+        assert_eq!(compact(1000.0, Unit::Bytes), "1000B");
+        assert_eq!(compact(1023.0, Unit::Bytes), "1023B");
+        assert_eq!(compact(1024.0, Unit::Bytes), "1K");
+
+        assert_eq!(compact(999.0, Unit::Percent), "999%");
+        assert_eq!(compact(1000.0, Unit::Percent), "1000%");
+        assert_eq!(compact(1023.0, Unit::Percent), "1023%");
+        assert_eq!(compact(1024.0, Unit::Percent), "1024%");
+
+        // Decimals follow the locale
+        assert_eq!(NumberLocale::European.format_compact(1500.0, Unit::Count), "1,5K");
+    }
+
+    #[test]
+    fn v0_file_migrates_without_losing_settings() {
+        // Written before versioning: no "version", the old smoothing flags, most options missing
+        let settings = Settings::parse(
+            r#"{
+                "position": "TopLeft",
+                "size": "Large",
+                "show_cpu_usage": true,
+                "fps_smoothing": true,
+                "ema_alpha": 0.3
+            }"#,
+            true,
+        )
+        .unwrap();
+        let defaults = Settings::default();
+
+        assert_eq!(settings.version, SETTINGS_VERSION);
+        assert_eq!(settings.position, OverlayPosition::TopLeft);
+        assert_eq!(settings.size, OverlaySize::Large);
+        assert!(settings.show_cpu_usage);
+        assert_eq!(settings.smoothing, Smoothing::Ema { alpha: 0.3 });
+        assert_eq!(settings.overlay_margin, defaults.overlay_margin);
+        assert_eq!(settings.layout, defaults.layout);
+    }
+
+    #[test]
+    fn v2_alert_thresholds_are_renamed() {
+        let settings = Settings::parse(
+            r#"{
+                "version": 2,
+                "alert_fps_below": 45,
+                "alert_usage_above": 80,
+                "alert_stutters": 5,
+                "alert_below_fps": 30
+            }"#,
+            false,
+        )
+        .unwrap();
+
+        assert_eq!(settings.alert_only_fps_below, 45);
+        assert_eq!(settings.alert_only_usage_above, 80);
+        assert_eq!(settings.alert_only_stutters, 5);
+        assert_eq!(settings.alert_below_fps, Some(30));
+        let saved = serde_json::to_value(&settings).unwrap();
+        assert!(saved.get("alert_fps_below").is_none());
+    }
+}
@@ -0,0 +1,229 @@
+use crate::fps_capture::FrameStats;
+use crate::settings::NumberLocale;
+use parking_lot::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateFontW, CreatePen, CreateSolidBrush, DeleteObject, EndPaint, InvalidateRect,
+    RoundRect, SelectObject, SetBkMode, SetTextColor, TextOutW, HBRUSH, PAINTSTRUCT, PS_SOLID,
+    TRANSPARENT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetSystemMetrics, PeekMessageW,
+    PostQuitMessage, RegisterClassW, SetLayeredWindowAttributes, SetWindowPos, ShowWindow,
+    TranslateMessage, HWND_TOPMOST, LWA_ALPHA, MSG, PM_REMOVE, SM_CXSCREEN, SM_CYSCREEN,
+    SWP_NOACTIVATE, SW_HIDE, SW_SHOWNOACTIVATE, WM_DESTROY, WM_PAINT, WNDCLASSW, WS_EX_LAYERED,
+    WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+};
+
+const PANEL_WIDTH: i32 = 260;
+const ROW_HEIGHT: i32 = 24;
+const HEADER_HEIGHT: i32 = 36;
+const PADDING: i32 = 12;
+const BACKGROUND_COLOR: u32 = 0x1A1A1A;
+const BORDER_RADIUS: i32 = 10;
+
+static SUMMARY_HWND: AtomicIsize = AtomicIsize::new(0);
+static SUMMARY_VISIBLE: AtomicBool = AtomicBool::new(false);
+// Label/value rows currently displayed
+static SUMMARY_ROWS: once_cell::sync::Lazy<Mutex<Vec<(String, String)>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(Vec::new()));
+
+pub fn init() -> Result<(), String> {
+    std::thread::spawn(move || {
+        if let Err(e) = run_summary_window() {
+            crate::log::error(&format!("Summary window error: {}", e));
+        }
+    });
+    std::thread::sleep(std::time::Duration::from_millis(100));
+    Ok(())
+}
+
+pub fn is_visible() -> bool {
+    SUMMARY_VISIBLE.load(Ordering::SeqCst)
+}
+
+/// Show (or refresh) the summary panel centered on the primary screen, without taking focus.
+/// Stats come from the rolling sample buffer, except min/max which span the session.
+pub fn show(stats: &FrameStats, playtime_secs: Option<u64>, locale: NumberLocale) {
+    let n = |value: f64, decimals: usize| locale.format(value, decimals);
+    let mut rows = vec![
+        ("Avg FPS".to_string(), n(stats.avg_fps, 0)),
+        ("Session min / max".to_string(), format!("{} / {}", n(stats.min_fps, 0), n(stats.max_fps, 0))),
+        ("1% Low".to_string(), n(stats.one_percent_low, 0)),
+        ("0.1% Low".to_string(), n(stats.point_one_percent_low, 0)),
+        ("Median frametime".to_string(), format!("{} ms", n(stats.p50_ms, 1))),
+        ("99th pct frametime".to_string(), format!("{} ms", n(stats.p99_ms, 1))),
+        ("Jitter".to_string(), format!("{} ms", n(stats.jitter_ms, 1))),
+        ("Frames".to_string(), n(stats.frames as f64, 0)),
+    ];
+    if let Some(secs) = playtime_secs {
+        rows.push(("Total playtime".to_string(), crate::playtime::format_playtime(secs)));
+    }
+    let row_count = rows.len() as i32;
+    *SUMMARY_ROWS.lock() = rows;
+
+    let hwnd_val = SUMMARY_HWND.load(Ordering::SeqCst);
+    if hwnd_val == 0 {
+        return;
+    }
+    let hwnd = HWND(hwnd_val);
+    let height = HEADER_HEIGHT + row_count * ROW_HEIGHT + PADDING;
+
+    unsafe {
+        let x = (GetSystemMetrics(SM_CXSCREEN) - PANEL_WIDTH) / 2;
+        let y = (GetSystemMetrics(SM_CYSCREEN) - height) / 2;
+        let _ = SetWindowPos(hwnd, HWND_TOPMOST, x, y, PANEL_WIDTH, height, SWP_NOACTIVATE);
+        if !SUMMARY_VISIBLE.swap(true, Ordering::SeqCst) {
+            let _ = ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+        }
+        let _ = InvalidateRect(hwnd, None, true);
+    }
+}
+
+pub fn hide() {
+    if SUMMARY_VISIBLE.swap(false, Ordering::SeqCst) {
+        let hwnd_val = SUMMARY_HWND.load(Ordering::SeqCst);
+        if hwnd_val != 0 {
+            unsafe {
+                let _ = ShowWindow(HWND(hwnd_val), SW_HIDE);
+            }
+        }
+    }
+}
+
+unsafe extern "system" fn summary_wndproc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    match msg {
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let rows = SUMMARY_ROWS.lock();
+            let height = HEADER_HEIGHT + rows.len() as i32 * ROW_HEIGHT + PADDING;
+
+            // Background
+            let brush = CreateSolidBrush(COLORREF(BACKGROUND_COLOR));
+            let pen = CreatePen(PS_SOLID, 1, COLORREF(BACKGROUND_COLOR));
+            let old_brush = SelectObject(hdc, brush);
+            let old_pen = SelectObject(hdc, pen);
+            let _ = RoundRect(hdc, 0, 0, PANEL_WIDTH, height, BORDER_RADIUS, BORDER_RADIUS);
+            SelectObject(hdc, old_brush);
+            SelectObject(hdc, old_pen);
+            let _ = DeleteObject(brush);
+            let _ = DeleteObject(pen);
+
+            let _ = SetBkMode(hdc, TRANSPARENT);
+
+            let title_font = CreateFontW(
+                20, 0, 0, 0, 700, 0, 0, 0, 0, 0, 0, 0, 0,
+                windows::core::w!("Segoe UI"),
+            );
+            let row_font = CreateFontW(
+                17, 0, 0, 0, 400, 0, 0, 0, 0, 0, 0, 0, 0,
+                windows::core::w!("Segoe UI"),
+            );
+
+            // Title
+            let old_font = SelectObject(hdc, title_font);
+            SetTextColor(hdc, COLORREF(0xFFFFFF));
+            let title: Vec<u16> = "Recent frames summary".encode_utf16().collect();
+            let _ = TextOutW(hdc, PADDING, 8, &title);
+
+            // Rows: gray label on the left, white value right-aligned
+            SelectObject(hdc, row_font);
+            for (i, (label, value)) in rows.iter().enumerate() {
+                let y = HEADER_HEIGHT + i as i32 * ROW_HEIGHT;
+
+                SetTextColor(hdc, COLORREF(0xAAAAAA));
+                let label_wide: Vec<u16> = label.encode_utf16().collect();
+                let _ = TextOutW(hdc, PADDING, y, &label_wide);
+
+                SetTextColor(hdc, COLORREF(0xFFFFFF));
+                let value_wide: Vec<u16> = value.encode_utf16().collect();
+                let mut size = windows::Win32::Foundation::SIZE::default();
+                let _ = windows::Win32::Graphics::Gdi::GetTextExtentPoint32W(hdc, &value_wide, &mut size);
+                let _ = TextOutW(hdc, PANEL_WIDTH - PADDING - size.cx, y, &value_wide);
+            }
+
+            SelectObject(hdc, old_font);
+            let _ = DeleteObject(title_font);
+            let _ = DeleteObject(row_font);
+            drop(rows);
+
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+fn run_summary_window() -> Result<(), String> {
+    unsafe {
+        let class_name = windows::core::w!("EasyFPS_Summary");
+
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(summary_wndproc),
+            lpszClassName: class_name,
+            hbrBackground: HBRUSH(0),
+            ..Default::default()
+        };
+
+        RegisterClassW(&wc);
+
+        // Same non-activating, click-through style as the overlay so it never steals focus
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_TRANSPARENT,
+            class_name,
+            windows::core::w!(""),
+            WS_POPUP,
+            0, 0, PANEL_WIDTH, 100,
+            None, None, None, None,
+        );
+
+        if hwnd.0 == 0 {
+            return Err("CreateWindowExW failed".to_string());
+        }
+
+        SUMMARY_HWND.store(hwnd.0, Ordering::SeqCst);
+
+        SetLayeredWindowAttributes(hwnd, None, 235, LWA_ALPHA)
+            .map_err(|e| format!("SetLayeredWindowAttributes failed: {}", e))?;
+
+        let mut msg = MSG::default();
+        loop {
+            while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+                if msg.message == windows::Win32::UI::WindowsAndMessaging::WM_QUIT {
+                    return Ok(());
+                }
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+            std::thread::sleep(std::time::Duration::from_millis(16));
+        }
+    }
+}
+
+pub fn shutdown() {
+    let hwnd_val = SUMMARY_HWND.load(Ordering::SeqCst);
+    if hwnd_val != 0 {
+        // DestroyWindow only works from the owning thread, so ask the window to close itself
+        unsafe {
+            let _ = windows::Win32::UI::WindowsAndMessaging::PostMessageW(
+                HWND(hwnd_val),
+                windows::Win32::UI::WindowsAndMessaging::WM_CLOSE,
+                WPARAM(0),
+                LPARAM(0),
+            );
+        }
+        SUMMARY_HWND.store(0, Ordering::SeqCst);
+        SUMMARY_VISIBLE.store(false, Ordering::SeqCst);
+    }
+}
@@ -0,0 +1,113 @@
+//! User-defined overlay text, e.g. "{fps} fps | {low} low | {cpu}% cpu".
+//! A template that fails to parse is ignored and the default layout is used.
+
+/// Values a template token can be replaced with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    Fps,
+    Low,
+    Cpu,
+    Gpu,
+    Game,
+    Time,
+    Res,
+}
+
+impl Token {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "fps" => Some(Token::Fps),
+            "low" => Some(Token::Low),
+            "cpu" => Some(Token::Cpu),
+            "gpu" => Some(Token::Gpu),
+            "game" => Some(Token::Game),
+            "time" => Some(Token::Time),
+            "res" => Some(Token::Res),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Segment {
+    Text(String),
+    Token(Token),
+}
+
+/// A parsed template: one segment list per overlay line ("\n" starts a new line)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template {
+    lines: Vec<Vec<Segment>>,
+}
+
+/// Current values substituted into the template, already formatted
+#[derive(Debug, Clone, Default)]
+pub struct TemplateValues {
+    pub fps: String,
+    pub low: String,
+    pub cpu: String,
+    pub gpu: String,
+    pub game: String,
+    pub time: String,
+    pub res: String,
+}
+
+impl Template {
+    /// Parse a template. Unknown tokens and unbalanced braces are errors.
+    pub fn parse(template: &str) -> Result<Self, String> {
+        let mut lines = Vec::new();
+        for line in template.split('\n') {
+            let mut segments = Vec::new();
+            let mut rest = line;
+            while let Some(open) = rest.find(['{', '}']) {
+                if rest[open..].starts_with('}') {
+                    return Err(format!("'}}' senza '{{' in \"{}\"", line));
+                }
+                if open > 0 {
+                    segments.push(Segment::Text(rest[..open].to_string()));
+                }
+                let after = &rest[open + 1..];
+                let close = after
+                    .find('}')
+                    .ok_or_else(|| format!("'{{' non chiusa in \"{}\"", line))?;
+                let name = after[..close].trim();
+                let token = Token::from_name(name)
+                    .ok_or_else(|| format!("Segnaposto sconosciuto: {{{}}}", name))?;
+                segments.push(Segment::Token(token));
+                rest = &after[close + 1..];
+            }
+            if !rest.is_empty() {
+                segments.push(Segment::Text(rest.to_string()));
+            }
+            lines.push(segments);
+        }
+        Ok(Self { lines })
+    }
+
+    /// Whether `token` appears anywhere in the template
+    pub fn uses(&self, token: Token) -> bool {
+        self.lines.iter().flatten().any(|segment| *segment == Segment::Token(token))
+    }
+
+    /// Substitute the values, returning one string per overlay line
+    pub fn render(&self, values: &TemplateValues) -> Vec<String> {
+        self.lines
+            .iter()
+            .map(|segments| {
+                segments
+                    .iter()
+                    .map(|segment| match segment {
+                        Segment::Text(text) => text.as_str(),
+                        Segment::Token(Token::Fps) => &values.fps,
+                        Segment::Token(Token::Low) => &values.low,
+                        Segment::Token(Token::Cpu) => &values.cpu,
+                        Segment::Token(Token::Gpu) => &values.gpu,
+                        Segment::Token(Token::Game) => &values.game,
+                        Segment::Token(Token::Time) => &values.time,
+                        Segment::Token(Token::Res) => &values.res,
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
@@ -1,21 +1,56 @@
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem},
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     TrayIcon, TrayIconBuilder, TrayIconEvent,
     Icon, MouseButton, MouseButtonState,
 };
+use crate::i18n::{tr, Text};
+use crate::settings::TrayClickAction;
+use std::cell::RefCell;
 use std::time::Instant;
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 pub const MENU_SETTINGS: &str = "settings";
+pub const MENU_BENCHMARK: &str = "benchmark";
+pub const MENU_LAST_BENCHMARK: &str = "last_benchmark";
+pub const MENU_RESET_STATS: &str = "reset_stats";
+pub const MENU_LOCK_POSITION: &str = "lock_position";
+pub const MENU_TOGGLE_OVERLAY: &str = "toggle_overlay";
+pub const MENU_PAUSE_CAPTURE: &str = "pause_capture";
+pub const MENU_EXCLUDE_GAME: &str = "exclude_game";
+pub const MENU_PREVIEW_OVERLAY: &str = "preview_overlay";
 pub const MENU_EXIT: &str = "exit";
+/// "Monitor process" entries: automatic detection, or "target:<process name>"
+pub const MENU_TARGET_AUTO: &str = "target_auto";
+pub const MENU_TARGET_PREFIX: &str = "target:";
 
-static mut TRAY_ICON: Option<TrayIcon> = None;
+thread_local! {
+    // The icon belongs to the main thread, which created it and runs its message loop
+    static TRAY_ICON: RefCell<Option<TrayIcon>> = const { RefCell::new(None) };
+    // Tooltip currently shown, so an unchanged one isn't set again
+    static TOOLTIP: RefCell<String> = const { RefCell::new(String::new()) };
+    // Same for the icon color
+    static ICON_COLOR: std::cell::Cell<(u8, u8, u8)> = const { std::cell::Cell::new(ICON_IDLE) };
+    // Whether a left click opens the menu (tray-icon's default), off with a single-click action
+    static MENU_ON_LEFT_CLICK: std::cell::Cell<bool> = const { std::cell::Cell::new(true) };
+    // Windowed processes offered under "Monitor process", and the one picked (if any)
+    static TARGET_PROCESSES: RefCell<(Vec<String>, Option<String>)> = const { RefCell::new((Vec::new(), None)) };
+}
+
+/// Icon colors: FPS at or above the high threshold, in between, below the low one, no game
+pub const ICON_GOOD: (u8, u8, u8) = (57, 255, 20);
+pub const ICON_FAIR: (u8, u8, u8) = (255, 220, 0);
+pub const ICON_POOR: (u8, u8, u8) = (255, 60, 60);
+pub const ICON_IDLE: (u8, u8, u8) = (128, 128, 128);
+// FPS capture stopped from the menu, shown as "Resume capture"
+static CAPTURE_PAUSED: AtomicBool = AtomicBool::new(false);
 
-// Store last click time as u64 millis since app start
+// Time of a left click that may still become a double click, as u64 millis since app start
+// (0 = none pending)
 static LAST_CLICK_MS: AtomicU64 = AtomicU64::new(0);
 static APP_START: once_cell::sync::Lazy<Instant> = once_cell::sync::Lazy::new(|| Instant::now());
 
-fn create_green_icon() -> Icon {
+/// Round icon of `(r, g, b)` with a black "F"
+fn create_icon((r, g, b): (u8, u8, u8)) -> Icon {
     const SIZE: usize = 32;
     let mut rgba = vec![0u8; SIZE * SIZE * 4];
     
@@ -28,9 +63,9 @@ fn create_green_icon() -> Icon {
             let radius = SIZE as f32 / 2.0 - 2.0;
             
             if dist <= radius {
-                rgba[idx] = 57;
-                rgba[idx + 1] = 255;
-                rgba[idx + 2] = 20;
+                rgba[idx] = r;
+                rgba[idx + 1] = g;
+                rgba[idx + 2] = b;
                 rgba[idx + 3] = 255;
                 
                 let in_f = (x >= 10 && x <= 13 && y >= 8 && y <= 24) ||
@@ -50,26 +85,18 @@ fn create_green_icon() -> Icon {
 }
 
 pub fn init() -> Result<(), String> {
-    let menu = Menu::new();
-    
-    let settings_item = MenuItem::with_id(MENU_SETTINGS, "Impostazioni", true, None);
-    let exit_item = MenuItem::with_id(MENU_EXIT, "Esci", true, None);
-    
-    menu.append(&settings_item).map_err(|e| format!("{}", e))?;
-    menu.append(&exit_item).map_err(|e| format!("{}", e))?;
-    
-    let icon = create_green_icon();
+    let menu = build_menu()?;
+    let icon = create_icon(ICON_COLOR.with(|c| c.get()));
     
     let tray_icon = TrayIconBuilder::new()
         .with_menu(Box::new(menu))
-        .with_tooltip("EasyFPS - Doppio click = Impostazioni")
+        .with_tooltip(tr(Text::TrayTooltip))
         .with_icon(icon)
         .build()
         .map_err(|e| format!("{}", e))?;
     
-    unsafe {
-        TRAY_ICON = Some(tray_icon);
-    }
+    TRAY_ICON.with(|t| *t.borrow_mut() = Some(tray_icon));
+    TOOLTIP.with(|t| *t.borrow_mut() = tr(Text::TrayTooltip).to_string());
     
     // Initialize app start time
     let _ = *APP_START;
@@ -77,34 +104,206 @@ pub fn init() -> Result<(), String> {
     Ok(())
 }
 
-pub fn check_menu_event() -> Option<String> {
+/// Menu entries in the current language
+fn build_menu() -> Result<Menu, String> {
+    let menu = Menu::new();
+    
+    let settings_item = MenuItem::with_id(MENU_SETTINGS, tr(Text::MenuSettings), true, None);
+    let benchmark_item = MenuItem::with_id(MENU_BENCHMARK, tr(Text::MenuBenchmark), true, None);
+    // Grayed out until a run has been saved
+    let last_benchmark_item = MenuItem::with_id(
+        MENU_LAST_BENCHMARK,
+        tr(Text::MenuLastBenchmark),
+        crate::last_benchmark::LastBenchmark::load().is_some(),
+        None,
+    );
+    let reset_item = MenuItem::with_id(MENU_RESET_STATS, tr(Text::MenuResetStats), true, None);
+    let overlay_visible = crate::overlay::is_enabled();
+    let overlay_item = CheckMenuItem::with_id(
+        MENU_TOGGLE_OVERLAY,
+        tr(if overlay_visible { Text::MenuOverlayShown } else { Text::MenuOverlayHidden }),
+        true,
+        overlay_visible,
+        None,
+    );
+    let pause_text = if CAPTURE_PAUSED.load(Ordering::SeqCst) { Text::MenuResumeCapture } else { Text::MenuPauseCapture };
+    let pause_item = MenuItem::with_id(MENU_PAUSE_CAPTURE, tr(pause_text), true, None);
+    let preview_item = MenuItem::with_id(MENU_PREVIEW_OVERLAY, tr(Text::MenuPreviewOverlay), true, None);
+    let exclude_item = MenuItem::with_id(MENU_EXCLUDE_GAME, tr(Text::MenuExcludeGame), true, None);
+    let lock_item = CheckMenuItem::with_id(
+        MENU_LOCK_POSITION,
+        tr(Text::MenuLockPosition),
+        true,
+        crate::overlay::is_position_locked(),
+        None,
+    );
+    let target_menu = build_target_menu()?;
+    // Way back for an overlay dragged somewhere it can't be grabbed
+    let reset_position_item = MenuItem::with_id(
+        crate::overlay::MENU_RESET_POSITION,
+        tr(Text::MenuResetOverlayPosition),
+        true,
+        None,
+    );
+    let exit_item = MenuItem::with_id(MENU_EXIT, tr(Text::MenuExit), true, None);
+    
+    menu.append(&settings_item).map_err(|e| format!("{}", e))?;
+    menu.append(&overlay_item).map_err(|e| format!("{}", e))?;
+    menu.append(&preview_item).map_err(|e| format!("{}", e))?;
+    menu.append(&pause_item).map_err(|e| format!("{}", e))?;
+    menu.append(&benchmark_item).map_err(|e| format!("{}", e))?;
+    menu.append(&last_benchmark_item).map_err(|e| format!("{}", e))?;
+    menu.append(&reset_item).map_err(|e| format!("{}", e))?;
+    menu.append(&lock_item).map_err(|e| format!("{}", e))?;
+    menu.append(&reset_position_item).map_err(|e| format!("{}", e))?;
+    menu.append(&exclude_item).map_err(|e| format!("{}", e))?;
+    menu.append(&target_menu).map_err(|e| format!("{}", e))?;
+    menu.append(&exit_item).map_err(|e| format!("{}", e))?;
+    Ok(menu)
+}
+
+/// "Monitor process" submenu: automatic detection, then one entry per windowed process
+fn build_target_menu() -> Result<Submenu, String> {
+    let submenu = Submenu::new(tr(Text::MenuTargetProcess), true);
+    TARGET_PROCESSES.with(|t| {
+        let (names, current) = &*t.borrow();
+        let auto_item = CheckMenuItem::with_id(MENU_TARGET_AUTO, tr(Text::MenuTargetAuto), true, current.is_none(), None);
+        submenu.append(&auto_item).map_err(|e| format!("{}", e))?;
+        submenu.append(&PredefinedMenuItem::separator()).map_err(|e| format!("{}", e))?;
+        // The picked process stays listed while it isn't running
+        let missing = current.as_ref().filter(|c| !names.iter().any(|n| n.eq_ignore_ascii_case(c)));
+        for name in names.iter().chain(missing) {
+            let checked = current.as_ref().is_some_and(|c| c.eq_ignore_ascii_case(name));
+            let item = CheckMenuItem::with_id(format!("{}{}", MENU_TARGET_PREFIX, name), name, true, checked, None);
+            submenu.append(&item).map_err(|e| format!("{}", e))?;
+        }
+        Ok(submenu)
+    })
+}
+
+/// Update the processes under "Monitor process" and the one checked. The menu is only
+/// rebuilt when either changed.
+pub fn set_target_processes(names: Vec<String>, current: Option<String>) {
+    let changed = TARGET_PROCESSES.with(|t| {
+        let mut t = t.borrow_mut();
+        if t.0 == names && t.1 == current {
+            return false;
+        }
+        *t = (names, current);
+        true
+    });
+    if changed {
+        refresh_menu();
+    }
+}
+
+/// Rebuild the menu and tooltip after the language or an entry's state changed
+pub fn refresh_menu() {
+    let menu = match build_menu() {
+        Ok(menu) => menu,
+        Err(e) => {
+            crate::log::warn(&format!("Tray menu rebuild failed: {}", e));
+            return;
+        }
+    };
+    TRAY_ICON.with(|t| {
+        if let Some(tray_icon) = t.borrow().as_ref() {
+            tray_icon.set_menu(Some(Box::new(menu)));
+        }
+    });
+}
+
+/// Replace the tooltip (Windows shows at most 127 characters). Call from the thread that
+/// created the icon; elsewhere, and without a tray, it does nothing.
+pub fn set_tooltip(text: &str) {
+    let text: String = text.chars().take(127).collect();
+    if TOOLTIP.with(|t| *t.borrow() == text) {
+        return;
+    }
+    TRAY_ICON.with(|t| {
+        if let Some(tray_icon) = t.borrow().as_ref() {
+            if tray_icon.set_tooltip(Some(&text)).is_ok() {
+                TOOLTIP.with(|t| *t.borrow_mut() = text);
+            }
+        }
+    });
+}
+
+/// Label the pause entry for the capture state
+pub fn set_capture_paused(paused: bool) {
+    CAPTURE_PAUSED.store(paused, Ordering::SeqCst);
+    refresh_menu();
+}
+
+/// Next menu entry to act on. A double click on the icon opens settings; a single click
+/// runs `single_click` once `double_click_ms` has passed without a second click.
+pub fn check_menu_event(double_click_ms: u64, single_click: TrayClickAction) -> Option<String> {
+    set_menu_on_left_click(single_click == TrayClickAction::None);
+
     // Menu events (right-click menu)
     if let Ok(event) = MenuEvent::receiver().try_recv() {
         return Some(event.id.0.clone());
     }
     
+    let now_ms = APP_START.elapsed().as_millis() as u64;
+
     // Tray icon click events
-    if let Ok(event) = TrayIconEvent::receiver().try_recv() {
-        match event {
-            TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } => {
-                let now_ms = APP_START.elapsed().as_millis() as u64;
-                let last_ms = LAST_CLICK_MS.swap(now_ms, Ordering::SeqCst);
-                
-                // Double click if within 500ms
-                if now_ms.saturating_sub(last_ms) < 500 {
-                    LAST_CLICK_MS.store(0, Ordering::SeqCst); // Reset
-                    return Some(MENU_SETTINGS.to_string());
-                }
-            }
-            _ => {}
+    if let Ok(TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. }) =
+        TrayIconEvent::receiver().try_recv()
+    {
+        let last_ms = LAST_CLICK_MS.swap(now_ms, Ordering::SeqCst);
+
+        // Second click within the interval: a double click, the pending single click is dropped
+        if last_ms != 0 && now_ms.saturating_sub(last_ms) < double_click_ms {
+            LAST_CLICK_MS.store(0, Ordering::SeqCst); // Reset
+            return Some(MENU_SETTINGS.to_string());
         }
+        return None;
     }
-    
+
+    // No second click came in time: it was a single click
+    let last_ms = LAST_CLICK_MS.load(Ordering::SeqCst);
+    if last_ms != 0 && now_ms.saturating_sub(last_ms) >= double_click_ms {
+        LAST_CLICK_MS.store(0, Ordering::SeqCst);
+        return match single_click {
+            TrayClickAction::None => None,
+            TrayClickAction::ToggleOverlay => Some(MENU_TOGGLE_OVERLAY.to_string()),
+            TrayClickAction::OpenSettings => Some(MENU_SETTINGS.to_string()),
+        };
+    }
+
     None
 }
 
-pub fn shutdown() {
-    unsafe {
-        TRAY_ICON = None;
+/// Show the menu on left click too, or keep left click for the single-click action.
+/// Right click always opens the menu.
+fn set_menu_on_left_click(enable: bool) {
+    if MENU_ON_LEFT_CLICK.with(|m| m.get()) == enable {
+        return;
     }
+    TRAY_ICON.with(|t| {
+        if let Some(tray_icon) = t.borrow().as_ref() {
+            tray_icon.set_show_menu_on_left_click(enable);
+            MENU_ON_LEFT_CLICK.with(|m| m.set(enable));
+        }
+    });
+}
+
+/// Recolor the icon. The icon is only rebuilt when the color actually changes.
+pub fn set_icon_color(rgb: (u8, u8, u8)) {
+    if ICON_COLOR.with(|c| c.get()) == rgb {
+        return;
+    }
+    TRAY_ICON.with(|t| {
+        if let Some(tray_icon) = t.borrow().as_ref() {
+            if tray_icon.set_icon(Some(create_icon(rgb))).is_ok() {
+                ICON_COLOR.with(|c| c.set(rgb));
+            }
+        }
+    });
+}
+
+/// Remove the icon (only from the thread that created it)
+pub fn shutdown() {
+    let _ = TRAY_ICON.try_with(|t| t.borrow_mut().take());
 }